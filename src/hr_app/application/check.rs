@@ -0,0 +1,590 @@
+use super::super::domain::department::Department;
+use super::super::domain::person::Person;
+use super::message::{Language, Message};
+use std::collections::{HashMap, HashSet};
+
+// 検査するテキストファイル(person.txt, department.txt, member.txt)の内容
+// 存在しないファイルは空文字列とする
+#[derive(PartialEq, Clone, Debug)]
+pub struct Files {
+    pub person: String,
+    pub department: String,
+    pub member: String,
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum DataFile {
+    Person,
+    Department,
+    Member,
+}
+
+// 見つかった問題
+#[derive(PartialEq, Clone, Debug)]
+pub enum Problem {
+    // 空行、列の数の誤り、IDが数値でないなど行の形式の誤り
+    InvalidLine,
+    // 同じIDで内容も同じ行
+    DuplicateLine(u64),
+    // 同じIDで内容が異なる行
+    DuplicateId(u64),
+    // 削除されていない部署の名前の重複
+    DuplicateDepartmentName(String),
+    // 削除されていない個人の社員番号の重複
+    DuplicateEmployeeNumber(String),
+    // 名前や社員番号などの値の誤り(ドメイン層のエラーメッセージ)
+    InvalidValue(String),
+    // 存在しない個人、部署への所属
+    MissingPerson(u64),
+    MissingDepartment(u64),
+    DuplicateMembership,
+    // 削除済の個人、部署への所属が有効となっている
+    ShouldBeSuspended,
+    // 個人と部署のどちらも削除されていないのに所属が無効となっている
+    ShouldBeValid,
+    // 欠番(完全に削除した場合にも生じるので情報として表示する)
+    IdGap(String),
+}
+
+pub struct Issue {
+    pub file: DataFile,
+    // 行番号(1から)、ファイル全体に関する問題は0
+    pub line: usize,
+    pub problem: Problem,
+    // --repairで安全に修復できるかどうか
+    pub repairable: bool,
+}
+
+impl Issue {
+    fn new(file: DataFile, line: usize, problem: Problem, repairable: bool) -> Issue {
+        return Issue {
+            file: file,
+            line: line,
+            problem: problem,
+            repairable: repairable,
+        };
+    }
+
+    pub fn describe(&self, language: Language) -> String {
+        let text = match &self.problem {
+            Problem::InvalidLine => String::from(language.text(Message::CheckInvalidLine)),
+            Problem::DuplicateLine(id) => {
+                language.format(Message::CheckDuplicateLine, &[&id.to_string()])
+            }
+            Problem::DuplicateId(id) => {
+                language.format(Message::CheckDuplicateId, &[&id.to_string()])
+            }
+            Problem::DuplicateDepartmentName(name) => {
+                language.format(Message::CheckDuplicateDepartmentName, &[name])
+            }
+            Problem::DuplicateEmployeeNumber(number) => {
+                language.format(Message::CheckDuplicateEmployeeNumber, &[number])
+            }
+            Problem::InvalidValue(error) => language.format(
+                Message::CheckInvalidValue,
+                &[&language.translate_error(error)],
+            ),
+            Problem::MissingPerson(id) => {
+                language.format(Message::CheckMissingPerson, &[&id.to_string()])
+            }
+            Problem::MissingDepartment(id) => {
+                language.format(Message::CheckMissingDepartment, &[&id.to_string()])
+            }
+            Problem::DuplicateMembership => {
+                String::from(language.text(Message::CheckDuplicateMembership))
+            }
+            Problem::ShouldBeSuspended => {
+                String::from(language.text(Message::CheckShouldBeSuspended))
+            }
+            Problem::ShouldBeValid => String::from(language.text(Message::CheckShouldBeValid)),
+            Problem::IdGap(ids) => language.format(Message::CheckIdGap, &[ids]),
+        };
+        if self.repairable {
+            return format!("{} {}", text, language.text(Message::CheckRepairable));
+        }
+        return text;
+    }
+}
+
+pub struct CheckResult {
+    pub issues: Vec<Issue>,
+    // 修復可能な問題を修復した後のファイルの内容
+    pub repaired: Files,
+}
+
+// 個人、部署の行から読み取った内容
+struct Entry {
+    id: u64,
+    deleted: bool,
+    name: String,
+    // 部署は常にNone
+    employee_number: Option<String>,
+}
+
+// 修復後のファイルの行を集める
+struct Output {
+    lines: Vec<String>,
+}
+
+impl Output {
+    fn new() -> Output {
+        return Output { lines: Vec::new() };
+    }
+
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+        for line in self.lines.iter() {
+            text.push_str(line);
+            text.push('\n');
+        }
+        return text;
+    }
+}
+
+// データファイルを１行ずつ検査する
+// 形式の誤りがあっても読み込みを中断せず、全ての問題を報告する
+pub fn check(files: &Files) -> CheckResult {
+    let mut issues: Vec<Issue> = Vec::new();
+    let (persons, unreadable_persons, person_output) =
+        check_entries(DataFile::Person, &files.person, parse_person, &mut issues);
+    let (departments, unreadable_departments, department_output) = check_entries(
+        DataFile::Department,
+        &files.department,
+        parse_department,
+        &mut issues,
+    );
+    check_department_names(&departments, &mut issues);
+    check_employee_numbers(&persons, &mut issues);
+    let member_output = check_members(
+        &files.member,
+        (&persons, &unreadable_persons),
+        (&departments, &unreadable_departments),
+        &mut issues,
+    );
+    issues.sort_by_key(|issue| (issue.file as usize, issue.line));
+    return CheckResult {
+        issues: issues,
+        repaired: Files {
+            person: person_output.to_text(),
+            department: department_output.to_text(),
+            member: member_output.to_text(),
+        },
+    };
+}
+
+// 行の解析結果
+// Errの場合、Noneは行の形式の誤り、Someは値の誤り(IDは読めている)
+type Parsed = Result<Entry, Option<(u64, String)>>;
+
+// "ID 姓 名(任意) キー=値(任意、複数可)"
+fn parse_person(line: &str) -> Parsed {
+    let (info, attributes): (Vec<&str>, Vec<&str>) = line
+        .split_whitespace()
+        .partition(|token| !token.contains('='));
+    if info.len() < 2 || info.len() > 3 {
+        return Err(None);
+    }
+    let id: u64 = match info[0].parse() {
+        Ok(id) => id,
+        Err(_) => return Err(None),
+    };
+    let first_name = if info.len() == 3 { Some(info[2]) } else { None };
+    let mut person = match Person::new(info[1], first_name) {
+        Ok(person) => person,
+        Err(e) => return Err(Some((id, e.to_string()))),
+    };
+    let mut deleted = false;
+    for attribute in attributes {
+        let (key, value) = match attribute.split_once('=') {
+            Some(pair) => pair,
+            None => return Err(None),
+        };
+        if key == "deleted_at" {
+            if value.parse::<u64>().is_err() {
+                return Err(None);
+            }
+            deleted = true;
+        } else if let Err(e) = person.set_attribute(key, value) {
+            return Err(Some((id, e.to_string())));
+        }
+    }
+    return Ok(Entry {
+        id: id,
+        deleted: deleted,
+        name: person.name(),
+        employee_number: person.employee_number().map(String::from),
+    });
+}
+
+// "ID 部署名 deleted_at=削除日時(任意)"
+fn parse_department(line: &str) -> Parsed {
+    let info: Vec<&str> = line.split_whitespace().collect();
+    if info.len() != 2 && info.len() != 3 {
+        return Err(None);
+    }
+    let id: u64 = match info[0].parse() {
+        Ok(id) => id,
+        Err(_) => return Err(None),
+    };
+    if info.len() == 3 {
+        let valid = match info[2].strip_prefix("deleted_at=") {
+            Some(value) => value.parse::<u64>().is_ok(),
+            None => false,
+        };
+        if !valid {
+            return Err(None);
+        }
+    }
+    let department = match Department::new(info[1]) {
+        Ok(department) => department,
+        Err(e) => return Err(Some((id, e.to_string()))),
+    };
+    return Ok(Entry {
+        id: id,
+        deleted: info.len() == 3,
+        name: department.name(),
+        employee_number: None,
+    });
+}
+
+// 個人または部署のファイルを検査し、IDごとの内容、読み込めない行のID、修復後の行を返す
+// 空行と完全に重複した行は修復で取り除き、それ以外の問題のある行はそのまま残す
+// 読み込めない行もIDが読めればそのIDを返し、所属の検査ではその個人、部署が存在するものとして扱う
+fn check_entries(
+    file: DataFile,
+    text: &str,
+    parse: fn(&str) -> Parsed,
+    issues: &mut Vec<Issue>,
+) -> (HashMap<u64, Entry>, HashSet<u64>, Output) {
+    let mut entries: HashMap<u64, Entry> = HashMap::new();
+    let mut unreadable_ids: HashSet<u64> = HashSet::new();
+    let mut lines_by_id: HashMap<u64, Vec<String>> = HashMap::new();
+    let mut output = Output::new();
+    for (index, line) in text.split_terminator('\n').enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            issues.push(Issue::new(file, line_number, Problem::InvalidLine, true));
+            continue;
+        }
+        // 完全削除したIDを再利用しないための "max_id=ID" の行はそのまま残す
+        if let Some(max_id) = line.trim().strip_prefix("max_id=") {
+            if max_id.parse::<u64>().is_ok() {
+                output.lines.push(String::from(line));
+                continue;
+            }
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let normalized = tokens.join(" ");
+        let id = match parse(line) {
+            Ok(entry) => {
+                let id = entry.id;
+                // 同じIDの行が複数ある場合は最初の行の内容を使う
+                entries.entry(id).or_insert(entry);
+                id
+            }
+            Err(None) => {
+                issues.push(Issue::new(file, line_number, Problem::InvalidLine, false));
+                if let Some(Ok(id)) = tokens.first().map(|id| id.parse::<u64>()) {
+                    unreadable_ids.insert(id);
+                }
+                output.lines.push(String::from(line));
+                continue;
+            }
+            Err(Some((id, error))) => {
+                let problem = Problem::InvalidValue(error);
+                issues.push(Issue::new(file, line_number, problem, false));
+                unreadable_ids.insert(id);
+                id
+            }
+        };
+        let seen = lines_by_id.entry(id).or_default();
+        if seen.contains(&normalized) {
+            let problem = Problem::DuplicateLine(id);
+            issues.push(Issue::new(file, line_number, problem, true));
+            continue;
+        }
+        if !seen.is_empty() {
+            let problem = Problem::DuplicateId(id);
+            issues.push(Issue::new(file, line_number, problem, false));
+        }
+        seen.push(normalized);
+        output.lines.push(String::from(line));
+    }
+    let mut ids: Vec<u64> = lines_by_id.keys().copied().collect();
+    ids.sort();
+    if let Some(gaps) = id_gaps(&ids) {
+        issues.push(Issue::new(file, 0, Problem::IdGap(gaps), false));
+    }
+    return (entries, unreadable_ids, output);
+}
+
+// 1から最大のIDまでの欠番を "3, 5-7" の形式で返す
+fn id_gaps(ids: &[u64]) -> Option<String> {
+    let mut gaps: Vec<String> = Vec::new();
+    let mut expected: u64 = 1;
+    for id in ids.iter() {
+        if *id > expected {
+            if *id - 1 == expected {
+                gaps.push(expected.to_string());
+            } else {
+                gaps.push(format!("{}-{}", expected, *id - 1));
+            }
+        }
+        expected = *id + 1;
+    }
+    if gaps.is_empty() {
+        return None;
+    }
+    return Some(gaps.join(", "));
+}
+
+fn check_department_names(departments: &HashMap<u64, Entry>, issues: &mut Vec<Issue>) {
+    let mut ids_by_name: HashMap<&str, Vec<u64>> = HashMap::new();
+    for entry in departments.values().filter(|entry| !entry.deleted) {
+        ids_by_name.entry(&entry.name).or_default().push(entry.id);
+    }
+    for (name, ids) in ids_by_name.iter() {
+        if ids.len() > 1 {
+            let problem = Problem::DuplicateDepartmentName(String::from(*name));
+            issues.push(Issue::new(DataFile::Department, 0, problem, false));
+        }
+    }
+}
+
+// 削除されていない個人の間で社員番号が重複していないかを検査する
+fn check_employee_numbers(persons: &HashMap<u64, Entry>, issues: &mut Vec<Issue>) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for entry in persons.values().filter(|entry| !entry.deleted) {
+        if let Some(number) = &entry.employee_number {
+            *counts.entry(number).or_insert(0) += 1;
+        }
+    }
+    let mut duplicated: Vec<&str> = counts
+        .into_iter()
+        .filter(|(_number, count)| *count > 1)
+        .map(|(number, _count)| number)
+        .collect();
+    duplicated.sort();
+    for number in duplicated {
+        let problem = Problem::DuplicateEmployeeNumber(String::from(number));
+        issues.push(Issue::new(DataFile::Person, 0, problem, false));
+    }
+}
+
+// "部署ID 個人ID suspended(任意)"
+// 存在しない個人、部署への所属と重複した所属は取り除き、有効、無効の誤りは直す
+// persons、departmentsは読み込めた内容と、読み込めない行のIDの組
+// 読み込めない行の個人、部署への所属は、削除済かどうか分からないのでそのまま残す
+fn check_members(
+    text: &str,
+    persons: (&HashMap<u64, Entry>, &HashSet<u64>),
+    departments: (&HashMap<u64, Entry>, &HashSet<u64>),
+    issues: &mut Vec<Issue>,
+) -> Output {
+    let (persons, unreadable_persons) = persons;
+    let (departments, unreadable_departments) = departments;
+    let file = DataFile::Member;
+    let mut seen: HashSet<(u64, u64)> = HashSet::new();
+    let mut output = Output::new();
+    for (index, line) in text.split_terminator('\n').enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            issues.push(Issue::new(file, line_number, Problem::InvalidLine, true));
+            continue;
+        }
+        let info: Vec<&str> = line.split_whitespace().collect();
+        let ids = (
+            info.first().map(|id| id.parse::<u64>()),
+            info.get(1).map(|id| id.parse::<u64>()),
+        );
+        let suspended = info.len() == 3 && info[2] == "suspended";
+        let (department_id, person_id) = match ids {
+            (Some(Ok(department_id)), Some(Ok(person_id))) if info.len() == 2 || suspended => {
+                (department_id, person_id)
+            }
+            _ => {
+                issues.push(Issue::new(file, line_number, Problem::InvalidLine, false));
+                output.lines.push(String::from(line));
+                continue;
+            }
+        };
+        let department = departments.get(&department_id);
+        if department.is_none() && !unreadable_departments.contains(&department_id) {
+            let problem = Problem::MissingDepartment(department_id);
+            issues.push(Issue::new(file, line_number, problem, true));
+            continue;
+        }
+        let person = persons.get(&person_id);
+        if person.is_none() && !unreadable_persons.contains(&person_id) {
+            let problem = Problem::MissingPerson(person_id);
+            issues.push(Issue::new(file, line_number, problem, true));
+            continue;
+        }
+        if !seen.insert((department_id, person_id)) {
+            let problem = Problem::DuplicateMembership;
+            issues.push(Issue::new(file, line_number, problem, true));
+            continue;
+        }
+        let (person, department) = match (person, department) {
+            (Some(person), Some(department)) => (person, department),
+            _ => {
+                output.lines.push(String::from(line));
+                continue;
+            }
+        };
+        let should_be_suspended = person.deleted || department.deleted;
+        if should_be_suspended && !suspended {
+            issues.push(Issue::new(
+                file,
+                line_number,
+                Problem::ShouldBeSuspended,
+                true,
+            ));
+        }
+        if !should_be_suspended && suspended {
+            issues.push(Issue::new(file, line_number, Problem::ShouldBeValid, true));
+        }
+        if should_be_suspended {
+            output
+                .lines
+                .push(format!("{} {} suspended", department_id, person_id));
+        } else {
+            output
+                .lines
+                .push(format!("{} {}", department_id, person_id));
+        }
+    }
+    return output;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(person: &str, department: &str, member: &str) -> Files {
+        return Files {
+            person: String::from(person),
+            department: String::from(department),
+            member: String::from(member),
+        };
+    }
+
+    fn problems(result: &CheckResult) -> Vec<(DataFile, usize, Problem)> {
+        return result
+            .issues
+            .iter()
+            .map(|issue| (issue.file, issue.line, issue.problem.clone()))
+            .collect();
+    }
+
+    #[test]
+    fn check_no_issue_ok() {
+        let files = files(
+            "1 Potter Harry\n2 Weasley Ron deleted_at=10\n",
+            "1 Gryffindor\n",
+            "1 1\n1 2 suspended\n",
+        );
+        let result = check(&files);
+        assert!(result.issues.is_empty());
+        assert_eq!(result.repaired, files);
+    }
+
+    #[test]
+    fn check_members_repair_ok() {
+        let result = check(&files(
+            "1 Potter Harry\n2 Weasley Ron deleted_at=10\n",
+            "1 Gryffindor\n",
+            "1 1\n1 1\n1 2\n1 3\n2 1\n\n",
+        ));
+        assert_eq!(
+            problems(&result),
+            vec![
+                (DataFile::Member, 2, Problem::DuplicateMembership),
+                (DataFile::Member, 3, Problem::ShouldBeSuspended),
+                (DataFile::Member, 4, Problem::MissingPerson(3)),
+                (DataFile::Member, 5, Problem::MissingDepartment(2)),
+                (DataFile::Member, 6, Problem::InvalidLine),
+            ]
+        );
+        assert!(result.issues.iter().all(|issue| issue.repairable));
+        assert_eq!(result.repaired.member, "1 1\n1 2 suspended\n");
+    }
+
+    #[test]
+    fn check_entries_ok() {
+        let result = check(&files(
+            "1 Potter Harry\n1 Potter  Harry\n1 Weasley Ron\n4 Granger Hermione employee_number=E#1\nabc\n5 Longbottom employee_number=E2\n6 Lovegood employee_number=E2\n",
+            "1 Gryffindor\n2 Gryffindor\n",
+            "",
+        ));
+        assert_eq!(
+            problems(&result),
+            vec![
+                (DataFile::Person, 0, Problem::IdGap(String::from("2-3"))),
+                (
+                    DataFile::Person,
+                    0,
+                    Problem::DuplicateEmployeeNumber(String::from("E2"))
+                ),
+                (DataFile::Person, 2, Problem::DuplicateLine(1)),
+                (DataFile::Person, 3, Problem::DuplicateId(1)),
+                (
+                    DataFile::Person,
+                    4,
+                    Problem::InvalidValue(String::from(
+                        "EmployeeNumber::new : employee number contains invalid characters!"
+                    ))
+                ),
+                (DataFile::Person, 5, Problem::InvalidLine),
+                (
+                    DataFile::Department,
+                    0,
+                    Problem::DuplicateDepartmentName(String::from("Gryffindor"))
+                ),
+            ]
+        );
+        assert_eq!(
+            result.repaired.person,
+            "1 Potter Harry\n1 Weasley Ron\n4 Granger Hermione employee_number=E#1\nabc\n5 Longbottom employee_number=E2\n6 Lovegood employee_number=E2\n"
+        );
+    }
+
+    #[test]
+    fn check_members_of_unreadable_entries_ok() {
+        let result = check(&files(
+            "1 Potter Harry\n4 Granger Hermione employee_number=E#1\n5 Weasley Ron Bilius Extra\n",
+            "1 Gryffindor\n2 A=B\n",
+            "1 4\n1 5 suspended\n2 1\n1 6\n",
+        ));
+        assert_eq!(
+            problems(&result),
+            vec![
+                (DataFile::Person, 0, Problem::IdGap(String::from("2-3"))),
+                (
+                    DataFile::Person,
+                    2,
+                    Problem::InvalidValue(String::from(
+                        "EmployeeNumber::new : employee number contains invalid characters!"
+                    ))
+                ),
+                (DataFile::Person, 3, Problem::InvalidLine),
+                (
+                    DataFile::Department,
+                    2,
+                    Problem::InvalidValue(String::from(
+                        "Name::new : the value must not contain spaces or '='."
+                    ))
+                ),
+                (DataFile::Member, 4, Problem::MissingPerson(6)),
+            ]
+        );
+        // 読み込めない個人、部署への所属は修復で取り除かない
+        assert_eq!(result.repaired.member, "1 4\n1 5 suspended\n2 1\n");
+    }
+
+    #[test]
+    fn id_gaps_ok() {
+        assert_eq!(id_gaps(&[1, 2, 3]), None);
+        assert_eq!(id_gaps(&[2, 3, 7]), Some(String::from("1, 4-6")));
+    }
+}
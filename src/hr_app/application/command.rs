@@ -0,0 +1,928 @@
+use super::super::domain::person::Person;
+use super::export::ExportFormat;
+use super::import::ImportTarget;
+use super::message::{Language, Message};
+use super::org_chart::ChartFormat;
+
+// helpで表示する使い方の列の幅
+const USAGE_WIDTH: usize = 48;
+
+// 入力ミスとみなして候補を提示する最大の編集距離
+const SUGGESTION_DISTANCE: usize = 2;
+
+// コマンドの引数の種類
+// 対話入力での補完候補を選ぶために使用する
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ArgumentKind {
+    Person,
+    Department,
+    Command,
+    // 残りの語を全て空白で繋げて１つの引数とする(最後の引数にのみ指定できる)
+    Expression,
+    // 残りの語を全て個人のIDまたは名前の一覧として１つの引数とする(最後の引数にのみ指定できる)
+    Persons,
+    Other,
+}
+
+impl ArgumentKind {
+    // 残りの語を全て受け取る引数かどうか
+    pub fn takes_rest(&self) -> bool {
+        return *self == ArgumentKind::Expression || *self == ArgumentKind::Persons;
+    }
+}
+
+// コマンドの位置引数の定義
+// 必須の引数は省略された時に表示するメッセージ(missing)を持つ
+// choicesが空でない場合は、その中のいずれかしか指定できない
+pub struct ArgumentSpec {
+    pub name: &'static str,
+    pub kind: ArgumentKind,
+    pub missing: Option<Message>,
+    pub choices: &'static [&'static str],
+}
+
+// コマンドの定義
+// attributesは "キー=値" 形式で指定できるキー、flagsは "--名前" 形式で指定できるスイッチ
+// optionsは "--名前=値" または "--名前 値" 形式で値を指定するスイッチで、同じ名前を複数回指定できる
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub arguments: &'static [ArgumentSpec],
+    pub attributes: &'static [&'static str],
+    pub flags: &'static [&'static str],
+    pub options: &'static [&'static str],
+}
+
+// コマンドの解析結果
+// argumentsはCommandSpec::argumentsと同じ順番、同じ長さで、省略された引数はNoneとなる
+pub struct ParsedCommand {
+    pub spec: &'static CommandSpec,
+    arguments: Vec<Option<String>>,
+    attributes: Vec<(String, String)>,
+    flags: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+const NO_ALIASES: &[&str] = &[];
+const NO_ARGUMENTS: &[ArgumentSpec] = &[];
+const NO_ATTRIBUTES: &[&str] = &[];
+const NO_FLAGS: &[&str] = &[];
+const NO_OPTIONS: &[&str] = &[];
+// 一覧表示の絞り込みと範囲の指定
+const LIST_OPTIONS: &[&str] = &["filter", "limit", "offset"];
+
+const PERSON_ID: ArgumentSpec = ArgumentSpec {
+    name: "person_id",
+    kind: ArgumentKind::Person,
+    missing: Some(Message::PersonIdMissing),
+    choices: &[],
+};
+const DEPARTMENT_ID: ArgumentSpec = ArgumentSpec {
+    name: "department_id",
+    kind: ArgumentKind::Department,
+    missing: Some(Message::DepartmentIdMissing),
+    choices: &[],
+};
+const FROM_DEPARTMENT_ID: ArgumentSpec = ArgumentSpec {
+    name: "from_department_id",
+    kind: ArgumentKind::Department,
+    missing: Some(Message::SourceDepartmentMissing),
+    choices: &[],
+};
+const TO_DEPARTMENT_ID: ArgumentSpec = ArgumentSpec {
+    name: "to_department_id",
+    kind: ArgumentKind::Department,
+    missing: Some(Message::DestinationDepartmentMissing),
+    choices: &[],
+};
+const DEPARTMENT_NAME: ArgumentSpec = ArgumentSpec {
+    name: "department_name",
+    kind: ArgumentKind::Other,
+    missing: Some(Message::DepartmentNameMissing),
+    choices: &[],
+};
+const LAST_NAME: ArgumentSpec = ArgumentSpec {
+    name: "last_name",
+    kind: ArgumentKind::Other,
+    missing: Some(Message::PersonNameMissing),
+    choices: &[],
+};
+const FIRST_NAME: ArgumentSpec = ArgumentSpec {
+    name: "first_name",
+    kind: ArgumentKind::Other,
+    missing: None,
+    choices: &[],
+};
+const SEARCH_TEXT: ArgumentSpec = ArgumentSpec {
+    name: "text",
+    kind: ArgumentKind::Other,
+    missing: Some(Message::SearchTextMissing),
+    choices: &[],
+};
+const FILE_PATH: ArgumentSpec = ArgumentSpec {
+    name: "file",
+    kind: ArgumentKind::Other,
+    missing: Some(Message::FilePathMissing),
+    choices: &[],
+};
+
+// 入力可能な全てのコマンド
+// 解析、help、補完はこの表から行う
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "create_department",
+        aliases: NO_ALIASES,
+        arguments: &[DEPARTMENT_NAME],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "show_all_departments",
+        aliases: &["departments"],
+        arguments: NO_ARGUMENTS,
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: &["limit", "offset"],
+    },
+    CommandSpec {
+        name: "department_info",
+        aliases: &["department"],
+        arguments: &[DEPARTMENT_ID],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: LIST_OPTIONS,
+    },
+    CommandSpec {
+        name: "delete_department",
+        aliases: NO_ALIASES,
+        arguments: &[DEPARTMENT_ID],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "create_person",
+        aliases: NO_ALIASES,
+        arguments: &[LAST_NAME, FIRST_NAME],
+        attributes: &Person::ATTRIBUTE_KEYS,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "show_all_persons",
+        aliases: &["persons"],
+        arguments: &[ArgumentSpec {
+            name: "sort_key",
+            kind: ArgumentKind::Other,
+            missing: None,
+            choices: &["id", "reading"],
+        }],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: LIST_OPTIONS,
+    },
+    CommandSpec {
+        name: "person_info",
+        aliases: &["person"],
+        arguments: &[PERSON_ID],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "delete_person",
+        aliases: NO_ALIASES,
+        arguments: &[PERSON_ID],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "add_person",
+        aliases: NO_ALIASES,
+        arguments: &[PERSON_ID, DEPARTMENT_ID],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "remove_person",
+        aliases: NO_ALIASES,
+        arguments: &[PERSON_ID, DEPARTMENT_ID],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "transfer_person",
+        aliases: NO_ALIASES,
+        arguments: &[PERSON_ID, FROM_DEPARTMENT_ID, TO_DEPARTMENT_ID],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "merge_departments",
+        aliases: NO_ALIASES,
+        arguments: &[
+            ArgumentSpec {
+                name: "source_department_id",
+                kind: ArgumentKind::Department,
+                missing: Some(Message::SourceDepartmentMissing),
+                choices: &[],
+            },
+            ArgumentSpec {
+                name: "target_department_id",
+                kind: ArgumentKind::Department,
+                missing: Some(Message::DestinationDepartmentMissing),
+                choices: &[],
+            },
+        ],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "split_department",
+        aliases: NO_ALIASES,
+        arguments: &[
+            DEPARTMENT_ID,
+            ArgumentSpec {
+                name: "new_department_name",
+                kind: ArgumentKind::Other,
+                missing: Some(Message::DepartmentNameMissing),
+                choices: &[],
+            },
+            ArgumentSpec {
+                name: "person_ids",
+                kind: ArgumentKind::Persons,
+                missing: Some(Message::PersonIdsMissing),
+                choices: &[],
+            },
+        ],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "rename_person",
+        aliases: NO_ALIASES,
+        arguments: &[PERSON_ID, LAST_NAME, FIRST_NAME],
+        attributes: &Person::ATTRIBUTE_KEYS,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "rename_department",
+        aliases: NO_ALIASES,
+        arguments: &[DEPARTMENT_ID, DEPARTMENT_NAME],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "find_person",
+        aliases: NO_ALIASES,
+        arguments: &[SEARCH_TEXT],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "find_department",
+        aliases: NO_ALIASES,
+        arguments: &[SEARCH_TEXT],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "restore_person",
+        aliases: NO_ALIASES,
+        arguments: &[PERSON_ID],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "restore_department",
+        aliases: NO_ALIASES,
+        arguments: &[DEPARTMENT_ID],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "show_deleted",
+        aliases: NO_ALIASES,
+        arguments: NO_ARGUMENTS,
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "purge",
+        aliases: NO_ALIASES,
+        arguments: &[ArgumentSpec {
+            name: "days",
+            kind: ArgumentKind::Other,
+            missing: None,
+            choices: &[],
+        }],
+        attributes: NO_ATTRIBUTES,
+        flags: &["dry-run"],
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "import_csv",
+        aliases: NO_ALIASES,
+        arguments: &[
+            ArgumentSpec {
+                name: "target",
+                kind: ArgumentKind::Other,
+                missing: Some(Message::ImportTargetMissing),
+                choices: &ImportTarget::NAMES,
+            },
+            FILE_PATH,
+        ],
+        attributes: NO_ATTRIBUTES,
+        flags: &["dry-run"],
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "export",
+        aliases: NO_ALIASES,
+        arguments: &[
+            ArgumentSpec {
+                name: "format",
+                kind: ArgumentKind::Other,
+                missing: Some(Message::ExportFormatMissing),
+                choices: &ExportFormat::NAMES,
+            },
+            FILE_PATH,
+        ],
+        attributes: NO_ATTRIBUTES,
+        flags: &["include-deleted"],
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "org_chart",
+        aliases: NO_ALIASES,
+        arguments: &[
+            ArgumentSpec {
+                name: "format",
+                kind: ArgumentKind::Other,
+                missing: Some(Message::ChartFormatMissing),
+                choices: &ChartFormat::NAMES,
+            },
+            ArgumentSpec {
+                name: "file",
+                kind: ArgumentKind::Other,
+                missing: None,
+                choices: &[],
+            },
+        ],
+        attributes: NO_ATTRIBUTES,
+        flags: &["skip-empty", "unassigned"],
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "stats",
+        aliases: NO_ALIASES,
+        arguments: &[
+            ArgumentSpec {
+                name: "format",
+                kind: ArgumentKind::Other,
+                missing: None,
+                choices: &ExportFormat::NAMES,
+            },
+            ArgumentSpec {
+                name: "file",
+                kind: ArgumentKind::Other,
+                missing: None,
+                choices: &[],
+            },
+        ],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "query",
+        aliases: NO_ALIASES,
+        arguments: &[ArgumentSpec {
+            name: "expression",
+            kind: ArgumentKind::Expression,
+            missing: Some(Message::QueryExpressionMissing),
+            choices: &[],
+        }],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "undo",
+        aliases: NO_ALIASES,
+        arguments: NO_ARGUMENTS,
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "redo",
+        aliases: NO_ALIASES,
+        arguments: NO_ARGUMENTS,
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "all_info",
+        aliases: NO_ALIASES,
+        arguments: NO_ARGUMENTS,
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: &["filter"],
+    },
+    CommandSpec {
+        name: "help",
+        aliases: &["?"],
+        arguments: &[ArgumentSpec {
+            name: "command",
+            kind: ArgumentKind::Command,
+            missing: None,
+            choices: &[],
+        }],
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+    CommandSpec {
+        name: "quit",
+        aliases: &["exit"],
+        arguments: NO_ARGUMENTS,
+        attributes: NO_ATTRIBUTES,
+        flags: NO_FLAGS,
+        options: NO_OPTIONS,
+    },
+];
+
+impl CommandSpec {
+    // コマンド名または別名からコマンドを探す
+    pub fn find(name: &str) -> Option<&'static CommandSpec> {
+        return COMMANDS
+            .iter()
+            .find(|spec| spec.name == name || spec.aliases.contains(&name));
+    }
+
+    // コマンド名として認識できない語の場合のエラーメッセージを返す
+    // 似たコマンド名があれば候補として提示する
+    pub fn unrecognized(name: &str, language: Language) -> String {
+        let mut message = String::from(language.text(Message::UnrecognizedCommand));
+        let names = COMMANDS
+            .iter()
+            .flat_map(|spec| std::iter::once(&spec.name).chain(spec.aliases.iter()));
+        if let Some(candidate) = suggest(name, names) {
+            message.push(' ');
+            message.push_str(&language.format(Message::DidYouMean, &[candidate]));
+        }
+        return message;
+    }
+
+    // position番目(0始まり)の引数の種類を返す
+    pub fn argument_kind(&self, position: usize) -> ArgumentKind {
+        match (self.arguments.get(position), self.arguments.last()) {
+            (Some(argument), _) => return argument.kind,
+            (None, Some(last)) if last.kind.takes_rest() => return last.kind,
+            _ => return ArgumentKind::Other,
+        }
+    }
+
+    // 表から組み立てた使い方の文字列を返す
+    pub fn usage(&self) -> String {
+        let mut usage = String::from(self.name);
+        for argument in self.arguments.iter() {
+            usage.push_str(&format!(" <{}>", argument.name));
+            if argument.missing.is_none() {
+                usage.push_str("(optional)");
+            }
+        }
+        if !self.attributes.is_empty() {
+            usage.push_str(" <key=value>(optional)");
+        }
+        for flag in self.flags.iter() {
+            usage.push_str(&format!(" --{}(optional)", flag));
+        }
+        for option in self.options.iter() {
+            usage.push_str(&format!(" --{}=<value>(optional)", option));
+        }
+        return usage;
+    }
+
+    // 入力された語(コマンド名を除く)を解析する
+    // 必須の引数の不足、余分な引数、不明なキーやスイッチはエラーとする
+    pub fn parse<'a, I>(
+        &'static self,
+        tokens: I,
+        language: Language,
+    ) -> Result<ParsedCommand, String>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let mut positional: Vec<String> = Vec::new();
+        let mut attributes: Vec<(String, String)> = Vec::new();
+        let mut flags: Vec<String> = Vec::new();
+        let mut options: Vec<(String, String)> = Vec::new();
+        let mut tokens = tokens;
+        while let Some(token) = tokens.next() {
+            if let Some(flag) = token.strip_prefix("--") {
+                let (name, value) = match flag.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (flag, None),
+                };
+                if self.options.contains(&name) {
+                    // 値が "=" で繋がっていない場合は次の語を値とする
+                    let value = match value.or_else(|| tokens.next()) {
+                        Some(value) if !value.is_empty() => value,
+                        _ => {
+                            let message = language.format(Message::OptionValueMissing, &[token]);
+                            return Err(self.error(&message, language));
+                        }
+                    };
+                    options.push((String::from(name), String::from(value)));
+                    continue;
+                }
+                if !self.flags.contains(&flag) {
+                    let candidates = self.flags.iter().chain(self.options.iter());
+                    let name = format!("--{}", name);
+                    return Err(self.unknown_option(&name, candidates, "--", language));
+                }
+                flags.push(String::from(flag));
+            } else if let (Some((key, value)), false) =
+                (token.split_once('='), self.attributes.is_empty())
+            {
+                if !self.attributes.contains(&key) {
+                    return Err(self.unknown_option(key, self.attributes.iter(), "", language));
+                }
+                attributes.push((String::from(key), String::from(value)));
+            } else {
+                positional.push(String::from(token));
+            }
+        }
+
+        // 残りの語を全て受け取る引数は、空白で区切られた残りの語を全て含める
+        if let Some(last) = self.arguments.last() {
+            if last.kind.takes_rest() && positional.len() > self.arguments.len() {
+                let rest = positional.split_off(self.arguments.len() - 1);
+                positional.push(rest.join(" "));
+            }
+        }
+        if positional.len() > self.arguments.len() {
+            return Err(self.error(language.text(Message::TooManyArguments), language));
+        }
+        let mut arguments: Vec<Option<String>> = Vec::new();
+        for (index, argument) in self.arguments.iter().enumerate() {
+            let value = positional.get(index).cloned();
+            match (&value, argument.missing) {
+                (None, Some(missing)) => {
+                    return Err(self.error(language.text(missing), language));
+                }
+                (Some(value), _)
+                    if !argument.choices.is_empty()
+                        && !argument.choices.contains(&value.as_str()) =>
+                {
+                    let choices = argument.choices.join(" | ");
+                    let message =
+                        language.format(Message::InvalidChoice, &[argument.name, &choices]);
+                    return Err(self.error(&message, language));
+                }
+                _ => (),
+            }
+            arguments.push(value);
+        }
+
+        return Ok(ParsedCommand {
+            spec: self,
+            arguments: arguments,
+            attributes: attributes,
+            flags: flags,
+            options: options,
+        });
+    }
+
+    // helpで表示する１行分の説明を出力する
+    fn print_summary(&self, language: Language) {
+        let usage = self.usage();
+        let description = language.command_description(self.name);
+        // 使い方が長いコマンドは説明を次の行に表示する
+        if usage.chars().count() > USAGE_WIDTH {
+            println!(
+                "{}\n{:width$} : {}",
+                usage,
+                "",
+                description,
+                width = USAGE_WIDTH
+            );
+        } else {
+            println!("{:width$} : {}", usage, description, width = USAGE_WIDTH);
+        }
+    }
+
+    // help <command> で表示するコマンドの詳しい説明を出力する
+    pub fn print_help(&self, language: Language) {
+        println!("{}", language.text(Message::HowToUse));
+        println!(" {}", self.usage());
+        println!(" {}", language.command_description(self.name));
+        if !self.aliases.is_empty() {
+            println!(
+                "{} {}",
+                language.text(Message::Aliases),
+                self.aliases.join(", ")
+            );
+        }
+        for argument in self.arguments.iter() {
+            if !argument.choices.is_empty() {
+                println!(" <{}>: {}", argument.name, argument.choices.join(" | "));
+            }
+        }
+        if !self.attributes.is_empty() {
+            println!(" <key=value>: {}", language.text(Message::HelpAttributes));
+        }
+        for flag in self.flags.iter() {
+            println!(
+                " --{}: {}",
+                flag,
+                language.flag_description(self.name, flag)
+            );
+        }
+        for option in self.options.iter() {
+            println!(
+                " --{}=<value>: {}",
+                option,
+                language.flag_description(self.name, option)
+            );
+        }
+    }
+
+    fn error(&self, message: &str, language: Language) -> String {
+        return format!(
+            "{}\n{}\n {}",
+            message,
+            language.text(Message::HowToUse),
+            self.usage()
+        );
+    }
+
+    fn unknown_option<'a, I>(
+        &self,
+        key: &str,
+        candidates: I,
+        prefix: &str,
+        language: Language,
+    ) -> String
+    where
+        I: Iterator<Item = &'a &'static str>,
+    {
+        let mut message = language.format(Message::UnknownOption, &[key]);
+        let key = key.trim_start_matches('-');
+        if let Some(candidate) = suggest(key, candidates) {
+            message.push(' ');
+            let candidate = format!("{}{}", prefix, candidate);
+            message.push_str(&language.format(Message::DidYouMean, &[&candidate]));
+        }
+        return self.error(&message, language);
+    }
+}
+
+impl ParsedCommand {
+    // index番目の引数を返す
+    // 必須の引数は解析時に存在を確認しているので、常に値がある
+    pub fn argument(&self, index: usize) -> String {
+        return self.optional_argument(index).unwrap_or_default();
+    }
+
+    pub fn optional_argument(&self, index: usize) -> Option<String> {
+        match self.arguments.get(index) {
+            Some(value) => return value.clone(),
+            None => return None,
+        }
+    }
+
+    pub fn attributes(&self) -> Vec<(String, String)> {
+        return self.attributes.clone();
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        return self.flags.iter().any(|flag| flag == name);
+    }
+
+    // 値付きのスイッチの値を指定された順に返す
+    pub fn option_values(&self, name: &str) -> Vec<String> {
+        return self
+            .options
+            .iter()
+            .filter(|(option, _value)| option == name)
+            .map(|(_option, value)| value.clone())
+            .collect();
+    }
+
+    // 値付きのスイッチの値を返す
+    // 複数回指定された場合は最後の値とする
+    pub fn option(&self, name: &str) -> Option<String> {
+        return self.option_values(name).pop();
+    }
+}
+
+// 全てのコマンドの使い方と説明を出力する
+pub fn print_all_help(language: Language) {
+    println!("{}", language.text(Message::HelpTitle));
+    for spec in COMMANDS.iter() {
+        spec.print_summary(language);
+    }
+    println!("{}", language.text(Message::HelpNameNote));
+    println!("{}", language.text(Message::HelpDetailHint));
+}
+
+// 候補の中からwordに最も近いものを返す
+// 編集距離がSUGGESTION_DISTANCE以下で、word全体を書き換えるほどではないものに限る
+fn suggest<'a, I>(word: &str, candidates: I) -> Option<&'static str>
+where
+    I: Iterator<Item = &'a &'static str>,
+{
+    let mut best: Option<(usize, &'static str)> = None;
+    for candidate in candidates {
+        let distance = edit_distance(word, candidate);
+        if distance > SUGGESTION_DISTANCE || distance >= word.chars().count() {
+            continue;
+        }
+        match best {
+            Some((best_distance, _)) if best_distance <= distance => (),
+            _ => best = Some((distance, candidate)),
+        }
+    }
+    return best.map(|(_distance, candidate)| candidate);
+}
+
+// 2つの文字列のレーベンシュタイン距離を返す
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+        }
+        previous = current;
+    }
+    return previous[b.len()];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_by_alias_ok() {
+        assert_eq!(
+            CommandSpec::find("persons").unwrap().name,
+            "show_all_persons"
+        );
+        assert!(CommandSpec::find("nothing").is_none());
+    }
+
+    #[test]
+    fn usage_ok() {
+        let spec = CommandSpec::find("create_person").unwrap();
+        assert_eq!(
+            spec.usage(),
+            String::from("create_person <last_name> <first_name>(optional) <key=value>(optional)")
+        );
+    }
+
+    #[test]
+    fn parse_ok() {
+        let spec = CommandSpec::find("create_person").unwrap();
+        let tokens = "Potter Harry nickname=Boy".split_whitespace();
+        let parsed = spec.parse(tokens, Language::English).unwrap();
+        assert_eq!(parsed.argument(0), String::from("Potter"));
+        assert_eq!(parsed.optional_argument(1), Some(String::from("Harry")));
+        assert_eq!(
+            parsed.attributes(),
+            vec![(String::from("nickname"), String::from("Boy"))]
+        );
+    }
+
+    #[test]
+    fn parse_missing_argument_err() {
+        let spec = CommandSpec::find("add_person").unwrap();
+        let result = spec.parse("1".split_whitespace(), Language::English);
+        assert!(result
+            .err()
+            .unwrap()
+            .starts_with("department_id is missing"));
+    }
+
+    #[test]
+    fn parse_too_many_arguments_err() {
+        let spec = CommandSpec::find("person_info").unwrap();
+        assert!(spec
+            .parse("1 2".split_whitespace(), Language::English)
+            .is_err());
+    }
+
+    #[test]
+    fn parse_invalid_choice_err() {
+        let spec = CommandSpec::find("show_all_persons").unwrap();
+        assert!(spec
+            .parse("name".split_whitespace(), Language::English)
+            .is_err());
+    }
+
+    #[test]
+    fn parse_flag_ok() {
+        let spec = CommandSpec::find("purge").unwrap();
+        let parsed = spec
+            .parse("10 --dry-run".split_whitespace(), Language::English)
+            .unwrap();
+        assert!(parsed.flag("dry-run"));
+        assert_eq!(parsed.optional_argument(0), Some(String::from("10")));
+    }
+
+    #[test]
+    fn parse_unknown_attribute_suggests_err() {
+        let spec = CommandSpec::find("create_person").unwrap();
+        let result = spec.parse("Potter nicknme=Boy".split_whitespace(), Language::English);
+        assert!(result.err().unwrap().contains("did you mean 'nickname'?"));
+    }
+
+    #[test]
+    fn unrecognized_suggests_ok() {
+        let message = CommandSpec::unrecognized("person_inf", Language::English);
+        assert!(message.contains("did you mean 'person_info'?"));
+        let message = CommandSpec::unrecognized("xyz", Language::English);
+        assert!(!message.contains("did you mean"));
+    }
+
+    #[test]
+    fn edit_distance_ok() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("undo", "undo"), 0);
+    }
+
+    #[test]
+    fn parse_expression_ok() {
+        let spec = CommandSpec::find("query").unwrap();
+        let tokens = "dept:1 &  dept:2 - dept:3".split_whitespace();
+        let parsed = spec.parse(tokens, Language::English).unwrap();
+        assert_eq!(parsed.argument(0), String::from("dept:1 & dept:2 - dept:3"));
+        assert_eq!(spec.argument_kind(3), ArgumentKind::Expression);
+        assert!(spec
+            .parse("".split_whitespace(), Language::English)
+            .is_err());
+    }
+
+    #[test]
+    fn parse_persons_ok() {
+        let spec = CommandSpec::find("split_department").unwrap();
+        let tokens = "1 Seekers 2 Potter 5".split_whitespace();
+        let parsed = spec.parse(tokens, Language::English).unwrap();
+        assert_eq!(parsed.argument(1), String::from("Seekers"));
+        assert_eq!(parsed.argument(2), String::from("2 Potter 5"));
+        assert_eq!(spec.argument_kind(4), ArgumentKind::Persons);
+        assert!(spec
+            .parse("1 Seekers".split_whitespace(), Language::English)
+            .is_err());
+    }
+
+    #[test]
+    fn parse_option_ok() {
+        let spec = CommandSpec::find("show_all_persons").unwrap();
+        let tokens = "reading --filter=name:pot --limit 20 --filter unassigned".split_whitespace();
+        let parsed = spec.parse(tokens, Language::English).unwrap();
+        assert_eq!(parsed.optional_argument(0), Some(String::from("reading")));
+        assert_eq!(
+            parsed.option_values("filter"),
+            vec![String::from("name:pot"), String::from("unassigned")]
+        );
+        assert_eq!(parsed.option("limit"), Some(String::from("20")));
+        assert_eq!(parsed.option("offset"), None);
+    }
+
+    #[test]
+    fn parse_option_missing_value_err() {
+        let spec = CommandSpec::find("show_all_persons").unwrap();
+        let result = spec.parse("--limit".split_whitespace(), Language::English);
+        assert!(result
+            .err()
+            .unwrap()
+            .starts_with("value of --limit is missing"));
+        let result = spec.parse("--limt=2".split_whitespace(), Language::English);
+        assert!(result.err().unwrap().contains("did you mean '--limit'?"));
+    }
+}
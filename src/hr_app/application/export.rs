@@ -0,0 +1,361 @@
+use super::super::domain::department::id::DepartmentId;
+use super::super::domain::department::list::DepartmentList;
+use super::super::domain::member::MemberList;
+use super::super::domain::person::id::PersonId;
+use super::super::domain::person::list::PersonList;
+use super::super::domain::person::Person;
+use super::super::domain::timestamp::Timestamp;
+use super::super::format::csv;
+use super::super::format::json::Value;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// JSON出力の形式を変更した場合に上げる
+pub const SCHEMA_VERSION: u64 = 1;
+
+// 出力の形式
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub const NAMES: [&'static str; 2] = ["csv", "json"];
+
+    pub fn new(value: &str) -> Result<ExportFormat, &'static str> {
+        match value {
+            "csv" => return Ok(ExportFormat::Csv),
+            "json" => return Ok(ExportFormat::Json),
+            _ => return Err("ExportFormat::new : format must be csv or json"),
+        }
+    }
+}
+
+// 出力する表
+// 列と各行の値は常に同じ順番で出力し、値のない項目はNullとする
+pub(super) struct Table {
+    pub(super) name: &'static str,
+    pub(super) columns: &'static [&'static str],
+    pub(super) rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    // CSVでは見出しを１行目とし、Nullは空欄、数値と文字列はそのまま出力する
+    pub(super) fn to_csv(&self) -> String {
+        let header: Vec<String> = self.columns.iter().map(|c| String::from(*c)).collect();
+        let mut text = csv::format_row(&header);
+        for row in self.rows.iter() {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|value| match value {
+                    Value::Number(number) => number.to_string(),
+                    Value::String(value) => value.clone(),
+                    _ => String::new(),
+                })
+                .collect();
+            text.push_str(&csv::format_row(&fields));
+        }
+        return text;
+    }
+
+    // JSONでは各行を列名をキーとしたオブジェクトにする
+    pub(super) fn to_json(&self) -> Value {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let members = self
+                    .columns
+                    .iter()
+                    .zip(row.iter())
+                    .map(|(column, value)| (String::from(*column), value.clone()))
+                    .collect();
+                Value::Object(members)
+            })
+            .collect();
+        return Value::Array(rows);
+    }
+}
+
+fn deleted_at_value(deleted_at: Option<Timestamp>) -> Value {
+    match deleted_at {
+        Some(timestamp) => return Value::String(timestamp.to_iso8601()),
+        None => return Value::Null,
+    }
+}
+
+const PERSON_COLUMNS: [&str; 10] = [
+    "id",
+    "last_name",
+    "first_name",
+    "employee_number",
+    "middle",
+    "nickname",
+    "reading_last",
+    "reading_first",
+    "order",
+    "deleted_at",
+];
+
+fn person_row(id: PersonId, person: &Person, deleted_at: Option<Timestamp>) -> Vec<Value> {
+    let person_name = person.person_name();
+    let attributes = person_name.attributes();
+    let attribute = |key: &str| {
+        let value = attributes
+            .iter()
+            .find(|(attribute_key, _value)| *attribute_key == key)
+            .map(|(_key, value)| value.as_str());
+        return Value::optional_string(value);
+    };
+    return vec![
+        Value::Number(id.as_u64()),
+        Value::String(String::from(person_name.last_name())),
+        Value::optional_string(person_name.first_name()),
+        Value::optional_string(person.employee_number()),
+        attribute("middle"),
+        attribute("nickname"),
+        attribute("reading_last"),
+        attribute("reading_first"),
+        Value::String(person_name.order().to_string()),
+        deleted_at_value(deleted_at),
+    ];
+}
+
+// 1人分を出力と同じ列名のオブジェクトとして返す
+// 削除済でない個人のみを扱うので、deleted_atは含めない
+pub fn person_value(id: PersonId, person: &Person) -> Value {
+    let members = PERSON_COLUMNS
+        .iter()
+        .zip(person_row(id, person, None))
+        .filter(|(column, _value)| **column != "deleted_at")
+        .map(|(column, value)| (String::from(*column), value))
+        .collect();
+    return Value::Object(members);
+}
+
+pub fn department_value(id: DepartmentId, name: &str) -> Value {
+    return Value::Object(vec![
+        (String::from("id"), Value::Number(id.as_u64())),
+        (String::from("name"), Value::String(String::from(name))),
+    ]);
+}
+
+fn person_table(person_list: &mut PersonList, include_deleted: bool) -> Table {
+    let ids: Vec<PersonId> = person_list.into_iter().map(|(id, _name)| id).collect();
+    let mut rows: Vec<(PersonId, Vec<Value>)> = Vec::new();
+    for id in ids {
+        if let Ok(person) = person_list.person(id) {
+            rows.push((id, person_row(id, person, None)));
+        }
+    }
+    if include_deleted {
+        for (id, person, deleted_at) in person_list.deleted_persons() {
+            rows.push((id, person_row(id, person, Some(deleted_at))));
+        }
+    }
+    rows.sort_by_key(|(id, _row)| *id);
+    return Table {
+        name: "persons",
+        columns: &PERSON_COLUMNS,
+        rows: rows.into_iter().map(|(_id, row)| row).collect(),
+    };
+}
+
+fn department_table(department_list: &mut DepartmentList, include_deleted: bool) -> Table {
+    let mut rows: Vec<(DepartmentId, Vec<Value>)> = Vec::new();
+    for (id, name) in department_list.into_iter() {
+        let row = vec![Value::Number(id.as_u64()), Value::String(name), Value::Null];
+        rows.push((id, row));
+    }
+    if include_deleted {
+        for (id, department, deleted_at) in department_list.deleted_departments() {
+            let row = vec![
+                Value::Number(id.as_u64()),
+                Value::String(department.name()),
+                deleted_at_value(Some(deleted_at)),
+            ];
+            rows.push((id, row));
+        }
+    }
+    rows.sort_by_key(|(id, _row)| *id);
+    return Table {
+        name: "departments",
+        columns: &["id", "name", "deleted_at"],
+        rows: rows.into_iter().map(|(_id, row)| row).collect(),
+    };
+}
+
+// 一時的に無効となっている所属(削除済の人、部署の所属)はinclude_deletedの場合のみ出力する
+fn member_table(member_list: &mut MemberList, include_deleted: bool) -> Table {
+    let mut members: Vec<(DepartmentId, PersonId, &'static str)> = Vec::new();
+    for (department_id, person_ids) in member_list.into_iter() {
+        for person_id in person_ids {
+            members.push((department_id, person_id, "valid"));
+        }
+    }
+    if include_deleted {
+        for (department_id, person_id) in member_list.suspended_members() {
+            members.push((department_id, person_id, "suspended"));
+        }
+    }
+    members.sort();
+    let rows = members
+        .into_iter()
+        .map(|(department_id, person_id, status)| {
+            vec![
+                Value::Number(department_id.as_u64()),
+                Value::Number(person_id.as_u64()),
+                Value::String(String::from(status)),
+            ]
+        })
+        .collect();
+    return Table {
+        name: "members",
+        columns: &["department_id", "person_id", "status"],
+        rows: rows,
+    };
+}
+
+fn tables(
+    include_deleted: bool,
+    person_list: &mut PersonList,
+    department_list: &mut DepartmentList,
+    member_list: &mut MemberList,
+) -> Vec<Table> {
+    return vec![
+        person_table(person_list, include_deleted),
+        department_table(department_list, include_deleted),
+        member_table(member_list, include_deleted),
+    ];
+}
+
+// 組織全体をJSONの文書に変換する
+// {"schema_version": 1, "persons": [...], "departments": [...], "members": [...]}
+pub fn to_json(
+    include_deleted: bool,
+    person_list: &mut PersonList,
+    department_list: &mut DepartmentList,
+    member_list: &mut MemberList,
+) -> Value {
+    let mut document = vec![(
+        String::from("schema_version"),
+        Value::Number(SCHEMA_VERSION),
+    )];
+    for table in tables(include_deleted, person_list, department_list, member_list) {
+        document.push((String::from(table.name), table.to_json()));
+    }
+    return Value::Object(document);
+}
+
+// 組織全体をファイルに出力し、出力したファイルのパスを返す
+// CSVの場合はpathをディレクトリとして persons.csv, departments.csv, members.csv を作成する
+// JSONの場合はpathに１つの文書として出力する
+pub fn export(
+    format: ExportFormat,
+    path: &str,
+    include_deleted: bool,
+    person_list: &mut PersonList,
+    department_list: &mut DepartmentList,
+    member_list: &mut MemberList,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    match format {
+        ExportFormat::Csv => {
+            let tables = tables(include_deleted, person_list, department_list, member_list);
+            return write_csv(path, &tables);
+        }
+        ExportFormat::Json => {
+            let document = to_json(include_deleted, person_list, department_list, member_list);
+            fs::write(path, document.to_json())?;
+            return Ok(vec![String::from(path)]);
+        }
+    }
+}
+
+// pathをディレクトリとして表ごとに "<表の名前>.csv" を作成し、作成したファイルのパスを返す
+pub(super) fn write_csv(path: &str, tables: &[Table]) -> Result<Vec<String>, Box<dyn Error>> {
+    fs::create_dir_all(path)?;
+    let mut written: Vec<String> = Vec::new();
+    for table in tables.iter() {
+        let file_path = Path::new(path).join(format!("{}.csv", table.name));
+        fs::write(&file_path, table.to_csv())?;
+        written.push(file_path.to_string_lossy().to_string());
+    }
+    return Ok(written);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::domain::department::Department;
+    use super::*;
+
+    fn generate_lists() -> (PersonList, DepartmentList, MemberList) {
+        let mut person_list = PersonList::new();
+        let mut department_list = DepartmentList::new();
+        let mut member_list = MemberList::new();
+        let mut harry = Person::new("Potter", Some("Harry")).unwrap();
+        harry.set_attribute("employee_number", "E001").unwrap();
+        person_list.add_person(harry, None).unwrap();
+        let ron = Person::new("Weasley", Some("Ron")).unwrap();
+        person_list.add_person(ron, None).unwrap();
+        let department = Department::new("Gryffindor").unwrap();
+        department_list.add_department(department, None).unwrap();
+        for person_id in [PersonId::new(1), PersonId::new(2)] {
+            member_list
+                .add_person_to_department(
+                    person_id,
+                    DepartmentId::new(1),
+                    &person_list,
+                    &department_list,
+                )
+                .unwrap();
+        }
+        person_list.delete_person(PersonId::new(2)).unwrap();
+        member_list.suspend_person(PersonId::new(2));
+        return (person_list, department_list, member_list);
+    }
+
+    #[test]
+    fn person_table_to_csv_ok() {
+        let (mut person_list, _department_list, _member_list) = generate_lists();
+        let csv = person_table(&mut person_list, false).to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "id,last_name,first_name,employee_number,middle,nickname,reading_last,reading_first,order,deleted_at"
+        );
+        assert_eq!(lines[1], "1,Potter,Harry,E001,,,,,family_first,");
+    }
+
+    #[test]
+    fn include_deleted_ok() {
+        let (mut person_list, _department_list, mut member_list) = generate_lists();
+        assert_eq!(person_table(&mut person_list, true).rows.len(), 2);
+        let members = member_table(&mut member_list, false);
+        assert_eq!(members.rows.len(), 1);
+        let members = member_table(&mut member_list, true);
+        assert_eq!(members.rows[1][2], Value::String(String::from("suspended")));
+    }
+
+    #[test]
+    fn to_json_schema_ok() {
+        let (mut person_list, mut department_list, mut member_list) = generate_lists();
+        let document = to_json(
+            false,
+            &mut person_list,
+            &mut department_list,
+            &mut member_list,
+        );
+        let Value::Object(members) = document else {
+            panic!("document must be an object");
+        };
+        let keys: Vec<&str> = members.iter().map(|(key, _value)| key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec!["schema_version", "persons", "departments", "members"]
+        );
+        assert_eq!(members[0].1, Value::Number(SCHEMA_VERSION));
+    }
+}
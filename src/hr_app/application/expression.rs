@@ -0,0 +1,318 @@
+use std::error::Error;
+use std::fmt;
+
+// 所属の集合演算の式
+// 各項は個人の集合を表し、演算子で集合同士を組み合わせる
+//  dept:<部署のIDか名前>  部署に所属している個人(演算子や括弧を含む名前は dept:"..." と囲む)
+//  unassigned             どの部署にも所属していない個人
+//  all                    全ての個人
+//  A & B                  積集合(AとBの両方に含まれる)
+//  A | B                  和集合(AとBのどちらかに含まれる)
+//  A - B                  差集合(Aに含まれ、Bに含まれない)
+// & は | と - よりも先に計算し、同じ優先度の演算子は左から計算する。( ) で順序を指定できる
+// 式全体を "..." で囲んでもよい(query "dept:1 & dept:2")
+#[derive(PartialEq, Clone, Debug)]
+pub enum Expression {
+    Department(String),
+    Unassigned,
+    All,
+    Intersection(Box<Expression>, Box<Expression>),
+    Union(Box<Expression>, Box<Expression>),
+    Difference(Box<Expression>, Box<Expression>),
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Token {
+    Operand(String),
+    Department(String),
+    And,
+    Or,
+    Minus,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Operand(word) => return write!(f, "{}", word),
+            Token::Department(key) => return write!(f, "dept:{}", key),
+            Token::And => return write!(f, "&"),
+            Token::Or => return write!(f, "|"),
+            Token::Minus => return write!(f, "-"),
+            Token::Open => return write!(f, "("),
+            Token::Close => return write!(f, ")"),
+        }
+    }
+}
+
+pub const OPERATOR_CHARS: [char; 5] = ['&', '|', '-', '(', ')'];
+
+// 括弧の入れ子の上限
+// 再帰下降で解析するので、深すぎる入れ子でスタックを使い果たさないようにする
+const MAX_NESTING: usize = 64;
+// 語(項、演算子、括弧)の数の上限
+// 演算子の連続は左に深い木になり、評価や破棄で再帰するので、式の長さも制限する
+const MAX_TOKENS: usize = 1000;
+
+// 式を語に分割する
+// 演算子と括弧は空白が無くても区切りとし、dept: の直後の "..." の中は演算子も名前の一部とする
+fn tokenize(text: &str) -> Result<Vec<Token>, Box<dyn Error>> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.peek().copied() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let operator = match c {
+            '&' => Some(Token::And),
+            '|' => Some(Token::Or),
+            '-' => Some(Token::Minus),
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            _ => None,
+        };
+        if let Some(operator) = operator {
+            chars.next();
+            tokens.push(operator);
+            continue;
+        }
+        let mut word = String::new();
+        while let Some(c) = chars.peek().copied() {
+            if c.is_whitespace() || OPERATOR_CHARS.contains(&c) {
+                break;
+            }
+            chars.next();
+            if c != '"' || word != "dept:" {
+                word.push(c);
+                continue;
+            }
+            // 閉じる " までをそのまま名前とする
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => word.push(c),
+                    None => return Err("Expression::parse : missing closing quote".into()),
+                }
+            }
+        }
+        match word.strip_prefix("dept:") {
+            Some(key) => tokens.push(Token::Department(String::from(key))),
+            None => tokens.push(Token::Operand(word)),
+        }
+    }
+    return Ok(tokens);
+}
+
+// 再帰下降で式を解析する
+// depthは解析中の括弧の入れ子の深さ
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        return self.tokens.get(self.position);
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        return token;
+    }
+
+    // expression := term (("|" | "-") term)*
+    fn expression(&mut self) -> Result<Expression, Box<dyn Error>> {
+        let mut left = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Or) => {
+                    self.next();
+                    left = Expression::Union(Box::new(left), Box::new(self.term()?));
+                }
+                Some(Token::Minus) => {
+                    self.next();
+                    left = Expression::Difference(Box::new(left), Box::new(self.term()?));
+                }
+                _ => return Ok(left),
+            }
+        }
+    }
+
+    // term := factor ("&" factor)*
+    fn term(&mut self) -> Result<Expression, Box<dyn Error>> {
+        let mut left = self.factor()?;
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            left = Expression::Intersection(Box::new(left), Box::new(self.factor()?));
+        }
+        return Ok(left);
+    }
+
+    // factor := "(" expression ")" | dept:<key> | unassigned | all
+    fn factor(&mut self) -> Result<Expression, Box<dyn Error>> {
+        match self.next() {
+            Some(Token::Open) => {
+                if self.depth >= MAX_NESTING {
+                    return Err(format!(
+                        "Expression::parse : parentheses are nested too deeply (at most {})",
+                        MAX_NESTING
+                    )
+                    .into());
+                }
+                self.depth += 1;
+                let expression = self.expression()?;
+                self.depth -= 1;
+                if self.next() != Some(Token::Close) {
+                    return Err("Expression::parse : missing ')'".into());
+                }
+                return Ok(expression);
+            }
+            Some(Token::Department(key)) if !key.is_empty() => {
+                return Ok(Expression::Department(key))
+            }
+            Some(Token::Operand(word)) if word == "unassigned" => return Ok(Expression::Unassigned),
+            Some(Token::Operand(word)) if word == "all" => return Ok(Expression::All),
+            Some(token) => {
+                return Err(format!(
+                    "Expression::parse : unexpected '{}' (dept:<department>, unassigned, all, &, |, -, ( ))",
+                    token
+                )
+                .into())
+            }
+            None => return Err("Expression::parse : the expression ends unexpectedly".into()),
+        }
+    }
+}
+
+// 式全体を囲む "..." を1組だけ取り除く
+// " は dept: の直後にしか書けないので、先頭の " は式全体を囲むものとなる
+fn strip_quotes(text: &str) -> &str {
+    let text = text.trim();
+    if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+        return &text[1..text.len() - 1];
+    }
+    return text;
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Expression, Box<dyn Error>> {
+        let tokens = tokenize(strip_quotes(text))?;
+        if tokens.len() > MAX_TOKENS {
+            return Err(format!(
+                "Expression::parse : the expression is too long (at most {} words)",
+                MAX_TOKENS
+            )
+            .into());
+        }
+        let mut parser = Parser {
+            tokens: tokens,
+            position: 0,
+            depth: 0,
+        };
+        let expression = parser.expression()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!(
+                "Expression::parse : unexpected '{}' (dept:<department>, unassigned, all, &, |, -, ( ))",
+                token
+            )
+            .into());
+        }
+        return Ok(expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn department(key: &str) -> Box<Expression> {
+        return Box::new(Expression::Department(String::from(key)));
+    }
+
+    #[test]
+    fn parse_precedence_ok() {
+        // & は - よりも先に計算する
+        assert_eq!(
+            Expression::parse("dept:1 & dept:2 - dept:3").unwrap(),
+            Expression::Difference(
+                Box::new(Expression::Intersection(department("1"), department("2"))),
+                department("3")
+            )
+        );
+        assert_eq!(
+            Expression::parse("dept:1 - (dept:2|dept:3)").unwrap(),
+            Expression::Difference(
+                department("1"),
+                Box::new(Expression::Union(department("2"), department("3")))
+            )
+        );
+        // 同じ優先度の演算子は左から計算する
+        assert_eq!(
+            Expression::parse("all - dept:1 | unassigned").unwrap(),
+            Expression::Union(
+                Box::new(Expression::Difference(
+                    Box::new(Expression::All),
+                    department("1")
+                )),
+                Box::new(Expression::Unassigned)
+            )
+        );
+    }
+
+    #[test]
+    fn parse_quoted_name_ok() {
+        assert_eq!(
+            Expression::parse("dept:\"Research & Development\"&dept:Sales").unwrap(),
+            Expression::Intersection(department("Research & Development"), department("Sales"))
+        );
+    }
+
+    #[test]
+    fn parse_quoted_expression_ok() {
+        assert_eq!(
+            Expression::parse("\"dept:1 & dept:2 - dept:3\"").unwrap(),
+            Expression::parse("dept:1 & dept:2 - dept:3").unwrap()
+        );
+        assert_eq!(
+            Expression::parse("\"dept:\"Customer Support\" | all\"").unwrap(),
+            Expression::Union(department("Customer Support"), Box::new(Expression::All))
+        );
+    }
+
+    #[test]
+    fn parse_too_deep_err() {
+        let nested = format!("{}all{}", "(".repeat(MAX_NESTING), ")".repeat(MAX_NESTING));
+        assert_eq!(Expression::parse(&nested).unwrap(), Expression::All);
+        let depth = MAX_NESTING + 1;
+        let text = format!("{}all{}", "(".repeat(depth), ")".repeat(depth));
+        let error = Expression::parse(&text).err().unwrap().to_string();
+        assert!(error.contains("nested too deeply"), "{}", error);
+        let text = format!("{}all", "(".repeat(200000));
+        assert!(Expression::parse(&text).is_err());
+        let text = format!("all{}", " | all".repeat(200000));
+        let error = Expression::parse(&text).err().unwrap().to_string();
+        assert!(error.contains("too long"), "{}", error);
+    }
+
+    #[test]
+    fn parse_err() {
+        for text in [
+            "",
+            "dept:1 &",
+            "(dept:1",
+            "dept:1 dept:2",
+            "dept:",
+            "team:1",
+            "dept:\"Sales",
+            "\"dept:1",
+            "unass\"igned\"",
+            ")",
+        ] {
+            assert!(Expression::parse(text).is_err(), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::error::Error;
+
+// 所属部署数の比較の種類
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Comparison {
+    Equal,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+impl Comparison {
+    // 長い演算子から順に調べる(">=" を ">" と誤認しないため)
+    const OPERATORS: [(&'static str, Comparison); 6] = [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        ("=", Comparison::Equal),
+        (":", Comparison::Equal),
+    ];
+
+    pub fn matches(&self, value: usize, target: usize) -> bool {
+        match self {
+            Comparison::Equal => return value == target,
+            Comparison::Greater => return value > target,
+            Comparison::GreaterOrEqual => return value >= target,
+            Comparison::Less => return value < target,
+            Comparison::LessOrEqual => return value <= target,
+        }
+    }
+}
+
+// 個人の一覧の絞り込み条件
+// --filter=<式> で指定し、複数指定した場合は全てを満たす個人に絞り込む
+//  name:<文字列>           名前または読み仮名に文字列を含む(英字の大文字小文字は区別しない)
+//  dept:<部署のIDか名前>   部署に所属している
+//  unassigned              どの部署にも所属していない
+//  memberships<比較><数>   所属部署数の比較(比較は =, :, >, >=, <, <=)
+#[derive(PartialEq, Clone, Debug)]
+pub enum PersonFilter {
+    NameContains(String),
+    Department(String),
+    Unassigned,
+    Memberships(Comparison, usize),
+}
+
+impl PersonFilter {
+    pub fn new(expression: &str) -> Result<PersonFilter, Box<dyn Error>> {
+        if expression == "unassigned" {
+            return Ok(PersonFilter::Unassigned);
+        }
+        if let Some(text) = expression.strip_prefix("name:") {
+            if !text.is_empty() {
+                return Ok(PersonFilter::NameContains(text.to_lowercase()));
+            }
+        }
+        if let Some(department) = expression.strip_prefix("dept:") {
+            if !department.is_empty() {
+                return Ok(PersonFilter::Department(String::from(department)));
+            }
+        }
+        if let Some(rest) = expression.strip_prefix("memberships") {
+            for (operator, comparison) in Comparison::OPERATORS.iter() {
+                if let Some(count) = rest.strip_prefix(operator) {
+                    if let Ok(count) = count.parse() {
+                        return Ok(PersonFilter::Memberships(*comparison, count));
+                    }
+                }
+            }
+        }
+        return Err(format!(
+            "PersonFilter::new : invalid filter '{}' (name:<text>, dept:<department>, unassigned, memberships<op><count>)",
+            expression
+        )
+        .into());
+    }
+}
+
+// 一覧の表示範囲
+// offset件を飛ばした後、最大limit件を表示する(limitを省略した場合は残り全て)
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Paging {
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl Paging {
+    pub fn new(offset: Option<&str>, limit: Option<&str>) -> Result<Paging, Box<dyn Error>> {
+        let number = |value: &str| -> Result<usize, Box<dyn Error>> {
+            return value
+                .parse()
+                .map_err(|_| "Paging::new : limit and offset must be numbers".into());
+        };
+        let offset = match offset {
+            Some(offset) => number(offset)?,
+            None => 0,
+        };
+        let limit = match limit {
+            Some(limit) => Some(number(limit)?),
+            None => None,
+        };
+        return Ok(Paging {
+            offset: offset,
+            limit: limit,
+        });
+    }
+
+    // 範囲を指定したかどうか
+    pub fn is_specified(&self) -> bool {
+        return self.offset > 0 || self.limit.is_some();
+    }
+
+    pub fn apply<T>(&self, items: Vec<T>) -> Vec<T> {
+        let items = items.into_iter().skip(self.offset);
+        match self.limit {
+            Some(limit) => return items.take(limit).collect(),
+            None => return items.collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn person_filter_new_ok() {
+        assert_eq!(
+            PersonFilter::new("name:Pot").unwrap(),
+            PersonFilter::NameContains(String::from("pot"))
+        );
+        assert_eq!(
+            PersonFilter::new("dept:Sales").unwrap(),
+            PersonFilter::Department(String::from("Sales"))
+        );
+        assert_eq!(
+            PersonFilter::new("unassigned").unwrap(),
+            PersonFilter::Unassigned
+        );
+        assert_eq!(
+            PersonFilter::new("memberships>=2").unwrap(),
+            PersonFilter::Memberships(Comparison::GreaterOrEqual, 2)
+        );
+        assert_eq!(
+            PersonFilter::new("memberships:0").unwrap(),
+            PersonFilter::Memberships(Comparison::Equal, 0)
+        );
+    }
+
+    #[test]
+    fn person_filter_new_err() {
+        for expression in ["name:", "dept:", "age:3", "memberships>two", "memberships"] {
+            assert!(PersonFilter::new(expression).is_err(), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn comparison_matches_ok() {
+        assert!(Comparison::Greater.matches(2, 1));
+        assert!(!Comparison::Greater.matches(1, 1));
+        assert!(Comparison::LessOrEqual.matches(1, 1));
+        assert!(Comparison::Less.matches(0, 1));
+    }
+
+    #[test]
+    fn paging_ok() {
+        let items: Vec<u32> = (1..=10).collect();
+        let paging = Paging::new(Some("3"), Some("4")).unwrap();
+        assert_eq!(paging.apply(items.clone()), vec![4, 5, 6, 7]);
+        let paging = Paging::new(Some("8"), None).unwrap();
+        assert_eq!(paging.apply(items.clone()), vec![9, 10]);
+        let paging = Paging::new(None, None).unwrap();
+        assert!(!paging.is_specified());
+        assert_eq!(paging.apply(items).len(), 10);
+        assert!(Paging::new(None, Some("-1")).is_err());
+    }
+}
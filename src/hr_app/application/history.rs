@@ -0,0 +1,570 @@
+use super::super::domain::department::id::DepartmentId;
+use super::super::domain::department::list::DepartmentList;
+use super::super::domain::department::Department;
+use super::super::domain::member::MemberList;
+use super::super::domain::person::id::PersonId;
+use super::super::domain::person::list::PersonList;
+use super::super::domain::person::Person;
+use std::error::Error;
+
+// データを変更する操作を取り消し可能な形で表現する
+// 各操作はinverseで逆の操作を返し、applyで実際にリストへ反映する
+// 取り消し(undo)は逆の操作をapplyすることで行う
+pub enum Change {
+    InsertPerson {
+        id: PersonId,
+        person: Person,
+    },
+    TakePerson {
+        id: PersonId,
+        person: Person,
+    },
+    DeletePerson {
+        id: PersonId,
+    },
+    RestorePerson {
+        id: PersonId,
+    },
+    // Personは大きいので、他の操作の大きさに合わせてBoxに入れる
+    ReplacePerson {
+        id: PersonId,
+        before: Box<Person>,
+        after: Box<Person>,
+    },
+    InsertDepartment {
+        id: DepartmentId,
+        department: Department,
+    },
+    TakeDepartment {
+        id: DepartmentId,
+        department: Department,
+    },
+    DeleteDepartment {
+        id: DepartmentId,
+    },
+    RestoreDepartment {
+        id: DepartmentId,
+    },
+    RenameDepartment {
+        id: DepartmentId,
+        before: String,
+        after: String,
+    },
+    AddMember {
+        person_id: PersonId,
+        department_id: DepartmentId,
+    },
+    RemoveMember {
+        person_id: PersonId,
+        department_id: DepartmentId,
+    },
+    TransferMember {
+        person_id: PersonId,
+        from_department_id: DepartmentId,
+        to_department_id: DepartmentId,
+    },
+    // 削除済の個人の一時的に無効となっている所属(部署の統合で使用)
+    AddSuspendedMember {
+        person_id: PersonId,
+        department_id: DepartmentId,
+    },
+    RemoveSuspendedMember {
+        person_id: PersonId,
+        department_id: DepartmentId,
+    },
+    // 一括取り込みのように複数の操作をまとめて１つの操作として扱う
+    Batch {
+        label: String,
+        changes: Vec<Change>,
+    },
+}
+
+impl Change {
+    // 操作をリストに反映する
+    // 個人や部署の削除、復元に伴う所属の無効化、有効化もここで行う
+    pub fn apply(
+        &self,
+        person_list: &mut PersonList,
+        department_list: &mut DepartmentList,
+        member_list: &mut MemberList,
+    ) -> Result<(), Box<dyn Error>> {
+        match self {
+            Change::InsertPerson { id, person } => {
+                person_list.add_person(person.clone(), Some(*id))?;
+            }
+            Change::TakePerson { id, .. } => {
+                person_list.take_person(*id)?;
+            }
+            Change::DeletePerson { id } => {
+                person_list.delete_person(*id)?;
+                member_list.suspend_person(*id);
+            }
+            Change::RestorePerson { id } => {
+                person_list.restore_person(*id)?;
+                member_list.reactivate(person_list, department_list);
+            }
+            Change::ReplacePerson { id, after, .. } => {
+                person_list.replace_person(*id, (**after).clone())?;
+            }
+            Change::InsertDepartment { id, department } => {
+                department_list.add_department(department.clone(), Some(*id))?;
+            }
+            Change::TakeDepartment { id, .. } => {
+                department_list.take_department(*id)?;
+            }
+            Change::DeleteDepartment { id } => {
+                department_list.delete_department(*id)?;
+                member_list.suspend_department(*id);
+            }
+            Change::RestoreDepartment { id } => {
+                department_list.restore_department(*id)?;
+                member_list.reactivate(person_list, department_list);
+            }
+            Change::RenameDepartment { id, after, .. } => {
+                department_list.rename_department(*id, after)?;
+            }
+            Change::AddMember {
+                person_id,
+                department_id,
+            } => {
+                member_list.add_person_to_department(
+                    *person_id,
+                    *department_id,
+                    person_list,
+                    department_list,
+                )?;
+            }
+            Change::RemoveMember {
+                person_id,
+                department_id,
+            } => {
+                member_list.remove_person_from_department(
+                    *person_id,
+                    *department_id,
+                    person_list,
+                    department_list,
+                )?;
+            }
+            Change::TransferMember {
+                person_id,
+                from_department_id,
+                to_department_id,
+            } => {
+                member_list.transfer_person(
+                    *person_id,
+                    *from_department_id,
+                    *to_department_id,
+                    person_list,
+                    department_list,
+                )?;
+            }
+            Change::AddSuspendedMember {
+                person_id,
+                department_id,
+            } => {
+                member_list.add_suspended_member(
+                    *person_id,
+                    *department_id,
+                    person_list,
+                    department_list,
+                )?;
+            }
+            Change::RemoveSuspendedMember {
+                person_id,
+                department_id,
+            } => {
+                member_list.remove_suspended_member(*person_id, *department_id)?;
+            }
+            Change::Batch { changes, .. } => {
+                // 途中で失敗した場合は適用済の操作を逆順に取り消して元の状態に戻す
+                for (index, change) in changes.iter().enumerate() {
+                    if let Err(e) = change.apply(person_list, department_list, member_list) {
+                        for applied in changes[..index].iter().rev() {
+                            applied
+                                .inverse()
+                                .apply(person_list, department_list, member_list)?;
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // 逆の操作を返す
+    pub fn inverse(&self) -> Change {
+        match self {
+            Change::InsertPerson { id, person } => Change::TakePerson {
+                id: *id,
+                person: person.clone(),
+            },
+            Change::TakePerson { id, person } => Change::InsertPerson {
+                id: *id,
+                person: person.clone(),
+            },
+            Change::DeletePerson { id } => Change::RestorePerson { id: *id },
+            Change::RestorePerson { id } => Change::DeletePerson { id: *id },
+            Change::ReplacePerson { id, before, after } => Change::ReplacePerson {
+                id: *id,
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Change::InsertDepartment { id, department } => Change::TakeDepartment {
+                id: *id,
+                department: department.clone(),
+            },
+            Change::TakeDepartment { id, department } => Change::InsertDepartment {
+                id: *id,
+                department: department.clone(),
+            },
+            Change::DeleteDepartment { id } => Change::RestoreDepartment { id: *id },
+            Change::RestoreDepartment { id } => Change::DeleteDepartment { id: *id },
+            Change::RenameDepartment { id, before, after } => Change::RenameDepartment {
+                id: *id,
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Change::AddMember {
+                person_id,
+                department_id,
+            } => Change::RemoveMember {
+                person_id: *person_id,
+                department_id: *department_id,
+            },
+            Change::RemoveMember {
+                person_id,
+                department_id,
+            } => Change::AddMember {
+                person_id: *person_id,
+                department_id: *department_id,
+            },
+            Change::TransferMember {
+                person_id,
+                from_department_id,
+                to_department_id,
+            } => Change::TransferMember {
+                person_id: *person_id,
+                from_department_id: *to_department_id,
+                to_department_id: *from_department_id,
+            },
+            Change::AddSuspendedMember {
+                person_id,
+                department_id,
+            } => Change::RemoveSuspendedMember {
+                person_id: *person_id,
+                department_id: *department_id,
+            },
+            Change::RemoveSuspendedMember {
+                person_id,
+                department_id,
+            } => Change::AddSuspendedMember {
+                person_id: *person_id,
+                department_id: *department_id,
+            },
+            Change::Batch { label, changes } => Change::Batch {
+                label: label.clone(),
+                changes: changes
+                    .iter()
+                    .rev()
+                    .map(|change| change.inverse())
+                    .collect(),
+            },
+        }
+    }
+
+    // 監査ログや画面表示用に操作の内容を文字列で返す
+    pub fn description(&self) -> String {
+        match self {
+            Change::InsertPerson { id, person } => {
+                format!("create person {}: {}", id, person.name())
+            }
+            Change::TakePerson { id, person } => {
+                format!("remove person {}: {}", id, person.name())
+            }
+            Change::DeletePerson { id } => format!("delete person {}", id),
+            Change::RestorePerson { id } => format!("restore person {}", id),
+            Change::ReplacePerson { id, before, after } => format!(
+                "rename person {}: {} -> {}",
+                id,
+                before.name(),
+                after.name()
+            ),
+            Change::InsertDepartment { id, department } => {
+                format!("create department {}: {}", id, department.name())
+            }
+            Change::TakeDepartment { id, department } => {
+                format!("remove department {}: {}", id, department.name())
+            }
+            Change::DeleteDepartment { id } => format!("delete department {}", id),
+            Change::RestoreDepartment { id } => {
+                format!("restore department {}", id)
+            }
+            Change::RenameDepartment { id, before, after } => {
+                format!("rename department {}: {} -> {}", id, before, after)
+            }
+            Change::AddMember {
+                person_id,
+                department_id,
+            } => format!("add person {} to department {}", person_id, department_id),
+            Change::RemoveMember {
+                person_id,
+                department_id,
+            } => format!(
+                "remove person {} from department {}",
+                person_id, department_id
+            ),
+            Change::TransferMember {
+                person_id,
+                from_department_id,
+                to_department_id,
+            } => format!(
+                "transfer person {} from department {} to department {}",
+                person_id, from_department_id, to_department_id
+            ),
+            Change::AddSuspendedMember {
+                person_id,
+                department_id,
+            } => format!(
+                "add suspended membership of person {} to department {}",
+                person_id, department_id
+            ),
+            Change::RemoveSuspendedMember {
+                person_id,
+                department_id,
+            } => format!(
+                "remove suspended membership of person {} from department {}",
+                person_id, department_id
+            ),
+            Change::Batch { label, changes } => {
+                format!("{} ({} change(s))", label, changes.len())
+            }
+        }
+    }
+}
+
+// 取り消し(undo)とやり直し(redo)のための操作履歴
+// 新しい操作を記録するとやり直しの履歴は破棄される
+// 履歴はMAX_LENGTH件を超えると古いものから捨てる
+pub struct History {
+    undo_stack: Vec<Change>,
+    redo_stack: Vec<Change>,
+}
+
+impl History {
+    const MAX_LENGTH: usize = 100;
+
+    pub fn new() -> History {
+        return History {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        };
+    }
+
+    // 適用済の操作を履歴に記録する
+    pub fn record(&mut self, change: Change) {
+        self.redo_stack.clear();
+        self.undo_stack.push(change);
+        if self.undo_stack.len() > Self::MAX_LENGTH {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    // 直前の操作を取り消し、取り消した操作を返す
+    // 取り消しに失敗した場合は履歴を変更せずにエラーを返す
+    pub fn undo(
+        &mut self,
+        person_list: &mut PersonList,
+        department_list: &mut DepartmentList,
+        member_list: &mut MemberList,
+    ) -> Result<&Change, Box<dyn Error>> {
+        let change = match self.undo_stack.pop() {
+            Some(change) => change,
+            None => return Err("History::undo : There is nothing to undo!".into()),
+        };
+        if let Err(e) = change
+            .inverse()
+            .apply(person_list, department_list, member_list)
+        {
+            self.undo_stack.push(change);
+            return Err(e);
+        }
+        self.redo_stack.push(change);
+        return Ok(&self.redo_stack[self.redo_stack.len() - 1]);
+    }
+
+    // 直前に取り消した操作をやり直し、やり直した操作を返す
+    // やり直しに失敗した場合は履歴を変更せずにエラーを返す
+    pub fn redo(
+        &mut self,
+        person_list: &mut PersonList,
+        department_list: &mut DepartmentList,
+        member_list: &mut MemberList,
+    ) -> Result<&Change, Box<dyn Error>> {
+        let change = match self.redo_stack.pop() {
+            Some(change) => change,
+            None => return Err("History::redo : There is nothing to redo!".into()),
+        };
+        if let Err(e) = change.apply(person_list, department_list, member_list) {
+            self.redo_stack.push(change);
+            return Err(e);
+        }
+        self.undo_stack.push(change);
+        return Ok(&self.undo_stack[self.undo_stack.len() - 1]);
+    }
+
+    // 履歴を全て破棄する
+    // 完全削除(purge)のように取り消せない操作の後に使用する
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+impl Default for History {
+    fn default() -> History {
+        return History::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Lists {
+        person_list: PersonList,
+        department_list: DepartmentList,
+        member_list: MemberList,
+    }
+
+    impl Lists {
+        fn new() -> Lists {
+            return Lists {
+                person_list: PersonList::new(),
+                department_list: DepartmentList::new(),
+                member_list: MemberList::new(),
+            };
+        }
+
+        fn apply(&mut self, history: &mut History, change: Change) {
+            change
+                .apply(
+                    &mut self.person_list,
+                    &mut self.department_list,
+                    &mut self.member_list,
+                )
+                .unwrap();
+            history.record(change);
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_create_person_ok() {
+        let mut lists = Lists::new();
+        let mut history = History::new();
+        let id = lists.person_list.next_id();
+        let person = Person::new("Potter", None).unwrap();
+        lists.apply(
+            &mut history,
+            Change::InsertPerson {
+                id: id,
+                person: person,
+            },
+        );
+
+        history
+            .undo(
+                &mut lists.person_list,
+                &mut lists.department_list,
+                &mut lists.member_list,
+            )
+            .unwrap();
+        assert!(lists.person_list.person(id).is_err());
+
+        history
+            .redo(
+                &mut lists.person_list,
+                &mut lists.department_list,
+                &mut lists.member_list,
+            )
+            .unwrap();
+        assert!(lists.person_list.person(id).is_ok());
+    }
+
+    #[test]
+    fn undo_delete_department_restores_members_ok() {
+        let mut lists = Lists::new();
+        let mut history = History::new();
+        let person_id = PersonId::new(1);
+        let department_id = DepartmentId::new(1);
+        lists
+            .person_list
+            .add_person(Person::new("Potter", None).unwrap(), Some(person_id))
+            .unwrap();
+        lists
+            .department_list
+            .add_department(Department::new("Defence").unwrap(), Some(department_id))
+            .unwrap();
+        lists.apply(
+            &mut history,
+            Change::AddMember {
+                person_id: person_id,
+                department_id: department_id,
+            },
+        );
+        lists.apply(&mut history, Change::DeleteDepartment { id: department_id });
+
+        history
+            .undo(
+                &mut lists.person_list,
+                &mut lists.department_list,
+                &mut lists.member_list,
+            )
+            .unwrap();
+        let members = lists
+            .member_list
+            .person_list_by_department(department_id, &lists.person_list)
+            .unwrap();
+        assert_eq!(members.len(), 1);
+    }
+
+    #[test]
+    fn batch_failure_rolls_back_ok() {
+        let mut lists = Lists::new();
+        let id = PersonId::new(1);
+        let change = Change::Batch {
+            label: String::from("import"),
+            changes: vec![
+                Change::InsertPerson {
+                    id: id,
+                    person: Person::new("Potter", None).unwrap(),
+                },
+                Change::AddMember {
+                    person_id: id,
+                    department_id: DepartmentId::new(1),
+                },
+            ],
+        };
+        assert!(change
+            .apply(
+                &mut lists.person_list,
+                &mut lists.department_list,
+                &mut lists.member_list,
+            )
+            .is_err());
+        assert!(lists.person_list.person(id).is_err());
+    }
+
+    #[test]
+    fn undo_empty_history_err() {
+        let mut lists = Lists::new();
+        let mut history = History::new();
+        assert!(history
+            .undo(
+                &mut lists.person_list,
+                &mut lists.department_list,
+                &mut lists.member_list,
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,1241 @@
+use super::super::domain::audit::AuditEvent;
+use super::super::domain::department::id::DepartmentId;
+use super::super::domain::department::list::DepartmentList;
+use super::super::domain::department::Department;
+use super::super::domain::error::{DomainError, ErrorKind};
+use super::super::domain::member::MemberList;
+use super::super::domain::person::id::PersonId;
+use super::super::domain::person::list::PersonList;
+use super::super::domain::person::Person;
+use super::super::domain::timestamp::Timestamp;
+use super::super::repository::audit::AuditRepository;
+use super::super::repository::Repositories;
+use super::export::{self, ExportFormat};
+use super::expression::Expression;
+use super::filter::PersonFilter;
+use super::history::{Change, History};
+use super::import::{self, ImportTarget};
+use super::org_chart::{self, ChartFormat, ChartOptions};
+use super::stats::{self, Statistics};
+use std::collections::BTreeSet;
+use std::error::Error;
+
+// 読み込んだ時点の保存先の内容(個人、部署、所属)
+type Fingerprint = [Option<u64>; 3];
+
+// 個人の一覧の並び順
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum PersonOrder {
+    Id,
+    Reading,
+}
+
+// 結果として返す個人(IDと内容の複製)
+#[derive(Clone, Debug)]
+pub struct PersonEntry {
+    pub id: PersonId,
+    pub person: Person,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct DepartmentEntry {
+    pub id: DepartmentId,
+    pub name: String,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct DeletedPerson {
+    pub id: PersonId,
+    pub name: String,
+    pub deleted_at: Timestamp,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct DeletedDepartment {
+    pub id: DepartmentId,
+    pub name: String,
+    pub deleted_at: Timestamp,
+}
+
+// 部署と所属している個人
+#[derive(Clone, Debug)]
+pub struct DepartmentMembers {
+    pub department: DepartmentEntry,
+    pub members: Vec<PersonEntry>,
+}
+
+// 全ての部署の所属者と、どの部署にも所属していない個人
+#[derive(Clone, Debug)]
+pub struct Organization {
+    pub departments: Vec<DepartmentMembers>,
+    pub unassigned: Vec<PersonEntry>,
+}
+
+// 完全削除した(dry_runの場合は完全削除の対象となる)個人と部署
+#[derive(PartialEq, Clone, Debug)]
+pub struct Purged {
+    pub persons: Vec<DeletedPerson>,
+    pub departments: Vec<DeletedDepartment>,
+}
+
+// 部署の統合の結果
+// movedは統合先へ移した人数、duplicatedは統合先に既に所属していたため統合元の所属を外しただけの人数
+#[derive(PartialEq, Clone, Debug)]
+pub struct MergedDepartments {
+    pub moved: usize,
+    pub duplicated: usize,
+}
+
+// 取り込みの結果
+// errorsは誤りのある行の行番号とエラーメッセージ
+// appliedは実際にリストへ反映して保存したかどうか
+#[derive(PartialEq, Clone, Debug)]
+pub struct ImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub errors: Vec<(usize, String)>,
+    pub applied: bool,
+}
+
+// 個人の変更内容
+// Noneの項目は変更しない. first_nameをSome(None)とすると名を削除する
+// attributesは (キー, 値) の組で、値が空文字列の項目は削除する
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct PersonChanges {
+    pub last_name: Option<String>,
+    pub first_name: Option<Option<String>>,
+    pub attributes: Vec<(String, String)>,
+}
+
+// 個人、部署、所属のリストとリポジトリを持ち、アプリケーションの操作を型付きのメソッドとして提供する
+// REPL(service::run)とREST API(server)はどちらもこの構造体を通してデータを操作する
+// データを変更する操作は、リストへの反映、保存、監査ログへの記録、取り消し履歴への記録をまとめて行う
+pub struct HrService {
+    repositories: Repositories,
+    audit_repository: Box<dyn AuditRepository>,
+    read_only: bool,
+    person_list: PersonList,
+    department_list: DepartmentList,
+    member_list: MemberList,
+    history: History,
+    fingerprint: Fingerprint,
+}
+
+impl HrService {
+    // リポジトリから全てのデータを読み込む
+    // read_onlyの場合はデータを変更する操作をエラーとする
+    pub fn new(
+        repositories: Repositories,
+        audit_repository: Box<dyn AuditRepository>,
+        read_only: bool,
+    ) -> Result<HrService, Box<dyn Error>> {
+        let mut service = HrService {
+            repositories: repositories,
+            audit_repository: audit_repository,
+            read_only: read_only,
+            person_list: PersonList::new(),
+            department_list: DepartmentList::new(),
+            member_list: MemberList::new(),
+            history: History::new(),
+            fingerprint: [None, None, None],
+        };
+        service.reload()?;
+        return Ok(service);
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        return self.read_only;
+    }
+
+    // 保存先から読み込み直す
+    // 取り消しの履歴は残すので、保存先が他のプログラムに変更された場合はclear_historyも呼ぶ
+    pub fn reload(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut person_list = PersonList::new();
+        let mut department_list = DepartmentList::new();
+        let mut member_list = MemberList::new();
+        if let Err(e) = self.repositories.person.read_all_data(&mut person_list) {
+            return Err(format!("person file: {}", e).into());
+        }
+        if let Err(e) = self
+            .repositories
+            .department
+            .read_all_data(&mut department_list)
+        {
+            return Err(format!("department file: {}", e).into());
+        }
+        if let Err(e) =
+            self.repositories
+                .member
+                .read_all_data(&mut member_list, &person_list, &department_list)
+        {
+            return Err(format!("member file: {}", e).into());
+        }
+        self.person_list = person_list;
+        self.department_list = department_list;
+        self.member_list = member_list;
+        self.fingerprint = self.read_fingerprint()?;
+        return Ok(());
+    }
+
+    fn read_fingerprint(&self) -> Result<Fingerprint, Box<dyn Error>> {
+        return Ok([
+            self.repositories.person.fingerprint()?,
+            self.repositories.department.fingerprint()?,
+            self.repositories.member.fingerprint()?,
+        ]);
+    }
+
+    // 読み込んだ後で他のプログラムが保存先を変更したかどうかを返す
+    pub fn has_external_change(&self) -> Result<bool, Box<dyn Error>> {
+        return Ok(self.read_fingerprint()? != self.fingerprint);
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    fn check_writable(&self) -> Result<(), Box<dyn Error>> {
+        if self.read_only {
+            let message = "HrService : This session is read-only!";
+            return Err(DomainError::new(ErrorKind::ReadOnly, message).into());
+        }
+        return Ok(());
+    }
+
+    // 全てのリストを保存する
+    // 保存に失敗した場合は保存先から読み込み直して、メモリ上の変更を破棄する
+    fn save(&mut self) -> Result<(), Box<dyn Error>> {
+        let result = self
+            .repositories
+            .person
+            .overwrite_all_data(&mut self.person_list)
+            .and_then(|_| {
+                self.repositories
+                    .department
+                    .overwrite_all_data(&mut self.department_list)
+            })
+            .and_then(|_| {
+                self.repositories
+                    .member
+                    .overwrite_all_data(&mut self.member_list)
+            });
+        if let Err(e) = result {
+            self.reload()?;
+            return Err(e);
+        }
+        self.fingerprint = self.read_fingerprint()?;
+        return Ok(());
+    }
+
+    // 変更をリストに反映して保存し、監査ログと取り消しの履歴に記録する
+    fn commit(&mut self, change: Change, action: &str) -> Result<(), Box<dyn Error>> {
+        self.check_writable()?;
+        change.apply(
+            &mut self.person_list,
+            &mut self.department_list,
+            &mut self.member_list,
+        )?;
+        self.save()?;
+        self.audit_repository
+            .append_event(&AuditEvent::new(action, &change.description()))?;
+        self.history.record(change);
+        return Ok(());
+    }
+
+    // IDまたは名前から個人を特定する
+    pub fn resolve_person(&self, key: &str) -> Result<PersonId, Box<dyn Error>> {
+        return self.person_list.resolve_person(key);
+    }
+
+    // IDまたは名前から部署を特定する
+    pub fn resolve_department(&self, key: &str) -> Result<DepartmentId, Box<dyn Error>> {
+        return self.department_list.resolve_department(key);
+    }
+
+    fn person_ids(&mut self) -> Vec<PersonId> {
+        return (&mut self.person_list).map(|(id, _name)| id).collect();
+    }
+
+    fn person_entry(&self, id: PersonId) -> Result<PersonEntry, Box<dyn Error>> {
+        let person = self.person_list.person(id)?;
+        return Ok(PersonEntry {
+            id: id,
+            person: person.clone(),
+        });
+    }
+
+    fn department_entry(&self, id: DepartmentId) -> Result<DepartmentEntry, Box<dyn Error>> {
+        let department = self.department_list.department(id)?;
+        return Ok(DepartmentEntry {
+            id: id,
+            name: department.name(),
+        });
+    }
+
+    // 削除済でない全ての個人
+    pub fn persons(&mut self, order: PersonOrder) -> Result<Vec<PersonEntry>, Box<dyn Error>> {
+        let ids = match order {
+            PersonOrder::Id => self.person_ids(),
+            PersonOrder::Reading => self.person_list.ids_sorted_by_reading(),
+        };
+        return ids.into_iter().map(|id| self.person_entry(id)).collect();
+    }
+
+    pub fn person(&self, id: PersonId) -> Result<PersonEntry, Box<dyn Error>> {
+        return self.person_entry(id);
+    }
+
+    // 個人が所属している部署(IDの小さい順)
+    pub fn person_departments(&self, id: PersonId) -> Result<Vec<DepartmentEntry>, Box<dyn Error>> {
+        self.person_list.person(id)?;
+        return self
+            .member_list
+            .department_ids(id)
+            .into_iter()
+            .map(|department_id| self.department_entry(department_id))
+            .collect();
+    }
+
+    // 名前で検索した個人(一致度の高い順)
+    pub fn find_persons(&self, text: &str) -> Result<Vec<PersonEntry>, Box<dyn Error>> {
+        return self
+            .person_list
+            .find_persons(text)
+            .into_iter()
+            .map(|(id, _level)| self.person_entry(id))
+            .collect();
+    }
+
+    // 絞り込み条件を全て満たす個人だけを、元の順番のまま返す
+    // 条件で指定した部署が存在しない場合はエラーとする
+    pub fn filter_persons(
+        &self,
+        persons: Vec<PersonEntry>,
+        filters: &[PersonFilter],
+    ) -> Result<Vec<PersonEntry>, Box<dyn Error>> {
+        let mut department_ids: Vec<Option<DepartmentId>> = Vec::new();
+        for filter in filters.iter() {
+            match filter {
+                PersonFilter::Department(key) => {
+                    department_ids.push(Some(self.resolve_department(key)?))
+                }
+                _ => department_ids.push(None),
+            }
+        }
+        let matches = |entry: &PersonEntry| {
+            let memberships = self.member_list.department_ids(entry.id).len();
+            return filters
+                .iter()
+                .zip(department_ids.iter())
+                .all(|pair| match pair {
+                    (PersonFilter::NameContains(text), _) => {
+                        let person_name = entry.person.person_name();
+                        entry.person.name().to_lowercase().contains(text)
+                            || person_name.reading().to_lowercase().contains(text)
+                    }
+                    (PersonFilter::Department(_), Some(department_id)) => {
+                        self.member_list.is_member(entry.id, *department_id)
+                    }
+                    (PersonFilter::Department(_), None) => false,
+                    (PersonFilter::Unassigned, _) => memberships == 0,
+                    (PersonFilter::Memberships(comparison, count), _) => {
+                        comparison.matches(memberships, *count)
+                    }
+                });
+        };
+        return Ok(persons.into_iter().filter(|entry| matches(entry)).collect());
+    }
+
+    // 所属の集合演算の式を計算し、当てはまる個人をIDの小さい順に返す
+    // 式で指定した部署が存在しない場合はエラーとする
+    pub fn evaluate(
+        &mut self,
+        expression: &Expression,
+    ) -> Result<Vec<PersonEntry>, Box<dyn Error>> {
+        let all: BTreeSet<PersonId> = self.person_ids().into_iter().collect();
+        let ids = self.evaluate_set(expression, &all)?;
+        return ids.into_iter().map(|id| self.person_entry(id)).collect();
+    }
+
+    fn evaluate_set(
+        &self,
+        expression: &Expression,
+        all: &BTreeSet<PersonId>,
+    ) -> Result<BTreeSet<PersonId>, Box<dyn Error>> {
+        match expression {
+            Expression::Department(key) => {
+                let department_id = self.resolve_department(key)?;
+                return Ok(self
+                    .member_list
+                    .member_ids(department_id)
+                    .into_iter()
+                    .filter(|id| all.contains(id))
+                    .collect());
+            }
+            Expression::Unassigned => {
+                return Ok(all
+                    .iter()
+                    .filter(|id| self.member_list.department_ids(**id).is_empty())
+                    .copied()
+                    .collect())
+            }
+            Expression::All => return Ok(all.clone()),
+            Expression::Intersection(left, right) => {
+                let right = self.evaluate_set(right, all)?;
+                return Ok(&self.evaluate_set(left, all)? & &right);
+            }
+            Expression::Union(left, right) => {
+                let right = self.evaluate_set(right, all)?;
+                return Ok(&self.evaluate_set(left, all)? | &right);
+            }
+            Expression::Difference(left, right) => {
+                let right = self.evaluate_set(right, all)?;
+                return Ok(&self.evaluate_set(left, all)? - &right);
+            }
+        }
+    }
+
+    // 削除済でない全ての部署(IDの小さい順)
+    pub fn departments(&mut self) -> Vec<DepartmentEntry> {
+        return (&mut self.department_list)
+            .map(|(id, name)| DepartmentEntry { id: id, name: name })
+            .collect();
+    }
+
+    pub fn department(&self, id: DepartmentId) -> Result<DepartmentEntry, Box<dyn Error>> {
+        return self.department_entry(id);
+    }
+
+    // 部署に所属している個人(読み仮名順)
+    pub fn department_members(&self, id: DepartmentId) -> Result<Vec<PersonEntry>, Box<dyn Error>> {
+        self.department_list.department(id)?;
+        let mut members = self
+            .member_list
+            .member_ids(id)
+            .into_iter()
+            .map(|person_id| self.person_entry(person_id))
+            .collect::<Result<Vec<PersonEntry>, Box<dyn Error>>>()?;
+        members.sort_by_key(|entry| entry.person.person_name().sort_key());
+        return Ok(members);
+    }
+
+    // 名前で検索した部署(一致度の高い順)
+    pub fn find_departments(&self, text: &str) -> Result<Vec<DepartmentEntry>, Box<dyn Error>> {
+        return self
+            .department_list
+            .find_departments(text)
+            .into_iter()
+            .map(|(id, _level)| self.department_entry(id))
+            .collect();
+    }
+
+    // 部署ごとの所属者(部署、個人ともにIDの小さい順)
+    pub fn organization(&mut self) -> Result<Organization, Box<dyn Error>> {
+        let persons = self.persons(PersonOrder::Id)?;
+        let mut departments: Vec<DepartmentMembers> = Vec::new();
+        for department in self.departments() {
+            let members = persons
+                .iter()
+                .filter(|entry| self.member_list.is_member(entry.id, department.id))
+                .cloned()
+                .collect();
+            departments.push(DepartmentMembers {
+                department: department,
+                members: members,
+            });
+        }
+        let unassigned = persons
+            .into_iter()
+            .filter(|entry| {
+                !departments.iter().any(|department| {
+                    department
+                        .members
+                        .iter()
+                        .any(|member| member.id == entry.id)
+                })
+            })
+            .collect();
+        return Ok(Organization {
+            departments: departments,
+            unassigned: unassigned,
+        });
+    }
+
+    pub fn deleted_persons(&self) -> Vec<DeletedPerson> {
+        return self
+            .person_list
+            .deleted_persons()
+            .into_iter()
+            .map(|(id, person, deleted_at)| DeletedPerson {
+                id: id,
+                name: person.name(),
+                deleted_at: deleted_at,
+            })
+            .collect();
+    }
+
+    pub fn deleted_departments(&self) -> Vec<DeletedDepartment> {
+        return self
+            .department_list
+            .deleted_departments()
+            .into_iter()
+            .map(|(id, department, deleted_at)| DeletedDepartment {
+                id: id,
+                name: department.name(),
+                deleted_at: deleted_at,
+            })
+            .collect();
+    }
+
+    // attributesは (キー, 値) の組(Person::ATTRIBUTE_KEYSを参照)
+    pub fn create_person(
+        &mut self,
+        last_name: &str,
+        first_name: Option<&str>,
+        attributes: &[(String, String)],
+    ) -> Result<PersonEntry, Box<dyn Error>> {
+        let mut person = Person::new(last_name, first_name)?;
+        for (key, value) in attributes.iter() {
+            person.set_attribute(key, value)?;
+        }
+        let id = self.person_list.next_id();
+        let change = Change::InsertPerson {
+            id: id,
+            person: person,
+        };
+        self.commit(change, "create_person")?;
+        return self.person_entry(id);
+    }
+
+    // 名前と任意項目のうち、changesで指定した項目だけを変更する
+    pub fn update_person(
+        &mut self,
+        id: PersonId,
+        changes: PersonChanges,
+    ) -> Result<PersonEntry, Box<dyn Error>> {
+        let before = self.person_list.person(id)?.clone();
+        // 複製に対して変更を行うことで、途中で失敗して一部だけ変更されることを防ぐ
+        let mut after = before.clone();
+        if changes.last_name.is_some() || changes.first_name.is_some() {
+            let person_name = before.person_name();
+            let last_name = changes
+                .last_name
+                .as_deref()
+                .unwrap_or(person_name.last_name());
+            let first_name = match &changes.first_name {
+                Some(first_name) => first_name.as_deref(),
+                None => person_name.first_name(),
+            };
+            after.rename(last_name, first_name)?;
+        }
+        for (key, value) in changes.attributes.iter() {
+            after.set_attribute(key, value)?;
+        }
+        let change = Change::ReplacePerson {
+            id: id,
+            before: Box::new(before),
+            after: Box::new(after),
+        };
+        self.commit(change, "rename_person")?;
+        return self.person_entry(id);
+    }
+
+    pub fn delete_person(&mut self, id: PersonId) -> Result<(), Box<dyn Error>> {
+        return self.commit(Change::DeletePerson { id: id }, "delete_person");
+    }
+
+    pub fn restore_person(&mut self, id: PersonId) -> Result<(), Box<dyn Error>> {
+        return self.commit(Change::RestorePerson { id: id }, "restore_person");
+    }
+
+    pub fn create_department(&mut self, name: &str) -> Result<DepartmentEntry, Box<dyn Error>> {
+        let department = Department::new(name)?;
+        let id = self.department_list.next_id();
+        let change = Change::InsertDepartment {
+            id: id,
+            department: department,
+        };
+        self.commit(change, "create_department")?;
+        return self.department_entry(id);
+    }
+
+    pub fn rename_department(
+        &mut self,
+        id: DepartmentId,
+        name: &str,
+    ) -> Result<DepartmentEntry, Box<dyn Error>> {
+        let before = self.department_list.department(id)?.name();
+        let change = Change::RenameDepartment {
+            id: id,
+            before: before,
+            after: String::from(name),
+        };
+        self.commit(change, "rename_department")?;
+        return self.department_entry(id);
+    }
+
+    pub fn delete_department(&mut self, id: DepartmentId) -> Result<(), Box<dyn Error>> {
+        return self.commit(Change::DeleteDepartment { id: id }, "delete_department");
+    }
+
+    pub fn restore_department(&mut self, id: DepartmentId) -> Result<(), Box<dyn Error>> {
+        return self.commit(Change::RestoreDepartment { id: id }, "restore_department");
+    }
+
+    pub fn add_member(
+        &mut self,
+        person_id: PersonId,
+        department_id: DepartmentId,
+    ) -> Result<(), Box<dyn Error>> {
+        let change = Change::AddMember {
+            person_id: person_id,
+            department_id: department_id,
+        };
+        return self.commit(change, "add_person");
+    }
+
+    pub fn remove_member(
+        &mut self,
+        person_id: PersonId,
+        department_id: DepartmentId,
+    ) -> Result<(), Box<dyn Error>> {
+        let change = Change::RemoveMember {
+            person_id: person_id,
+            department_id: department_id,
+        };
+        return self.commit(change, "remove_person");
+    }
+
+    // 所属を別の部署へ移す
+    // 所属の削除と追加を１つの操作として行うので、取り消しも１回で元に戻る
+    pub fn transfer_member(
+        &mut self,
+        person_id: PersonId,
+        from_department_id: DepartmentId,
+        to_department_id: DepartmentId,
+    ) -> Result<(), Box<dyn Error>> {
+        let change = Change::TransferMember {
+            person_id: person_id,
+            from_department_id: from_department_id,
+            to_department_id: to_department_id,
+        };
+        return self.commit(change, "transfer_person");
+    }
+
+    // 統合元の部署の所属を全て統合先の部署へ移し、統合元の部署を削除する
+    // 統合先に既に所属している人は統合元の所属を外すだけとする
+    // 全ての変更を１つの操作として適用するので、途中で失敗した場合は何も変更しない
+    pub fn merge_departments(
+        &mut self,
+        source_id: DepartmentId,
+        target_id: DepartmentId,
+    ) -> Result<MergedDepartments, Box<dyn Error>> {
+        let source = self.department_entry(source_id)?;
+        let target = self.department_entry(target_id)?;
+        if source_id == target_id {
+            return Err(DomainError::invalid(
+                "HrService::merge_departments : The source and destination are the same department!",
+            )
+            .into());
+        }
+        let mut changes: Vec<Change> = Vec::new();
+        let mut merged = MergedDepartments {
+            moved: 0,
+            duplicated: 0,
+        };
+        for person_id in self.member_list.member_ids(source_id) {
+            if self.member_list.is_member(person_id, target_id) {
+                changes.push(Change::RemoveMember {
+                    person_id: person_id,
+                    department_id: source_id,
+                });
+                merged.duplicated += 1;
+            } else {
+                changes.push(Change::TransferMember {
+                    person_id: person_id,
+                    from_department_id: source_id,
+                    to_department_id: target_id,
+                });
+                merged.moved += 1;
+            }
+        }
+        // 削除済の個人の所属も移し、復元した時に統合先の所属として有効に戻るようにする
+        // 統合先に既に所属がある場合は統合元の所属を取り除く
+        // 削除済の個人は一覧に表示されないので、件数には含めない
+        // 削除済の個人の所属は全て無効となっているので、統合先の所属も無効な所属から探す
+        let suspended_members = self.member_list.suspended_members();
+        for (department_id, person_id) in suspended_members.iter().copied() {
+            if department_id != source_id {
+                continue;
+            }
+            if suspended_members.contains(&(target_id, person_id)) {
+                changes.push(Change::RemoveSuspendedMember {
+                    person_id: person_id,
+                    department_id: source_id,
+                });
+            } else {
+                changes.push(Change::TransferMember {
+                    person_id: person_id,
+                    from_department_id: source_id,
+                    to_department_id: target_id,
+                });
+            }
+        }
+        changes.push(Change::DeleteDepartment { id: source_id });
+        let change = Change::Batch {
+            label: format!(
+                "merge department {}: {} into {}: {}",
+                source_id, source.name, target_id, target.name
+            ),
+            changes: changes,
+        };
+        self.commit(change, "merge_departments")?;
+        return Ok(merged);
+    }
+
+    // 新しい部署を作成し、元の部署から指定した人の所属を移す
+    // 指定した人は全て元の部署に所属している必要がある(同じ人を重ねて指定した場合は１回とみなす)
+    // 全ての変更を１つの操作として適用するので、途中で失敗した場合は部署も作成しない
+    pub fn split_department(
+        &mut self,
+        source_id: DepartmentId,
+        name: &str,
+        person_ids: &[PersonId],
+    ) -> Result<DepartmentEntry, Box<dyn Error>> {
+        let source = self.department_entry(source_id)?;
+        if person_ids.is_empty() {
+            return Err(DomainError::invalid(
+                "HrService::split_department : No person is specified!",
+            )
+            .into());
+        }
+        let department = Department::new(name)?;
+        let id = self.department_list.next_id();
+        let mut changes = vec![Change::InsertDepartment {
+            id: id,
+            department: department,
+        }];
+        let mut moved: Vec<PersonId> = Vec::new();
+        for person_id in person_ids.iter() {
+            if moved.contains(person_id) {
+                continue;
+            }
+            moved.push(*person_id);
+            changes.push(Change::TransferMember {
+                person_id: *person_id,
+                from_department_id: source_id,
+                to_department_id: id,
+            });
+        }
+        let change = Change::Batch {
+            label: format!(
+                "split department {}: {} into {}: {}",
+                source_id, source.name, id, name
+            ),
+            changes: changes,
+        };
+        self.commit(change, "split_department")?;
+        return self.department_entry(id);
+    }
+
+    // 削除してからdays日以上経過した個人と部署を、所属も含めて完全に削除する
+    // dry_runの場合は対象を返すだけで、リストや保存先は変更しない
+    // 完全削除したデータは元に戻せないので、取り消しの履歴も破棄する
+    pub fn purge(&mut self, days: u64, dry_run: bool) -> Result<Purged, Box<dyn Error>> {
+        let before = Timestamp::now().days_before(days);
+        let purged = Purged {
+            persons: self
+                .deleted_persons()
+                .into_iter()
+                .filter(|person| person.deleted_at <= before)
+                .collect(),
+            departments: self
+                .deleted_departments()
+                .into_iter()
+                .filter(|department| department.deleted_at <= before)
+                .collect(),
+        };
+        if dry_run {
+            return Ok(purged);
+        }
+        self.check_writable()?;
+        for id in self.person_list.purge_deleted_persons(before) {
+            self.member_list.remove_person_entirely(id);
+        }
+        for id in self.department_list.purge_deleted_departments(before) {
+            self.member_list.remove_department_entirely(id);
+        }
+        self.save()?;
+        let detail = format!(
+            "older than {} days: {} person(s), {} department(s)",
+            days,
+            purged.persons.len(),
+            purged.departments.len()
+        );
+        self.audit_repository
+            .append_event(&AuditEvent::new("purge", &detail))?;
+        self.history.clear();
+        return Ok(purged);
+    }
+
+    // CSVの内容を取り込む
+    // 誤りのある行が１行でもある場合とdry_runの場合は、全ての行を取り込まない
+    pub fn import_csv(
+        &mut self,
+        target: ImportTarget,
+        text: &str,
+        label: &str,
+        dry_run: bool,
+    ) -> Result<ImportSummary, Box<dyn Error>> {
+        let result = import::import_csv(
+            target,
+            text,
+            label,
+            &mut self.person_list,
+            &mut self.department_list,
+            &mut self.member_list,
+        )?;
+        let apply = result.errors.is_empty() && !dry_run && result.created + result.updated > 0;
+        if apply {
+            self.commit(result.change, "import_csv")?;
+        }
+        return Ok(ImportSummary {
+            created: result.created,
+            updated: result.updated,
+            unchanged: result.unchanged,
+            errors: result.errors,
+            applied: apply,
+        });
+    }
+
+    // 組織全体をファイルに出力し、出力したファイルのパスを返す
+    pub fn export(
+        &mut self,
+        format: ExportFormat,
+        path: &str,
+        include_deleted: bool,
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        return export::export(
+            format,
+            path,
+            include_deleted,
+            &mut self.person_list,
+            &mut self.department_list,
+            &mut self.member_list,
+        );
+    }
+
+    // 部署と所属者の組織図をDOTまたはMermaidのテキストとして返す
+    pub fn org_chart(
+        &mut self,
+        format: ChartFormat,
+        options: ChartOptions,
+    ) -> Result<String, Box<dyn Error>> {
+        let organization = self.organization()?;
+        return Ok(org_chart::render(&organization, format, options));
+    }
+
+    // 部署ごとの人数や個人ごとの所属部署数などの統計を返す
+    pub fn statistics(&mut self) -> Result<Statistics, Box<dyn Error>> {
+        let persons = self.persons(PersonOrder::Id)?;
+        let departments = self.departments();
+        return Ok(stats::compute(departments, persons, &self.member_list));
+    }
+
+    // 直前の操作を取り消し、取り消した操作の説明を返す
+    pub fn undo(&mut self) -> Result<String, Box<dyn Error>> {
+        self.check_writable()?;
+        let detail = self
+            .history
+            .undo(
+                &mut self.person_list,
+                &mut self.department_list,
+                &mut self.member_list,
+            )?
+            .description();
+        self.save()?;
+        self.audit_repository
+            .append_event(&AuditEvent::new("undo", &detail))?;
+        return Ok(detail);
+    }
+
+    // 直前に取り消した操作をやり直し、やり直した操作の説明を返す
+    pub fn redo(&mut self) -> Result<String, Box<dyn Error>> {
+        self.check_writable()?;
+        let detail = self
+            .history
+            .redo(
+                &mut self.person_list,
+                &mut self.department_list,
+                &mut self.member_list,
+            )?
+            .description();
+        self.save()?;
+        self.audit_repository
+            .append_event(&AuditEvent::new("redo", &detail))?;
+        return Ok(detail);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::datasource::audit::TextFileAudit;
+    use super::super::super::datasource::department::TextFileDepartment;
+    use super::super::super::datasource::member::TextFileMember;
+    use super::super::super::datasource::person::TextFilePerson;
+    use super::super::super::datasource::Storage;
+    use super::*;
+    use std::fs;
+
+    fn with_service(name: &str, read_only: bool, test: fn(&mut HrService)) {
+        let dir = std::env::temp_dir().join(format!("hr_service_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir_path = dir.to_str().unwrap();
+        for file_name in [
+            TextFilePerson::FILE_NAME,
+            TextFileDepartment::FILE_NAME,
+            TextFileMember::FILE_NAME,
+        ] {
+            fs::write(format!("{}{}", dir_path, file_name), "").unwrap();
+        }
+        let mut service = HrService::new(
+            Storage::Text.repositories(dir_path),
+            Box::new(TextFileAudit::new(dir_path)),
+            read_only,
+        )
+        .unwrap();
+        test(&mut service);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn create_and_query_ok() {
+        with_service("query", false, |service| {
+            let attributes = vec![(String::from("nickname"), String::from("Boy"))];
+            let harry = service
+                .create_person("Potter", Some("Harry"), &attributes)
+                .unwrap();
+            let ron = service.create_person("Weasley", Some("Ron"), &[]).unwrap();
+            let gryffindor = service.create_department("Gryffindor").unwrap();
+            service.create_department("Slytherin").unwrap();
+            service.add_member(harry.id, gryffindor.id).unwrap();
+            service.add_member(ron.id, gryffindor.id).unwrap();
+
+            let members = service.department_members(gryffindor.id).unwrap();
+            let names: Vec<String> = members.iter().map(|entry| entry.person.name()).collect();
+            assert_eq!(names, vec!["Potter Harry", "Weasley Ron"]);
+            assert_eq!(
+                service.person_departments(harry.id).unwrap(),
+                vec![gryffindor.clone()]
+            );
+            assert_eq!(
+                service.resolve_department("Gryffindor").unwrap(),
+                gryffindor.id
+            );
+
+            service.delete_person(ron.id).unwrap();
+            let organization = service.organization().unwrap();
+            assert_eq!(organization.departments.len(), 2);
+            assert_eq!(organization.departments[0].members.len(), 1);
+            assert!(organization.unassigned.is_empty());
+            assert_eq!(service.deleted_persons().len(), 1);
+        });
+    }
+
+    #[test]
+    fn update_undo_and_reload_ok() {
+        with_service("update", false, |service| {
+            let harry = service.create_person("Potter", Some("Harry"), &[]).unwrap();
+            let changes = PersonChanges {
+                first_name: Some(None),
+                attributes: vec![(String::from("nickname"), String::from("Boy"))],
+                ..PersonChanges::default()
+            };
+            let updated = service.update_person(harry.id, changes).unwrap();
+            assert_eq!(updated.person.name(), "Potter");
+            assert_eq!(updated.person.person_name().nickname(), Some("Boy"));
+
+            assert_eq!(
+                service.undo().unwrap(),
+                "rename person 1: Potter Harry -> Potter"
+            );
+            service.reload().unwrap();
+            assert_eq!(
+                service.person(harry.id).unwrap().person.name(),
+                "Potter Harry"
+            );
+            assert!(!service.has_external_change().unwrap());
+        });
+    }
+
+    #[test]
+    fn read_only_err() {
+        with_service("read_only", true, |service| {
+            assert!(service.create_department("Gryffindor").is_err());
+            assert!(service.departments().is_empty());
+            assert!(service.purge(0, true).is_ok());
+        });
+    }
+
+    #[test]
+    fn filter_persons_ok() {
+        with_service("filter", false, |service| {
+            let harry = service.create_person("Potter", Some("Harry"), &[]).unwrap();
+            let ron = service.create_person("Weasley", Some("Ron"), &[]).unwrap();
+            let draco = service.create_person("Malfoy", Some("Draco"), &[]).unwrap();
+            let gryffindor = service.create_department("Gryffindor").unwrap();
+            let quidditch = service.create_department("Quidditch").unwrap();
+            service.add_member(harry.id, gryffindor.id).unwrap();
+            service.add_member(harry.id, quidditch.id).unwrap();
+            service.add_member(ron.id, gryffindor.id).unwrap();
+
+            let filter = |service: &mut HrService, expressions: &[&str]| {
+                let filters: Vec<PersonFilter> = expressions
+                    .iter()
+                    .map(|expression| PersonFilter::new(expression).unwrap())
+                    .collect();
+                let persons = service.persons(PersonOrder::Id).unwrap();
+                let persons = service.filter_persons(persons, &filters).unwrap();
+                return persons
+                    .iter()
+                    .map(|entry| entry.id)
+                    .collect::<Vec<PersonId>>();
+            };
+            assert_eq!(filter(service, &["name:WEAS"]), vec![ron.id]);
+            assert_eq!(
+                filter(service, &["dept:Gryffindor"]),
+                vec![harry.id, ron.id]
+            );
+            assert_eq!(filter(service, &["unassigned"]), vec![draco.id]);
+            assert_eq!(filter(service, &["memberships>=2"]), vec![harry.id]);
+            assert_eq!(filter(service, &["dept:1", "memberships<2"]), vec![ron.id]);
+            let filters = vec![PersonFilter::new("dept:Hufflepuff").unwrap()];
+            let persons = service.persons(PersonOrder::Id).unwrap();
+            assert!(service.filter_persons(persons, &filters).is_err());
+        });
+    }
+
+    #[test]
+    fn transfer_member_ok() {
+        with_service("transfer", false, |service| {
+            let harry = service.create_person("Potter", Some("Harry"), &[]).unwrap();
+            let gryffindor = service.create_department("Gryffindor").unwrap();
+            let quidditch = service.create_department("Quidditch").unwrap();
+            service.add_member(harry.id, gryffindor.id).unwrap();
+
+            service
+                .transfer_member(harry.id, gryffindor.id, quidditch.id)
+                .unwrap();
+            assert_eq!(
+                service.person_departments(harry.id).unwrap(),
+                vec![quidditch.clone()]
+            );
+            assert!(service
+                .transfer_member(harry.id, gryffindor.id, quidditch.id)
+                .is_err());
+
+            // 保存した内容を読み込み直しても移動後の所属となる
+            service.reload().unwrap();
+            assert_eq!(
+                service.person_departments(harry.id).unwrap(),
+                vec![quidditch.clone()]
+            );
+            assert_eq!(
+                service.undo().unwrap(),
+                "transfer person 1 from department 1 to department 2"
+            );
+            assert_eq!(
+                service.person_departments(harry.id).unwrap(),
+                vec![gryffindor]
+            );
+        });
+    }
+
+    #[test]
+    fn merge_departments_ok() {
+        with_service("merge", false, |service| {
+            let harry = service.create_person("Potter", Some("Harry"), &[]).unwrap();
+            let ron = service.create_person("Weasley", Some("Ron"), &[]).unwrap();
+            let gryffindor = service.create_department("Gryffindor").unwrap();
+            let quidditch = service.create_department("Quidditch").unwrap();
+            service.add_member(harry.id, gryffindor.id).unwrap();
+            service.add_member(ron.id, gryffindor.id).unwrap();
+            service.add_member(harry.id, quidditch.id).unwrap();
+
+            assert!(service
+                .merge_departments(gryffindor.id, gryffindor.id)
+                .is_err());
+            let merged = service
+                .merge_departments(gryffindor.id, quidditch.id)
+                .unwrap();
+            assert_eq!(
+                merged,
+                MergedDepartments {
+                    moved: 1,
+                    duplicated: 1
+                }
+            );
+            let members = service.department_members(quidditch.id).unwrap();
+            let ids: Vec<PersonId> = members.iter().map(|entry| entry.id).collect();
+            assert_eq!(ids, vec![harry.id, ron.id]);
+            assert!(service.department(gryffindor.id).is_err());
+
+            // １回の取り消しで統合前に戻る
+            service.undo().unwrap();
+            assert_eq!(service.departments().len(), 2);
+            assert_eq!(
+                service.person_departments(harry.id).unwrap(),
+                vec![gryffindor.clone(), quidditch.clone()]
+            );
+            assert_eq!(
+                service.person_departments(ron.id).unwrap(),
+                vec![gryffindor]
+            );
+        });
+    }
+
+    #[test]
+    fn merge_departments_with_deleted_person_ok() {
+        with_service("merge_deleted", false, |service| {
+            let harry = service.create_person("Potter", Some("Harry"), &[]).unwrap();
+            let ron = service.create_person("Weasley", Some("Ron"), &[]).unwrap();
+            let gryffindor = service.create_department("Gryffindor").unwrap();
+            let quidditch = service.create_department("Quidditch").unwrap();
+            service.add_member(harry.id, gryffindor.id).unwrap();
+            service.add_member(ron.id, gryffindor.id).unwrap();
+            service.add_member(ron.id, quidditch.id).unwrap();
+            service.delete_person(harry.id).unwrap();
+            service.delete_person(ron.id).unwrap();
+
+            let merged = service
+                .merge_departments(gryffindor.id, quidditch.id)
+                .unwrap();
+            assert_eq!(
+                merged,
+                MergedDepartments {
+                    moved: 0,
+                    duplicated: 0
+                }
+            );
+            // 統合元への無効な所属は残らない
+            assert_eq!(
+                service.member_list.suspended_members(),
+                vec![(quidditch.id, harry.id), (quidditch.id, ron.id)]
+            );
+
+            // 復元すると統合先の所属として有効に戻る
+            service.restore_person(harry.id).unwrap();
+            service.restore_person(ron.id).unwrap();
+            assert_eq!(
+                service.person_departments(harry.id).unwrap(),
+                vec![quidditch.clone()]
+            );
+            assert_eq!(
+                service.person_departments(ron.id).unwrap(),
+                vec![quidditch.clone()]
+            );
+
+            // 統合後の復元、削除と統合を取り消すと、無効な所属も統合元に戻る
+            service.delete_person(harry.id).unwrap();
+            service.delete_person(ron.id).unwrap();
+            service.undo().unwrap();
+            service.undo().unwrap();
+            service.undo().unwrap();
+            service.undo().unwrap();
+            service.undo().unwrap();
+            assert_eq!(
+                service.member_list.suspended_members(),
+                vec![
+                    (gryffindor.id, harry.id),
+                    (gryffindor.id, ron.id),
+                    (quidditch.id, ron.id)
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn split_department_ok() {
+        with_service("split", false, |service| {
+            let harry = service.create_person("Potter", Some("Harry"), &[]).unwrap();
+            let ron = service.create_person("Weasley", Some("Ron"), &[]).unwrap();
+            let draco = service.create_person("Malfoy", Some("Draco"), &[]).unwrap();
+            let gryffindor = service.create_department("Gryffindor").unwrap();
+            service.add_member(harry.id, gryffindor.id).unwrap();
+            service.add_member(ron.id, gryffindor.id).unwrap();
+
+            let seekers = service
+                .split_department(gryffindor.id, "Seekers", &[harry.id, harry.id])
+                .unwrap();
+            assert_eq!(seekers.name, "Seekers");
+            assert_eq!(
+                service.person_departments(harry.id).unwrap(),
+                vec![seekers.clone()]
+            );
+            assert_eq!(
+                service.person_departments(ron.id).unwrap(),
+                vec![gryffindor.clone()]
+            );
+
+            // 元の部署に所属していない人を含む場合は部署も作成しない
+            assert!(service
+                .split_department(gryffindor.id, "Keepers", &[ron.id, draco.id])
+                .is_err());
+            assert_eq!(service.departments().len(), 2);
+            assert_eq!(
+                service.person_departments(ron.id).unwrap(),
+                vec![gryffindor.clone()]
+            );
+            assert!(service
+                .split_department(gryffindor.id, "Keepers", &[])
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn evaluate_ok() {
+        with_service("evaluate", false, |service| {
+            let harry = service.create_person("Potter", Some("Harry"), &[]).unwrap();
+            let ron = service.create_person("Weasley", Some("Ron"), &[]).unwrap();
+            let draco = service.create_person("Malfoy", Some("Draco"), &[]).unwrap();
+            let neville = service
+                .create_person("Longbottom", Some("Neville"), &[])
+                .unwrap();
+            let gryffindor = service.create_department("Gryffindor").unwrap();
+            let quidditch = service.create_department("Quidditch").unwrap();
+            let prefects = service.create_department("Prefects").unwrap();
+            service.add_member(harry.id, gryffindor.id).unwrap();
+            service.add_member(harry.id, quidditch.id).unwrap();
+            service.add_member(ron.id, gryffindor.id).unwrap();
+            service.add_member(ron.id, quidditch.id).unwrap();
+            service.add_member(ron.id, prefects.id).unwrap();
+            service.add_member(draco.id, quidditch.id).unwrap();
+
+            let evaluate = |service: &mut HrService, text: &str| {
+                let expression = Expression::parse(text).unwrap();
+                return service
+                    .evaluate(&expression)
+                    .unwrap()
+                    .iter()
+                    .map(|entry| entry.id)
+                    .collect::<Vec<PersonId>>();
+            };
+            assert_eq!(
+                evaluate(service, "dept:1 & dept:2 - dept:3"),
+                vec![harry.id]
+            );
+            assert_eq!(
+                evaluate(service, "dept:Quidditch - dept:Gryffindor | unassigned"),
+                vec![draco.id, neville.id]
+            );
+            assert_eq!(evaluate(service, "all - dept:Quidditch"), vec![neville.id]);
+
+            // 削除した個人は含めない
+            service.delete_person(ron.id).unwrap();
+            assert_eq!(evaluate(service, "dept:Gryffindor"), vec![harry.id]);
+            let expression = Expression::parse("dept:Hufflepuff").unwrap();
+            assert!(service.evaluate(&expression).is_err());
+        });
+    }
+}
@@ -0,0 +1,576 @@
+use super::super::domain::department::list::DepartmentList;
+use super::super::domain::department::Department;
+use super::super::domain::member::MemberList;
+use super::super::domain::person::id::PersonId;
+use super::super::domain::person::list::PersonList;
+use super::super::domain::person::Person;
+use super::super::format::csv;
+use super::history::Change;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+// 取り込みの対象
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ImportTarget {
+    Persons,
+    Departments,
+    Members,
+}
+
+impl ImportTarget {
+    pub const NAMES: [&'static str; 3] = ["persons", "departments", "members"];
+
+    pub fn new(value: &str) -> Result<ImportTarget, &'static str> {
+        match value {
+            "persons" => return Ok(ImportTarget::Persons),
+            "departments" => return Ok(ImportTarget::Departments),
+            "members" => return Ok(ImportTarget::Members),
+            _ => return Err("ImportTarget::new : target must be persons, departments or members"),
+        }
+    }
+
+    // 列の名前と、見出しとして受け付ける表記
+    // 見出しは前後の空白を除き、小文字にして空白とハイフンをアンダースコアに置き換えてから比較する
+    fn columns(&self) -> &'static [(&'static str, &'static [&'static str])] {
+        match self {
+            ImportTarget::Persons => {
+                return &[
+                    (
+                        "employee_number",
+                        &["employee_number", "employee_no", "employee_id", "社員番号"],
+                    ),
+                    ("last_name", &["last_name", "family_name", "surname", "姓"]),
+                    ("first_name", &["first_name", "given_name", "名"]),
+                    ("middle", &["middle", "middle_name", "ミドルネーム"]),
+                    ("nickname", &["nickname", "呼び名", "ニックネーム"]),
+                    ("reading_last", &["reading_last", "姓の読み"]),
+                    ("reading_first", &["reading_first", "名の読み"]),
+                    ("order", &["order", "name_order"]),
+                    (
+                        "department",
+                        &["department", "department_name", "部署", "部署名"],
+                    ),
+                ]
+            }
+            ImportTarget::Departments => {
+                return &[(
+                    "name",
+                    &["name", "department", "department_name", "部署", "部署名"],
+                )]
+            }
+            ImportTarget::Members => {
+                return &[
+                    (
+                        "employee_number",
+                        &["employee_number", "employee_no", "employee_id", "社員番号"],
+                    ),
+                    ("person", &["person", "person_id", "person_name", "氏名"]),
+                    (
+                        "department",
+                        &["department", "department_name", "部署", "部署名"],
+                    ),
+                ]
+            }
+        }
+    }
+}
+
+impl fmt::Display for ImportTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportTarget::Persons => return write!(f, "persons"),
+            ImportTarget::Departments => return write!(f, "departments"),
+            ImportTarget::Members => return write!(f, "members"),
+        }
+    }
+}
+
+// 取り込みの結果
+// changeは全ての行の変更をまとめたもの(Change::Batch)で、リストにはまだ適用されていない
+// errorsは誤りのある行の行番号とエラーメッセージ
+pub struct ImportResult {
+    pub change: Change,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub errors: Vec<(usize, String)>,
+}
+
+// １行の取り込み結果
+enum Outcome {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+// 見出しから求めた列の位置
+struct Columns {
+    index: HashMap<&'static str, usize>,
+}
+
+impl Columns {
+    fn new(target: ImportTarget, header: &[String]) -> Result<Columns, Box<dyn Error>> {
+        let mut index: HashMap<&'static str, usize> = HashMap::new();
+        for (position, title) in header.iter().enumerate() {
+            let title = title.trim().to_lowercase().replace([' ', '-'], "_");
+            let column = target
+                .columns()
+                .iter()
+                .find(|(_name, titles)| titles.contains(&title.as_str()));
+            match column {
+                Some((name, _titles)) => {
+                    index.insert(name, position);
+                }
+                None => {
+                    let message = format!("import_csv : Unknown column! ({})", title);
+                    return Err(message.into());
+                }
+            }
+        }
+        let required: &[&str] = match target {
+            ImportTarget::Persons => &["last_name"],
+            ImportTarget::Departments => &["name"],
+            ImportTarget::Members => &["department"],
+        };
+        for name in required.iter() {
+            if !index.contains_key(name) {
+                let message = format!("import_csv : Required column is missing! ({})", name);
+                return Err(message.into());
+            }
+        }
+        if target == ImportTarget::Members
+            && !index.contains_key("employee_number")
+            && !index.contains_key("person")
+        {
+            let message = "import_csv : Required column is missing! (employee_number or person)";
+            return Err(message.into());
+        }
+        return Ok(Columns { index: index });
+    }
+
+    // 列の値を返す
+    // 列が無い場合、値が空の場合はNoneを返す
+    // ファイルの形式上、値に空白を含めることは出来ない
+    fn value<'a>(&self, row: &'a [String], name: &str) -> Result<Option<&'a str>, String> {
+        let value = match self.index.get(name) {
+            Some(position) => row[*position].trim(),
+            None => return Ok(None),
+        };
+        if value.is_empty() {
+            return Ok(None);
+        }
+        if value.contains(char::is_whitespace) {
+            return Err(format!(
+                "import_csv : Value must not contain spaces! ({})",
+                name
+            ));
+        }
+        return Ok(Some(value));
+    }
+
+    fn required_value<'a>(&self, row: &'a [String], name: &str) -> Result<&'a str, String> {
+        match self.value(row, name)? {
+            Some(value) => return Ok(value),
+            None => return Err(format!("import_csv : Value is missing! ({})", name)),
+        }
+    }
+}
+
+// 取り込み中のリストと、適用済の変更
+// 後の行が前の行の結果(作成した人や部署)を参照できるように、変更は１行ずつリストに適用する
+struct Importer<'a> {
+    person_list: &'a mut PersonList,
+    department_list: &'a mut DepartmentList,
+    member_list: &'a mut MemberList,
+    changes: Vec<Change>,
+}
+
+impl Importer<'_> {
+    fn apply(&mut self, change: Change) -> Result<(), String> {
+        if let Err(e) = change.apply(self.person_list, self.department_list, self.member_list) {
+            return Err(e.to_string());
+        }
+        self.changes.push(change);
+        return Ok(());
+    }
+
+    // length件目より後の変更を逆順に取り消す
+    fn rollback_to(&mut self, length: usize) -> Result<(), Box<dyn Error>> {
+        while self.changes.len() > length {
+            if let Some(change) = self.changes.pop() {
+                change
+                    .inverse()
+                    .apply(self.person_list, self.department_list, self.member_list)?;
+            }
+        }
+        return Ok(());
+    }
+
+    fn import_row(
+        &mut self,
+        target: ImportTarget,
+        columns: &Columns,
+        row: &[String],
+    ) -> Result<Outcome, String> {
+        match target {
+            ImportTarget::Persons => return self.import_person(columns, row),
+            ImportTarget::Departments => return self.import_department(columns, row),
+            ImportTarget::Members => return self.import_member(columns, row),
+        }
+    }
+
+    // 社員番号が一致する人、社員番号が無い場合は姓名が一致する人を更新し、いなければ作成する
+    // 空の値は更新しない(既存の値を残す)
+    // 部署が指定されている場合はその部署に所属させる
+    fn import_person(&mut self, columns: &Columns, row: &[String]) -> Result<Outcome, String> {
+        let last_name = columns.required_value(row, "last_name")?;
+        let first_name = columns.value(row, "first_name")?;
+        let employee_number = columns.value(row, "employee_number")?;
+        let mut attributes: Vec<(&str, &str)> = Vec::new();
+        for key in Person::ATTRIBUTE_KEYS.iter() {
+            if let Some(value) = columns.value(row, key)? {
+                attributes.push((key, value));
+            }
+        }
+        let department_id = match columns.value(row, "department")? {
+            Some(name) => {
+                let id = self
+                    .department_list
+                    .resolve_department(name)
+                    .map_err(|e| e.to_string())?;
+                self.department_list
+                    .department(id)
+                    .map_err(|e| e.to_string())?;
+                Some(id)
+            }
+            None => None,
+        };
+
+        let existing: Option<PersonId> = match employee_number {
+            Some(number) => self.person_list.find_by_employee_number(number),
+            None => {
+                let ids = self.person_list.find_by_full_name(last_name, first_name);
+                if ids.len() > 1 {
+                    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+                    return Err(format!(
+                        "import_csv : Several persons have this name! (ids: {})",
+                        ids.join(", ")
+                    ));
+                }
+                ids.first().copied()
+            }
+        };
+
+        let (person_id, outcome) = match existing {
+            Some(id) => {
+                let before = self
+                    .person_list
+                    .person(id)
+                    .map_err(|e| e.to_string())?
+                    .clone();
+                let mut after = before.clone();
+                let first_name = first_name
+                    .or(before.person_name().first_name())
+                    .map(String::from);
+                after
+                    .rename(last_name, first_name.as_deref())
+                    .map_err(|e| e.to_string())?;
+                for (key, value) in attributes {
+                    after.set_attribute(key, value).map_err(|e| e.to_string())?;
+                }
+                if same_person(&before, &after) {
+                    (id, Outcome::Unchanged)
+                } else {
+                    self.apply(Change::ReplacePerson {
+                        id: id,
+                        before: Box::new(before),
+                        after: Box::new(after),
+                    })?;
+                    (id, Outcome::Updated)
+                }
+            }
+            None => {
+                let mut person = Person::new(last_name, first_name).map_err(|e| e.to_string())?;
+                for (key, value) in attributes {
+                    person
+                        .set_attribute(key, value)
+                        .map_err(|e| e.to_string())?;
+                }
+                let id = self.person_list.next_id();
+                self.apply(Change::InsertPerson {
+                    id: id,
+                    person: person,
+                })?;
+                (id, Outcome::Created)
+            }
+        };
+
+        if let Some(department_id) = department_id {
+            if !self.member_list.is_member(person_id, department_id) {
+                self.apply(Change::AddMember {
+                    person_id: person_id,
+                    department_id: department_id,
+                })?;
+                if let Outcome::Unchanged = outcome {
+                    return Ok(Outcome::Updated);
+                }
+            }
+        }
+        return Ok(outcome);
+    }
+
+    // 同じ名前の部署が無ければ作成する
+    fn import_department(&mut self, columns: &Columns, row: &[String]) -> Result<Outcome, String> {
+        let name = columns.required_value(row, "name")?;
+        if self.department_list.find_by_name(name).is_some() {
+            return Ok(Outcome::Unchanged);
+        }
+        let department = Department::new(name).map_err(|e| e.to_string())?;
+        let id = self.department_list.next_id();
+        self.apply(Change::InsertDepartment {
+            id: id,
+            department: department,
+        })?;
+        return Ok(Outcome::Created);
+    }
+
+    // 社員番号またはIDか名前で指定された人を、IDか名前で指定された部署に所属させる
+    fn import_member(&mut self, columns: &Columns, row: &[String]) -> Result<Outcome, String> {
+        let person_id = match columns.value(row, "employee_number")? {
+            Some(number) => match self.person_list.find_by_employee_number(number) {
+                Some(id) => id,
+                None => {
+                    return Err(format!(
+                        "import_csv : No person has this employee number! ({})",
+                        number
+                    ))
+                }
+            },
+            None => {
+                let key = columns.required_value(row, "person")?;
+                self.person_list
+                    .resolve_person(key)
+                    .map_err(|e| e.to_string())?
+            }
+        };
+        self.person_list
+            .person(person_id)
+            .map_err(|e| e.to_string())?;
+        let department = columns.required_value(row, "department")?;
+        let department_id = self
+            .department_list
+            .resolve_department(department)
+            .map_err(|e| e.to_string())?;
+        self.department_list
+            .department(department_id)
+            .map_err(|e| e.to_string())?;
+
+        if self.member_list.is_member(person_id, department_id) {
+            return Ok(Outcome::Unchanged);
+        }
+        self.apply(Change::AddMember {
+            person_id: person_id,
+            department_id: department_id,
+        })?;
+        return Ok(Outcome::Created);
+    }
+}
+
+fn same_person(a: &Person, b: &Person) -> bool {
+    let (a_name, b_name) = (a.person_name(), b.person_name());
+    return a_name.last_name() == b_name.last_name()
+        && a_name.first_name() == b_name.first_name()
+        && a.attributes() == b.attributes();
+}
+
+// CSVの内容を検証し、取り込みのための変更を作成する
+// 誤りのある行はerrorsに記録して次の行に進むので、全ての誤りを一度に確認できる
+// リストは検証のために一時的に変更するが、戻ってきた時点では元の状態に戻っている
+pub fn import_csv(
+    target: ImportTarget,
+    text: &str,
+    label: &str,
+    person_list: &mut PersonList,
+    department_list: &mut DepartmentList,
+    member_list: &mut MemberList,
+) -> Result<ImportResult, Box<dyn Error>> {
+    let rows = csv::parse(text)?;
+    let mut rows = rows.into_iter();
+    let columns = match rows.next() {
+        Some((_line, header)) => Columns::new(target, &header)?,
+        None => return Err("import_csv : The file is empty!".into()),
+    };
+    let width = columns.index.values().max().map_or(0, |max| max + 1);
+
+    let mut importer = Importer {
+        person_list: person_list,
+        department_list: department_list,
+        member_list: member_list,
+        changes: Vec::new(),
+    };
+    let (mut created, mut updated, mut unchanged) = (0, 0, 0);
+    let mut errors: Vec<(usize, String)> = Vec::new();
+    for (line, row) in rows {
+        if row.len() < width {
+            let message = "import_csv : The number of values does not match the header!";
+            errors.push((line, String::from(message)));
+            continue;
+        }
+        let length = importer.changes.len();
+        match importer.import_row(target, &columns, &row) {
+            Ok(Outcome::Created) => created += 1,
+            Ok(Outcome::Updated) => updated += 1,
+            Ok(Outcome::Unchanged) => unchanged += 1,
+            Err(e) => {
+                importer.rollback_to(length)?;
+                errors.push((line, e));
+            }
+        }
+    }
+
+    let changes = std::mem::take(&mut importer.changes);
+    let change = Change::Batch {
+        label: String::from(label),
+        changes: changes,
+    };
+    change
+        .inverse()
+        .apply(person_list, department_list, member_list)?;
+
+    return Ok(ImportResult {
+        change: change,
+        created: created,
+        updated: updated,
+        unchanged: unchanged,
+        errors: errors,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::domain::department::id::DepartmentId;
+    use super::*;
+
+    struct Lists {
+        person_list: PersonList,
+        department_list: DepartmentList,
+        member_list: MemberList,
+    }
+
+    impl Lists {
+        fn new() -> Lists {
+            let mut department_list = DepartmentList::new();
+            department_list
+                .add_department(Department::new("Defence").unwrap(), None)
+                .unwrap();
+            return Lists {
+                person_list: PersonList::new(),
+                department_list: department_list,
+                member_list: MemberList::new(),
+            };
+        }
+
+        fn import(&mut self, target: ImportTarget, text: &str) -> ImportResult {
+            let result = import_csv(
+                target,
+                text,
+                "import",
+                &mut self.person_list,
+                &mut self.department_list,
+                &mut self.member_list,
+            )
+            .unwrap();
+            return result;
+        }
+
+        fn apply(&mut self, result: &ImportResult) {
+            result
+                .change
+                .apply(
+                    &mut self.person_list,
+                    &mut self.department_list,
+                    &mut self.member_list,
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn import_persons_ok() {
+        let mut lists = Lists::new();
+        let text =
+            "社員番号,姓,名,nickname,department\nE1,Potter,Harry,Boy,Defence\nE2,Weasley,Ron,,\n";
+        let result = lists.import(ImportTarget::Persons, text);
+        assert!(result.errors.is_empty());
+        assert_eq!(result.created, 2);
+        // 検証の後はリストが元の状態に戻っている
+        assert!(lists.person_list.person(PersonId::new(1)).is_err());
+
+        lists.apply(&result);
+        let id = lists.person_list.find_by_employee_number("E1").unwrap();
+        assert_eq!(lists.person_list.person(id).unwrap().name(), "Potter Harry");
+        assert!(lists.member_list.is_member(id, DepartmentId::new(1)));
+    }
+
+    #[test]
+    fn import_persons_upsert_ok() {
+        let mut lists = Lists::new();
+        let result = lists.import(
+            ImportTarget::Persons,
+            "employee_number,last_name\nE1,Potter\n",
+        );
+        lists.apply(&result);
+
+        let text = "employee_number,last_name,first_name\nE1,Potter,Harry\nE1,Potter,Harry\n";
+        let result = lists.import(ImportTarget::Persons, text);
+        assert_eq!(result.created, 0);
+        assert_eq!(result.updated, 1);
+        assert_eq!(result.unchanged, 1);
+    }
+
+    #[test]
+    fn import_persons_reports_every_bad_row() {
+        let mut lists = Lists::new();
+        let text = "last_name,first_name,department\n,Harry,\nPotter,Harry,Nowhere\nWeasley,Ron,Defence\nLupin,Remus John,\n";
+        let result = lists.import(ImportTarget::Persons, text);
+        let lines: Vec<usize> = result.errors.iter().map(|(line, _e)| *line).collect();
+        assert_eq!(lines, vec![2, 3, 5]);
+        assert_eq!(result.created, 1);
+    }
+
+    #[test]
+    fn import_departments_and_members_ok() {
+        let mut lists = Lists::new();
+        let result = lists.import(ImportTarget::Departments, "name\nDefence\nPotions\n");
+        assert_eq!(result.created, 1);
+        assert_eq!(result.unchanged, 1);
+        lists.apply(&result);
+
+        let result = lists.import(
+            ImportTarget::Persons,
+            "employee_number,last_name\nE1,Potter\n",
+        );
+        lists.apply(&result);
+        let result = lists.import(
+            ImportTarget::Members,
+            "employee_number,department\nE1,Potions\nE9,Potions\n",
+        );
+        assert_eq!(result.created, 1);
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn import_unknown_column_err() {
+        let mut lists = Lists::new();
+        assert!(import_csv(
+            ImportTarget::Persons,
+            "last_name,salary\nPotter,100\n",
+            "import",
+            &mut lists.person_list,
+            &mut lists.department_list,
+            &mut lists.member_list,
+        )
+        .is_err());
+    }
+}
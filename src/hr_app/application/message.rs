@@ -0,0 +1,962 @@
+use std::env;
+
+// 画面に表示するメッセージの言語
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Language {
+    English,
+    Japanese,
+}
+
+// 言語ごとに文言を切り替えるメッセージ
+// 文言中の "{}" はLanguage::formatで引数に置き換える
+#[derive(Copy, Clone, Debug)]
+pub enum Message {
+    InputCommand,
+    HelpHint,
+    QueryError,
+    ApplicationError,
+    ApplicationFinished,
+    HowToUse,
+    UnrecognizedCommand,
+    DepartmentNameMissing,
+    DepartmentIdMissing,
+    PersonNameMissing,
+    PersonIdMissing,
+    SourceDepartmentMissing,
+    DestinationDepartmentMissing,
+    PersonIdsMissing,
+    SearchTextMissing,
+    ImportTargetMissing,
+    ExportFormatMissing,
+    ChartFormatMissing,
+    FilePathMissing,
+    QueryExpressionMissing,
+    InvalidChoice,
+    TooManyArguments,
+    UnknownOption,
+    OptionValueMissing,
+    DidYouMean,
+    Aliases,
+    HelpTitle,
+    HelpAttributes,
+    HelpNameNote,
+    HelpDetailHint,
+    DeletedPersons,
+    DeletedDepartments,
+    DeletedAt,
+    Purged,
+    Undo,
+    Redo,
+    NoPersonFound,
+    NoDepartmentFound,
+    NotMemberOfAnyDepartment,
+    EmployeeNumber,
+    LineError,
+    ImportSummary,
+    ImportDryRun,
+    ImportAborted,
+    Exported,
+    MigrationUsage,
+    MigrationSource,
+    MigrationTarget,
+    MigrationNoData,
+    BackedUp,
+    MigrationVerified,
+    CheckInvalidLine,
+    CheckDuplicateLine,
+    CheckDuplicateId,
+    CheckDuplicateDepartmentName,
+    CheckDuplicateEmployeeNumber,
+    CheckInvalidValue,
+    CheckMissingPerson,
+    CheckMissingDepartment,
+    CheckDuplicateMembership,
+    CheckShouldBeSuspended,
+    CheckShouldBeValid,
+    CheckIdGap,
+    CheckRepairable,
+    CheckIssue,
+    CheckSummary,
+    CheckNoIssue,
+    CheckRepairHint,
+    CheckRepaired,
+    CheckTextOnly,
+    ReadOnlySession,
+    ExternalChange,
+    CommandAborted,
+    DataLocked,
+    DataLockedForReading,
+    Serving,
+    StatsHeadcount,
+    StatsDepartment,
+    StatsLargest,
+    StatsSmallest,
+    StatsAverage,
+    StatsMultipleDepartments,
+    StatsPersonMemberships,
+    StatsUnassigned,
+    StatsDistribution,
+    StatsDistributionRow,
+    ShowingRange,
+    ShowingNone,
+    PagerPrompt,
+    DepartmentsMerged,
+    DepartmentSplit,
+    QueryMatched,
+}
+
+// コマンド名と、helpで表示する説明(英語、日本語)
+const COMMAND_DESCRIPTIONS: &[(&str, &str, &str)] = &[
+    (
+        "create_department",
+        "create a new department and add it to the department list",
+        "部署を作成して部署の一覧に追加する",
+    ),
+    (
+        "show_all_departments",
+        "show all departments in the list",
+        "全ての部署を表示する",
+    ),
+    (
+        "department_info",
+        "show an information of the department",
+        "部署に所属している人を表示する",
+    ),
+    (
+        "delete_department",
+        "delete a department from the list",
+        "部署を削除する",
+    ),
+    (
+        "create_person",
+        "create a new person and add it to the person list",
+        "人を作成して人の一覧に追加する",
+    ),
+    (
+        "show_all_persons",
+        "show all persons in the list (sort_key: id or reading)",
+        "全ての人を表示する (sort_key: id または reading)",
+    ),
+    (
+        "person_info",
+        "show an information of the person",
+        "人が所属している部署を表示する",
+    ),
+    (
+        "delete_person",
+        "delete a person from the list",
+        "人を削除する",
+    ),
+    (
+        "add_person",
+        "add a person to the department",
+        "人を部署に所属させる",
+    ),
+    (
+        "remove_person",
+        "remove a person from the department",
+        "人を部署から外す",
+    ),
+    (
+        "transfer_person",
+        "move a person from one department to another in a single step",
+        "人の所属を別の部署へ移す(１回の操作として取り消せる)",
+    ),
+    (
+        "merge_departments",
+        "move all members of the source department to the target and delete the source",
+        "統合元の部署の全員を統合先の部署へ移し, 統合元の部署を削除する",
+    ),
+    (
+        "split_department",
+        "create a new department and move the listed members of the department to it",
+        "新しい部署を作成し, 部署の指定した人をその部署へ移す",
+    ),
+    (
+        "rename_person",
+        "change the name of the person",
+        "人の名前を変更する",
+    ),
+    (
+        "rename_department",
+        "change the name of the department",
+        "部署の名前を変更する",
+    ),
+    (
+        "find_person",
+        "search persons by name, reading or nickname",
+        "名前, 読み仮名, ニックネームで人を検索する",
+    ),
+    (
+        "find_department",
+        "search departments by name",
+        "名前で部署を検索する",
+    ),
+    (
+        "restore_person",
+        "restore a deleted person and the memberships",
+        "削除した人を所属と合わせて復元する",
+    ),
+    (
+        "restore_department",
+        "restore a deleted department and the memberships",
+        "削除した部署を所属と合わせて復元する",
+    ),
+    (
+        "show_deleted",
+        "show deleted persons and departments",
+        "削除済の人と部署を表示する",
+    ),
+    (
+        "purge",
+        "permanently delete entries deleted more than <days> days ago (default 30)",
+        "<days>日以上前に削除した人と部署を完全に削除する (省略時は30日)",
+    ),
+    (
+        "import_csv",
+        "import persons, departments or memberships from a CSV file",
+        "CSVファイルから人, 部署, 所属をまとめて取り込む",
+    ),
+    (
+        "export",
+        "export persons, departments and memberships to CSV files in <file> (a directory) or to a JSON file",
+        "人, 部署, 所属をCSVファイル(<file>はディレクトリ)またはJSONファイルに出力する",
+    ),
+    (
+        "org_chart",
+        "show the organization chart in Graphviz DOT or Mermaid, or write it to <file>",
+        "組織図をGraphvizのDOT形式またはMermaid形式で表示する(<file>を指定するとファイルに出力する)",
+    ),
+    (
+        "stats",
+        "show headcount and membership statistics, or write them as CSV files in <file> (a directory) or a JSON file",
+        "部署ごとの人数や所属の統計を表示する(<format>と<file>を指定するとCSVファイルまたはJSONファイルに出力する)",
+    ),
+    (
+        "query",
+        "show persons matching a set expression of department memberships (dept:<department>, unassigned, all, &, |, -, ( ))",
+        "部署の所属を集合演算した式に当てはまる人を表示する(dept:<部署>, unassigned, all, &, |, -, ( ))",
+    ),
+    ("undo", "cancel the last change", "直前の操作を取り消す"),
+    (
+        "redo",
+        "apply the last cancelled change again",
+        "取り消した操作をやり直す",
+    ),
+    (
+        "all_info",
+        "show all persons by department",
+        "全ての人を部署ごとに表示する",
+    ),
+    ("help", "show help", "ヘルプを表示する"),
+    (
+        "quit",
+        "finish this application",
+        "アプリケーションを終了する",
+    ),
+];
+
+// コマンド名、スイッチ名と、helpで表示する説明(英語、日本語)
+const FLAG_DESCRIPTIONS: &[(&str, &str, &str, &str)] = &[
+    (
+        "import_csv",
+        "dry-run",
+        "only validate the file and report every invalid row",
+        "ファイルの検証と誤りのある行の表示だけを行い, 保存はしない",
+    ),
+    (
+        "export",
+        "include-deleted",
+        "also export deleted persons, departments and their memberships",
+        "削除済の人, 部署とその所属も出力する",
+    ),
+    (
+        "org_chart",
+        "skip-empty",
+        "leave out departments without members",
+        "所属している人がいない部署を含めない",
+    ),
+    (
+        "org_chart",
+        "unassigned",
+        "also show persons who belong to no department",
+        "どの部署にも所属していない人も含める",
+    ),
+    (
+        "show_all_persons",
+        "filter",
+        "show only matching persons (name:<text>, dept:<department>, unassigned, memberships<op><count>); repeat to combine",
+        "条件に合う人だけを表示する (name:<文字列>, dept:<部署>, unassigned, memberships<比較><数>). 複数指定すると全てを満たす人を表示する",
+    ),
+    (
+        "show_all_persons",
+        "offset",
+        "skip the first <value> entries",
+        "先頭から<value>件を飛ばして表示する",
+    ),
+    (
+        "show_all_persons",
+        "limit",
+        "show at most <value> entries",
+        "最大<value>件を表示する",
+    ),
+    (
+        "show_all_departments",
+        "offset",
+        "skip the first <value> entries",
+        "先頭から<value>件を飛ばして表示する",
+    ),
+    (
+        "show_all_departments",
+        "limit",
+        "show at most <value> entries",
+        "最大<value>件を表示する",
+    ),
+    (
+        "department_info",
+        "filter",
+        "show only matching members (same expressions as show_all_persons)",
+        "条件に合う所属者だけを表示する (show_all_persons と同じ条件)",
+    ),
+    (
+        "department_info",
+        "offset",
+        "skip the first <value> members",
+        "先頭から<value>人を飛ばして表示する",
+    ),
+    (
+        "department_info",
+        "limit",
+        "show at most <value> members",
+        "最大<value>人を表示する",
+    ),
+    (
+        "all_info",
+        "filter",
+        "show only matching persons (same expressions as show_all_persons)",
+        "条件に合う人だけを表示する (show_all_persons と同じ条件)",
+    ),
+    (
+        "purge",
+        "dry-run",
+        "only show the entries to be purged",
+        "完全に削除される人と部署を表示するだけで削除はしない",
+    ),
+];
+
+// ドメイン層などのエラーメッセージ(英語)と、対応する日本語
+// エラーは "Type::method : message" の形式なので、" : " より後ろの部分と前方一致で比較する
+const ERROR_MESSAGES: &[(&str, &str)] = &[
+    (
+        "port must be a number",
+        "ポート番号は数値で指定してください",
+    ),
+    (
+        "Verification failed",
+        "移行後のデータが移行前と一致しません. バックアップから復元してください",
+    ),
+    (
+        "Unsupported schema version",
+        "このバージョンのデータファイルには対応していません",
+    ),
+    (
+        "schema_version is missing",
+        "データファイルに schema_version がありません",
+    ),
+    (
+        "format must be csv or json",
+        "出力形式には csv または json を指定してください",
+    ),
+    (
+        "format must be dot or mermaid",
+        "出力形式には dot または mermaid を指定してください",
+    ),
+    ("invalid filter", "絞り込み条件の指定が正しくありません"),
+    ("unexpected", "式に予期しない語があります"),
+    ("missing ')'", "式に閉じ括弧 ) がありません"),
+    ("missing closing quote", "式に閉じる \" がありません"),
+    (
+        "parentheses are nested too deeply",
+        "式の括弧の入れ子が深すぎます",
+    ),
+    ("the expression is too long", "式が長すぎます"),
+    (
+        "the expression ends unexpectedly",
+        "式が途中で終わっています",
+    ),
+    (
+        "limit and offset must be numbers",
+        "limit と offset には0以上の数値を指定してください",
+    ),
+    ("Id is empty", "指定されたIDの人は存在しません"),
+    ("This id is empty", "指定されたIDは存在しません"),
+    ("Received id is empty", "指定されたIDの部署は存在しません"),
+    ("DepartmentId is empty", "指定されたIDの部署は存在しません"),
+    (
+        "This person has already been deleted",
+        "この人は既に削除されています",
+    ),
+    (
+        "This department has already been deleted",
+        "この部署は既に削除されています",
+    ),
+    (
+        "This person is not in the deleted list",
+        "この人は削除済の一覧にありません",
+    ),
+    (
+        "This department is not in the deleted list",
+        "この部署は削除済の一覧にありません",
+    ),
+    (
+        "The same name already exists",
+        "同じ名前の部署が既に存在します",
+    ),
+    ("The same id has already existed", "同じIDが既に存在します"),
+    (
+        "This person has already belonged to the department",
+        "この人は既にこの部署に所属しています",
+    ),
+    (
+        "This person is not a member",
+        "この人はこの部署に所属していません",
+    ),
+    (
+        "This person is not a suspended member",
+        "この人はこの部署に無効な状態で所属していません",
+    ),
+    (
+        "The source and destination are the same department",
+        "移動元と移動先が同じ部署です",
+    ),
+    ("No person is specified", "移す人が指定されていません"),
+    (
+        "Person or department does not exist",
+        "人または部署が存在しません",
+    ),
+    ("No person has this name", "この名前の人は存在しません"),
+    (
+        "Several persons have this name",
+        "この名前の人が複数存在します",
+    ),
+    (
+        "No department has this name",
+        "この名前の部署は存在しません",
+    ),
+    (
+        "Several departments match this name",
+        "この名前の部署が複数存在します",
+    ),
+    ("There is nothing to undo", "取り消せる操作がありません"),
+    ("There is nothing to redo", "やり直せる操作がありません"),
+    ("unknown attribute key", "名前の項目が正しくありません"),
+    (
+        "order must be family_first or given_first",
+        "orderにはfamily_firstまたはgiven_firstを指定してください",
+    ),
+    (
+        "the size of value is larger than the max length",
+        "名前が長すぎます",
+    ),
+    (
+        "the size of value is smaller than the min length",
+        "名前が短すぎます",
+    ),
+    (
+        "the value must not contain spaces or '='",
+        "名前に空白と = は使えません",
+    ),
+    ("Invalid file format", "ファイルの形式が正しくありません"),
+    ("invalid digit found in string", "数値を指定してください"),
+    (
+        "the length of employee number is invalid",
+        "社員番号の長さが正しくありません",
+    ),
+    (
+        "employee number contains invalid characters",
+        "社員番号に使用できない文字が含まれています",
+    ),
+    ("The file is empty", "ファイルが空です"),
+    ("Unknown column", "不明な列です"),
+    ("Required column is missing", "必要な列がありません"),
+    (
+        "The number of values does not match the header",
+        "値の数が見出しと一致しません",
+    ),
+    (
+        "Value must not contain spaces",
+        "値に空白を含めることはできません",
+    ),
+    ("Value is missing", "値がありません"),
+    (
+        "No person has this employee number",
+        "この社員番号の人は存在しません",
+    ),
+    (
+        "This employee number is already used",
+        "この社員番号は既に使われています",
+    ),
+    ("Unexpected quote", "引用符(\")の位置が正しくありません"),
+    (
+        "Quoted value is not closed",
+        "引用符(\")が閉じられていません",
+    ),
+    (
+        "Another session is using the data directory",
+        "他のセッションがデータディレクトリを使用しています",
+    ),
+    (
+        "The new and legacy department files exist with different contents",
+        "新旧の部署ファイルが両方あり, 内容が異なります",
+    ),
+    ("Invalid status", "所属の状態が正しくありません"),
+    (
+        "target must be persons, departments or members",
+        "取り込む対象には persons, departments または members を指定してください",
+    ),
+    (
+        "min_length must be larger than 0",
+        "名前の最小の長さは1以上にしてください",
+    ),
+    (
+        "max_length must be larger than min_length",
+        "名前の最大の長さは最小の長さより大きくしてください",
+    ),
+];
+
+impl Language {
+    // "en", "ja" の他に "ja_JP.UTF-8" のような環境変数LANGの値も受け付ける
+    pub fn new(code: &str) -> Option<Language> {
+        let code = code.to_lowercase();
+        if code.starts_with("ja") {
+            return Some(Language::Japanese);
+        }
+        if code.starts_with("en") || code == "c" || code == "posix" {
+            return Some(Language::English);
+        }
+        return None;
+    }
+
+    // コマンドライン引数の "--lang ja|en" を優先し、無ければ環境変数LANGから決める
+    // どちらからも決まらない場合は英語とする
+    pub fn detect(args: &[String]) -> Result<Language, String> {
+        if let Some(index) = args.iter().position(|arg| arg == "--lang") {
+            let code = match args.get(index + 1) {
+                Some(code) => code,
+                None => return Err(String::from("--lang requires ja or en")),
+            };
+            match Language::new(code) {
+                Some(language) => return Ok(language),
+                None => return Err(format!("unsupported language: {}", code)),
+            }
+        }
+        let language = match env::var("LANG") {
+            Ok(code) => Language::new(&code),
+            Err(_) => None,
+        };
+        return Ok(language.unwrap_or(Language::English));
+    }
+
+    pub fn text(&self, message: Message) -> &'static str {
+        let (english, japanese) = match message {
+            Message::InputCommand => ("input a command", "コマンドを入力してください"),
+            Message::HelpHint => (
+                "if you need some help, input 'help'",
+                "ヘルプを表示するには 'help' と入力してください",
+            ),
+            Message::QueryError => ("query error", "コマンドエラー"),
+            Message::ApplicationError => ("Application Error", "アプリケーションエラー"),
+            Message::ApplicationFinished => (
+                "Application finished successfully!",
+                "アプリケーションを終了しました",
+            ),
+            Message::HowToUse => ("how to use:", "使い方:"),
+            Message::UnrecognizedCommand => ("unrecognized command!", "不明なコマンドです"),
+            Message::DepartmentNameMissing => ("department_name is missing", "部署名がありません"),
+            Message::DepartmentIdMissing => ("department_id is missing", "部署IDがありません"),
+            Message::PersonNameMissing => ("person_name is missing", "名前がありません"),
+            Message::PersonIdMissing => ("person_id is missing", "人のIDがありません"),
+            Message::SourceDepartmentMissing => (
+                "source department is missing",
+                "移動元の部署がありません",
+            ),
+            Message::DestinationDepartmentMissing => (
+                "destination department is missing",
+                "移動先の部署がありません",
+            ),
+            Message::PersonIdsMissing => (
+                "person_ids are missing",
+                "移す人のIDがありません",
+            ),
+            Message::ImportTargetMissing => (
+                "target is missing (persons, departments or members)",
+                "取り込む対象(persons, departments, members)がありません",
+            ),
+            Message::ExportFormatMissing => (
+                "format is missing (csv or json)",
+                "出力形式(csv, json)がありません",
+            ),
+            Message::ChartFormatMissing => (
+                "format is missing (dot or mermaid)",
+                "出力形式(dot, mermaid)がありません",
+            ),
+            Message::FilePathMissing => ("file is missing", "ファイルの指定がありません"),
+            Message::QueryExpressionMissing => (
+                "expression is missing (e.g. dept:1 & dept:2 - dept:3)",
+                "式がありません(例: dept:1 & dept:2 - dept:3)",
+            ),
+            Message::SearchTextMissing => ("search text is missing", "検索する文字列がありません"),
+            Message::InvalidChoice => ("{} must be {}", "{} には {} を指定してください"),
+            Message::TooManyArguments => ("too many arguments", "引数が多すぎます"),
+            Message::UnknownOption => ("unknown option '{}'!", "不明なオプションです ({})"),
+            Message::OptionValueMissing => ("value of {} is missing", "{} の値がありません"),
+            Message::DidYouMean => ("did you mean '{}'?", "'{}' のことですか?"),
+            Message::Aliases => ("aliases:", "別名:"),
+            Message::HelpTitle => ("~~HELP~~", "~~ヘルプ~~"),
+            Message::HelpAttributes => (
+                "employee_number, middle, nickname, reading_last, reading_first, order(family_first|given_first)",
+                "employee_number(社員番号), middle(ミドルネーム), nickname(ニックネーム), reading_last(姓の読み), reading_first(名の読み), order(family_first|given_first)",
+            ),
+            Message::HelpNameNote => (
+                "<person_id> and <department_id> also accept a name if exactly one entry has that name",
+                "<person_id> と <department_id> には, 該当するものが１つだけであれば名前も指定できます",
+            ),
+            Message::HelpDetailHint => (
+                "input 'help <command>' to show the details of the command",
+                "'help <command>' でコマンドの詳しい説明を表示します",
+            ),
+            Message::DeletedPersons => ("Deleted persons:", "削除済の人:"),
+            Message::DeletedDepartments => ("Deleted departments:", "削除済の部署:"),
+            Message::DeletedAt => ("deleted at {}", "{} に削除"),
+            Message::Purged => (
+                "purged {} person(s) and {} department(s)",
+                "{}人, {}部署を完全に削除しました",
+            ),
+            Message::DepartmentsMerged => (
+                "merged: moved {} member(s), {} already belonged to the target",
+                "統合しました: {}人を移し, {}人は統合先に既に所属していました",
+            ),
+            Message::DepartmentSplit => (
+                "created department {}: {} and moved {} member(s)",
+                "部署 {}: {} を作成して{}人を移しました",
+            ),
+            Message::Undo => ("undo: {}", "取り消し: {}"),
+            Message::Redo => ("redo: {}", "やり直し: {}"),
+            Message::NoPersonFound => ("no person found", "該当する人はいません"),
+            Message::NoDepartmentFound => ("no department found", "該当する部署はありません"),
+            Message::NotMemberOfAnyDepartment => (
+                "Not member of any department:",
+                "どの部署にも所属していない人:",
+            ),
+            Message::EmployeeNumber => ("employee number: {}", "社員番号: {}"),
+            Message::LineError => ("line {}: {}", "{}行目: {}"),
+            Message::ImportSummary => (
+                "{} created, {} updated, {} unchanged",
+                "作成 {}件, 更新 {}件, 変更なし {}件",
+            ),
+            Message::ImportDryRun => (
+                "dry run: nothing was saved",
+                "確認のみのため保存していません",
+            ),
+            Message::ImportAborted => (
+                "{} invalid row(s): nothing was saved",
+                "{}行に誤りがあるため保存していません",
+            ),
+            Message::Exported => ("exported to {}", "{} に出力しました"),
+            Message::MigrationUsage => (
+                "usage: migrate --to text|json|sqlite [--from text|json|sqlite] (default --from text)",
+                "使い方: migrate --to text|json|sqlite [--from text|json|sqlite] (--from の省略時は text)",
+            ),
+            Message::MigrationSource => (
+                "source: {} (schema version {})",
+                "移行元: {} (形式のバージョン {})",
+            ),
+            Message::MigrationTarget => (
+                "target: {} (schema version {})",
+                "移行先: {} (形式のバージョン {})",
+            ),
+            Message::MigrationNoData => ("no data to migrate", "移行するデータがありません"),
+            Message::BackedUp => ("backed up to {}", "{} にバックアップしました"),
+            Message::CheckInvalidLine => ("invalid line", "行の形式が正しくありません"),
+            Message::CheckDuplicateLine => (
+                "duplicate line of id {}",
+                "ID {} の行が重複しています",
+            ),
+            Message::CheckDuplicateId => (
+                "id {} is used by several lines",
+                "ID {} が複数の行で使われています",
+            ),
+            Message::CheckDuplicateDepartmentName => (
+                "department name '{}' is used by several departments",
+                "部署名 '{}' が複数の部署で使われています",
+            ),
+            Message::CheckDuplicateEmployeeNumber => (
+                "employee number '{}' is used by several persons",
+                "社員番号 '{}' が複数の人で使われています",
+            ),
+            Message::CheckInvalidValue => ("invalid value: {}", "値が正しくありません: {}"),
+            Message::CheckMissingPerson => (
+                "membership of missing person {}",
+                "存在しない人 {} の所属です",
+            ),
+            Message::CheckMissingDepartment => (
+                "membership of missing department {}",
+                "存在しない部署 {} への所属です",
+            ),
+            Message::CheckDuplicateMembership => (
+                "duplicate membership",
+                "所属が重複しています",
+            ),
+            Message::CheckShouldBeSuspended => (
+                "membership of a deleted person or department is not suspended",
+                "削除済の人または部署への所属が無効になっていません",
+            ),
+            Message::CheckShouldBeValid => (
+                "membership is suspended although neither the person nor the department is deleted",
+                "人と部署のどちらも削除されていないのに所属が無効になっています",
+            ),
+            Message::CheckIdGap => ("missing ids: {}", "欠番: {}"),
+            Message::CheckRepairable => ("(repairable)", "(修復可能)"),
+            Message::CheckIssue => ("{}: {}", "{}: {}"),
+            Message::CheckSummary => (
+                "{} issue(s) found, {} repairable",
+                "{}件の問題があります (修復可能 {}件)",
+            ),
+            Message::CheckNoIssue => ("no issues found", "問題はありません"),
+            Message::CheckRepairHint => (
+                "run 'check --repair' to fix the repairable issues",
+                "'check --repair' で修復可能な問題を修復します",
+            ),
+            Message::CheckRepaired => ("repaired {}", "{} を修復しました"),
+            Message::CheckTextOnly => (
+                "check supports only the text storage",
+                "check はテキストファイルの保存形式のみに対応しています",
+            ),
+            Message::ReadOnlySession => (
+                "this session is read-only (started with --read-only)",
+                "読み取り専用で起動しているため実行できません (--read-only)",
+            ),
+            Message::ExternalChange => (
+                "the data was changed by another program after it was loaded. input 'r' to reload the data, or anything else to abort this command",
+                "読み込んだ後に他のプログラムがデータを変更しました. 'r' で読み込み直し, それ以外でこのコマンドを中止します",
+            ),
+            Message::CommandAborted => ("the command was aborted", "コマンドを中止しました"),
+            Message::DataLocked => (
+                "another session is using the data. start with --read-only to view the data",
+                "他のセッションがデータを使用中です. 閲覧のみの場合は --read-only を付けて起動してください",
+            ),
+            Message::DataLockedForReading => (
+                "another session is editing the data. try again after it finishes",
+                "他のセッションがデータを編集中です. 編集が終わってから起動してください",
+            ),
+            Message::Serving => (
+                "serving the REST API on http://{} (press Ctrl+C to stop)",
+                "REST API を http://{} で公開しています (Ctrl+C で終了)",
+            ),
+            Message::ShowingRange => ("(showing {} to {} of {})", "({}〜{}件目を表示, 全{}件)"),
+            Message::ShowingNone => ("(showing none of {})", "(表示する項目はありません, 全{}件)"),
+            Message::PagerPrompt => (
+                "-- more: press Enter for the next page, q to stop --",
+                "-- 続きがあります: Enterで次のページ, qで終了 --",
+            ),
+            Message::QueryMatched => ("{} person(s) matched", "{}人が該当しました"),
+            Message::StatsHeadcount => ("Headcount by department:", "部署ごとの人数:"),
+            Message::StatsDepartment => (" {}: {} ({} person(s))", " {}: {} ({}人)"),
+            Message::StatsLargest => (
+                "Largest department(s): {} ({} person(s))",
+                "最も人数の多い部署: {} ({}人)",
+            ),
+            Message::StatsSmallest => (
+                "Smallest department(s): {} ({} person(s))",
+                "最も人数の少ない部署: {} ({}人)",
+            ),
+            Message::StatsAverage => ("Average department size: {}", "部署の平均人数: {}"),
+            Message::StatsMultipleDepartments => (
+                "Persons in multiple departments: {}",
+                "複数の部署に所属している人: {}人",
+            ),
+            Message::StatsPersonMemberships => (
+                " {}: {} ({} departments)",
+                " {}: {} ({}部署)",
+            ),
+            Message::StatsUnassigned => (
+                "Persons in no department: {}",
+                "どの部署にも所属していない人: {}人",
+            ),
+            Message::StatsDistribution => ("Departments per person:", "１人あたりの所属部署数:"),
+            Message::StatsDistributionRow => (
+                " {} department(s): {} person(s)",
+                " {}部署: {}人",
+            ),
+            Message::MigrationVerified => (
+                "migrated and verified: {}",
+                "移行と検証が完了しました: {}",
+            ),
+        };
+        match self {
+            Language::English => return english,
+            Language::Japanese => return japanese,
+        }
+    }
+
+    // 文言中の "{}" を先頭から順に引数で置き換える
+    pub fn format(&self, message: Message, args: &[&str]) -> String {
+        let mut result = String::new();
+        let mut rest = self.text(message);
+        for arg in args.iter() {
+            match rest.split_once("{}") {
+                Some((before, after)) => {
+                    result.push_str(before);
+                    result.push_str(arg);
+                    rest = after;
+                }
+                None => break,
+            }
+        }
+        result.push_str(rest);
+        return result;
+    }
+
+    // helpで表示するコマンドの説明を返す
+    pub fn command_description(&self, command: &str) -> &'static str {
+        for (name, english, japanese) in COMMAND_DESCRIPTIONS.iter() {
+            if *name == command {
+                match self {
+                    Language::English => return english,
+                    Language::Japanese => return japanese,
+                }
+            }
+        }
+        return "";
+    }
+
+    // helpで表示するスイッチの説明を返す
+    pub fn flag_description(&self, command: &str, flag: &str) -> &'static str {
+        for (name, flag_name, english, japanese) in FLAG_DESCRIPTIONS.iter() {
+            if *name == command && *flag_name == flag {
+                match self {
+                    Language::English => return english,
+                    Language::Japanese => return japanese,
+                }
+            }
+        }
+        return "";
+    }
+
+    // エラーメッセージを表示用に変換する
+    // 英語の場合はそのまま返す
+    // 日本語の場合は対応する文言に置き換え、"(ids: 1, 2)" のような補足は残す
+    // 対応する文言が無い場合はそのまま返す
+    pub fn translate_error(&self, error: &str) -> String {
+        if *self == Language::English {
+            return String::from(error);
+        }
+        let message = match error.split_once(" : ") {
+            Some((_location, message)) => message,
+            None => error,
+        };
+        for (english, japanese) in ERROR_MESSAGES.iter() {
+            if let Some(rest) = message.strip_prefix(english) {
+                let rest = rest.trim_start_matches(['!', '.']);
+                return format!("{}{}", japanese, rest);
+            }
+        }
+        return String::from(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn new_from_lang_ok() {
+        assert_eq!(Language::new("ja_JP.UTF-8"), Some(Language::Japanese));
+        assert_eq!(Language::new("en_US.UTF-8"), Some(Language::English));
+        assert_eq!(Language::new("fr"), None);
+    }
+
+    #[test]
+    fn detect_option_ok() {
+        let args = vec![
+            String::from("app"),
+            String::from("--lang"),
+            String::from("ja"),
+        ];
+        assert_eq!(Language::detect(&args).unwrap(), Language::Japanese);
+        let args = vec![String::from("app"), String::from("--lang")];
+        assert!(Language::detect(&args).is_err());
+    }
+
+    #[test]
+    fn format_ok() {
+        let text = Language::Japanese.format(Message::Purged, &["2", "1"]);
+        assert_eq!(text, String::from("2人, 1部署を完全に削除しました"));
+    }
+
+    #[test]
+    fn translate_error_ok() {
+        let error = "PersonList::resolve_person : Several persons have this name! (ids: 1, 2)";
+        assert_eq!(
+            Language::Japanese.translate_error(error),
+            String::from("この名前の人が複数存在します (ids: 1, 2)")
+        );
+        assert_eq!(
+            Language::English.translate_error(error),
+            String::from(error)
+        );
+    }
+
+    // ソースコード中の全てのエラーメッセージ("Type::method : message" 形式の文字列)に日本語があることを確かめる
+    // {} より後ろは実行時に決まるので、その前までを比較する
+    #[test]
+    fn translate_every_error_ok() {
+        let mut paths = vec![PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src"))];
+        let mut untranslated: Vec<String> = Vec::new();
+        while let Some(path) = paths.pop() {
+            if path.is_dir() {
+                for entry in fs::read_dir(&path).unwrap() {
+                    paths.push(entry.unwrap().path());
+                }
+                continue;
+            }
+            if !path.to_string_lossy().ends_with(".rs") {
+                continue;
+            }
+            let contents = fs::read_to_string(&path).unwrap();
+            // テストで使う文字列とコメントは対象外とする
+            let code = contents.split("#[cfg(test)]").next().unwrap();
+            for line in code.lines() {
+                if line.trim_start().starts_with("//") {
+                    continue;
+                }
+                for literal in line.split('"').skip(1).step_by(2) {
+                    let message = match literal.split_once(" : ") {
+                        Some((location, message))
+                            if location.contains("::") && !location.contains(' ') =>
+                        {
+                            message
+                        }
+                        _ => continue,
+                    };
+                    let message = message.split('{').next().unwrap();
+                    if !message.is_empty() && Language::Japanese.translate_error(message) == message
+                    {
+                        untranslated.push(String::from(literal));
+                    }
+                }
+            }
+        }
+        assert!(untranslated.is_empty(), "{:?}", untranslated);
+    }
+
+    #[test]
+    fn translate_error_unknown_ok() {
+        assert_eq!(
+            Language::Japanese.translate_error("something wrong"),
+            String::from("something wrong")
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use super::super::domain::department::list::DepartmentList;
+use super::super::domain::member::MemberList;
+use super::super::domain::person::list::PersonList;
+use super::super::repository::Repositories;
+use super::export;
+use std::error::Error;
+use std::fmt;
+
+// 移行したデータの件数
+#[derive(PartialEq, Debug)]
+pub struct Summary {
+    pub persons: usize,
+    pub deleted_persons: usize,
+    pub departments: usize,
+    pub deleted_departments: usize,
+    pub members: usize,
+    pub suspended_members: usize,
+}
+
+impl Summary {
+    fn new(
+        person_list: &mut PersonList,
+        department_list: &mut DepartmentList,
+        member_list: &mut MemberList,
+    ) -> Summary {
+        let members: usize = member_list
+            .into_iter()
+            .map(|(_department_id, person_ids)| person_ids.len())
+            .sum();
+        return Summary {
+            persons: person_list.into_iter().count(),
+            deleted_persons: person_list.deleted_persons().len(),
+            departments: department_list.into_iter().count(),
+            deleted_departments: department_list.deleted_departments().len(),
+            members: members,
+            suspended_members: member_list.suspended_members().len(),
+        };
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f,
+                "{} person(s) ({} deleted), {} department(s) ({} deleted), {} membership(s) ({} suspended)",
+                self.persons,
+                self.deleted_persons,
+                self.departments,
+                self.deleted_departments,
+                self.members,
+                self.suspended_members
+            );
+    }
+}
+
+struct Lists {
+    person_list: PersonList,
+    department_list: DepartmentList,
+    member_list: MemberList,
+}
+
+impl Lists {
+    fn load(repositories: &Repositories) -> Result<Lists, Box<dyn Error>> {
+        let mut person_list = PersonList::new();
+        let mut department_list = DepartmentList::new();
+        let mut member_list = MemberList::new();
+        repositories.person.read_all_data(&mut person_list)?;
+        repositories
+            .department
+            .read_all_data(&mut department_list)?;
+        repositories
+            .member
+            .read_all_data(&mut member_list, &person_list, &department_list)?;
+        return Ok(Lists {
+            person_list: person_list,
+            department_list: department_list,
+            member_list: member_list,
+        });
+    }
+
+    fn save(&mut self, repositories: &Repositories) -> Result<(), Box<dyn Error>> {
+        repositories
+            .person
+            .overwrite_all_data(&mut self.person_list)?;
+        repositories
+            .department
+            .overwrite_all_data(&mut self.department_list)?;
+        repositories
+            .member
+            .overwrite_all_data(&mut self.member_list)?;
+        return Ok(());
+    }
+
+    fn summary(&mut self) -> Summary {
+        return Summary::new(
+            &mut self.person_list,
+            &mut self.department_list,
+            &mut self.member_list,
+        );
+    }
+
+    // 削除済のデータも含めた組織全体の内容
+    // 移行の前後で比較するため、exportと同じ形式とする
+    fn contents(&mut self) -> String {
+        return export::to_json(
+            true,
+            &mut self.person_list,
+            &mut self.department_list,
+            &mut self.member_list,
+        )
+        .to_json();
+    }
+}
+
+// sourceの全データをtargetに書き込む
+// 書き込んだ後にtargetから読み直し、件数と所属を含む全ての内容が一致することを確かめる
+// sourceとtargetが同じ保存形式の場合は、現在のバージョンの形式で書き直すことになる
+pub fn migrate(source: &Repositories, target: &Repositories) -> Result<Summary, Box<dyn Error>> {
+    let mut lists = Lists::load(source)?;
+    let summary = lists.summary();
+    let contents = lists.contents();
+    lists.save(target)?;
+
+    let mut written = Lists::load(target)?;
+    let written_summary = written.summary();
+    if written_summary != summary {
+        let message = format!(
+            "migrate::migrate : Verification failed! (source: {}, target: {})",
+            summary, written_summary
+        );
+        return Err(message.into());
+    }
+    if written.contents() != contents {
+        return Err("migrate::migrate : Verification failed! (contents differ)".into());
+    }
+    return Ok(summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::datasource::department::TextFileDepartment;
+    use super::super::super::datasource::member::TextFileMember;
+    use super::super::super::datasource::person::TextFilePerson;
+    use super::super::super::datasource::Storage;
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn migrate_text_to_json_to_sqlite_ok() {
+        let dir = std::env::temp_dir().join(format!("hr_migrate_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir_path = dir.to_str().unwrap();
+        let file_path = |file_name: &str| format!("{}{}", dir_path, file_name);
+        fs::write(
+            file_path(TextFilePerson::FILE_NAME),
+            "1 Potter Harry employee_number=E001 nickname=Boy\n2 Weasley Ron deleted_at=100\n",
+        )
+        .unwrap();
+        fs::write(
+            file_path(TextFileDepartment::FILE_NAME),
+            "1 Gryffindor\n2 Slytherin deleted_at=200\n",
+        )
+        .unwrap();
+        fs::write(file_path(TextFileMember::FILE_NAME), "1 1\n1 2 suspended\n").unwrap();
+
+        let text = Storage::Text.repositories(dir_path);
+        let json = Storage::Json.repositories(dir_path);
+        let sqlite = Storage::Sqlite.repositories(dir_path);
+        let summary = migrate(&text, &json).unwrap();
+        assert_eq!(
+            summary,
+            Summary {
+                persons: 1,
+                deleted_persons: 1,
+                departments: 1,
+                deleted_departments: 1,
+                members: 1,
+                suspended_members: 1,
+            }
+        );
+        assert_eq!(migrate(&json, &sqlite).unwrap(), summary);
+        assert_eq!(migrate(&sqlite, &sqlite).unwrap(), summary);
+        assert_eq!(Storage::Sqlite.schema_version(dir_path).unwrap(), Some(2));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,2 +1,22 @@
+pub mod check;
+pub mod command;
+pub mod export;
+pub mod expression;
+pub mod filter;
+pub mod history;
+pub mod hr_service;
+pub mod import;
+pub mod message;
+pub mod migrate;
+pub mod org_chart;
+pub mod pager;
 mod query;
+pub mod repl;
+pub mod server;
 pub mod service;
+pub mod stats;
+
+pub use hr_service::{
+    DeletedDepartment, DeletedPerson, DepartmentEntry, DepartmentMembers, HrService, ImportSummary,
+    MergedDepartments, Organization, PersonChanges, PersonEntry, PersonOrder, Purged,
+};
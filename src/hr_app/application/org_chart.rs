@@ -0,0 +1,221 @@
+use super::hr_service::{Organization, PersonEntry};
+use std::collections::BTreeMap;
+
+// 組織図の出力形式
+// DotはGraphviz、MermaidはMarkdownなどに埋め込めるflowchartとして出力する
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ChartFormat {
+    Dot,
+    Mermaid,
+}
+
+impl ChartFormat {
+    pub const NAMES: [&'static str; 2] = ["dot", "mermaid"];
+
+    pub fn new(value: &str) -> Result<ChartFormat, &'static str> {
+        match value {
+            "dot" => return Ok(ChartFormat::Dot),
+            "mermaid" => return Ok(ChartFormat::Mermaid),
+            _ => return Err("ChartFormat::new : format must be dot or mermaid"),
+        }
+    }
+}
+
+// 組織図に含める範囲
+// include_emptyは所属者のいない部署、include_unassignedはどの部署にも所属していない人を含めるかどうか
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct ChartOptions {
+    pub include_empty: bool,
+    pub include_unassigned: bool,
+}
+
+// どの部署にも所属していない人をまとめるノードの名前とラベル
+const UNASSIGNED_NODE: &str = "unassigned";
+const UNASSIGNED_LABEL: &str = "Unassigned";
+
+// 図の構成要素
+// ノード名は部署が "d<ID>"、人が "p<ID>" で、複数の部署に所属する人も１つのノードとする
+struct Chart {
+    departments: Vec<(String, String)>,
+    persons: Vec<(String, String)>,
+    edges: Vec<(String, String)>,
+}
+
+fn person_node(entry: &PersonEntry) -> String {
+    return format!("p{}", entry.id);
+}
+
+impl Chart {
+    fn new(organization: &Organization, options: ChartOptions) -> Chart {
+        let mut departments: Vec<(String, String)> = Vec::new();
+        let mut persons: BTreeMap<_, (String, String)> = BTreeMap::new();
+        let mut edges: Vec<(String, String)> = Vec::new();
+        for department in organization.departments.iter() {
+            if department.members.is_empty() && !options.include_empty {
+                continue;
+            }
+            let node = format!("d{}", department.department.id);
+            for member in department.members.iter() {
+                persons.insert(member.id, (person_node(member), member.person.name()));
+                edges.push((node.clone(), person_node(member)));
+            }
+            departments.push((node, department.department.name.clone()));
+        }
+        if options.include_unassigned && !organization.unassigned.is_empty() {
+            let node = String::from(UNASSIGNED_NODE);
+            for entry in organization.unassigned.iter() {
+                persons.insert(entry.id, (person_node(entry), entry.person.name()));
+                edges.push((node.clone(), person_node(entry)));
+            }
+            departments.push((node, String::from(UNASSIGNED_LABEL)));
+        }
+        return Chart {
+            departments: departments,
+            persons: persons.into_values().collect(),
+            edges: edges,
+        };
+    }
+
+    // 部署はフォルダ型、どの部署にも所属していない人のまとまりは破線で表す
+    fn to_dot(&self) -> String {
+        let label = |name: &str| name.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut text =
+            String::from("digraph org_chart {\n    rankdir=LR;\n    node [shape=box];\n");
+        for (node, name) in self.departments.iter() {
+            let style = if node == UNASSIGNED_NODE {
+                ", style=dashed"
+            } else {
+                ""
+            };
+            text.push_str(&format!(
+                "    {} [label=\"{}\", shape=folder{}];\n",
+                node,
+                label(name),
+                style
+            ));
+        }
+        for (node, name) in self.persons.iter() {
+            text.push_str(&format!("    {} [label=\"{}\"];\n", node, label(name)));
+        }
+        for (from, to) in self.edges.iter() {
+            text.push_str(&format!("    {} -> {};\n", from, to));
+        }
+        text.push_str("}\n");
+        return text;
+    }
+
+    // 部署はサブルーチン型 [[...]]、人は四角 [...] のノードで表す
+    fn to_mermaid(&self) -> String {
+        let label = |name: &str| name.replace('"', "#quot;");
+        let mut text = String::from("flowchart LR\n");
+        for (node, name) in self.departments.iter() {
+            text.push_str(&format!("    {}[[\"{}\"]]\n", node, label(name)));
+        }
+        for (node, name) in self.persons.iter() {
+            text.push_str(&format!("    {}[\"{}\"]\n", node, label(name)));
+        }
+        for (from, to) in self.edges.iter() {
+            text.push_str(&format!("    {} --> {}\n", from, to));
+        }
+        return text;
+    }
+}
+
+// 部署とその所属者を組織図のテキストに変換する
+// 部署に階層は無いので、部署から所属者への１段の図になる
+pub fn render(organization: &Organization, format: ChartFormat, options: ChartOptions) -> String {
+    let chart = Chart::new(organization, options);
+    match format {
+        ChartFormat::Dot => return chart.to_dot(),
+        ChartFormat::Mermaid => return chart.to_mermaid(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::domain::department::id::DepartmentId;
+    use super::super::super::domain::person::id::PersonId;
+    use super::super::super::domain::person::Person;
+    use super::super::hr_service::{DepartmentEntry, DepartmentMembers};
+    use super::*;
+
+    fn person(id: u64, last_name: &str, first_name: &str) -> PersonEntry {
+        return PersonEntry {
+            id: PersonId::new(id),
+            person: Person::new(last_name, Some(first_name)).unwrap(),
+        };
+    }
+
+    fn department(id: u64, name: &str, members: Vec<PersonEntry>) -> DepartmentMembers {
+        return DepartmentMembers {
+            department: DepartmentEntry {
+                id: DepartmentId::new(id),
+                name: String::from(name),
+            },
+            members: members,
+        };
+    }
+
+    // Harryは2つの部署に所属し、Dracoはどの部署にも所属していない
+    fn generate_organization() -> Organization {
+        let harry = person(1, "Potter", "Harry");
+        let ron = person(2, "Weasley", "Ron");
+        return Organization {
+            departments: vec![
+                department(1, "Gryffindor", vec![harry.clone(), ron]),
+                department(2, "Quidditch \"Team\"", vec![harry]),
+                department(3, "Hufflepuff", vec![]),
+            ],
+            unassigned: vec![person(3, "Malfoy", "Draco")],
+        };
+    }
+
+    #[test]
+    fn render_dot_ok() {
+        let options = ChartOptions {
+            include_empty: true,
+            include_unassigned: false,
+        };
+        let dot = render(&generate_organization(), ChartFormat::Dot, options);
+        assert!(dot.starts_with("digraph org_chart {\n"));
+        assert!(dot.contains("    d2 [label=\"Quidditch \\\"Team\\\"\", shape=folder];\n"));
+        assert!(dot.contains("    d3 [label=\"Hufflepuff\", shape=folder];\n"));
+        // 複数の部署に所属していてもノードは１つ
+        assert_eq!(dot.matches("p1 [label=\"Potter Harry\"]").count(), 1);
+        assert!(dot.contains("    d1 -> p1;\n    d1 -> p2;\n    d2 -> p1;\n"));
+        assert!(!dot.contains("Malfoy"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn render_mermaid_ok() {
+        let options = ChartOptions {
+            include_empty: false,
+            include_unassigned: true,
+        };
+        let mermaid = render(&generate_organization(), ChartFormat::Mermaid, options);
+        let lines: Vec<&str> = mermaid.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "flowchart LR",
+                "    d1[[\"Gryffindor\"]]",
+                "    d2[[\"Quidditch #quot;Team#quot;\"]]",
+                "    unassigned[[\"Unassigned\"]]",
+                "    p1[\"Potter Harry\"]",
+                "    p2[\"Weasley Ron\"]",
+                "    p3[\"Malfoy Draco\"]",
+                "    d1 --> p1",
+                "    d1 --> p2",
+                "    d2 --> p1",
+                "    unassigned --> p3",
+            ]
+        );
+    }
+
+    #[test]
+    fn chart_format_new_err() {
+        assert_eq!(ChartFormat::new("mermaid"), Ok(ChartFormat::Mermaid));
+        assert!(ChartFormat::new("svg").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use super::message::{Language, Message};
+use std::env;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+// 端末の行数が分からない場合の１画面の行数
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+// 一覧表示の出力を溜めておき、まとめて表示する
+// 標準入力と標準出力がどちらも端末の場合のみ、１画面分ずつ表示してEnterの入力を待つ
+// パイプやファイルからコマンドを入力している場合は、次のコマンドを読んでしまわないように全て表示する
+pub struct Pager {
+    lines: Vec<String>,
+}
+
+impl Pager {
+    pub fn new() -> Pager {
+        return Pager { lines: Vec::new() };
+    }
+
+    pub fn push(&mut self, line: String) {
+        self.lines.push(line);
+    }
+
+    pub fn show(&self, language: Language) -> io::Result<()> {
+        let stdin = io::stdin();
+        let mut stdout = io::stdout();
+        if !stdin.is_terminal() || !stdout.is_terminal() {
+            return self.write(None, &mut stdin.lock(), &mut stdout, "");
+        }
+        let prompt = language.text(Message::PagerPrompt);
+        return self.write(Some(page_size()), &mut stdin.lock(), &mut stdout, prompt);
+    }
+
+    // page_sizeがNoneの場合は全ての行を続けて出力する
+    // 入力の終わりまたは "q" で残りの出力を打ち切る
+    fn write<R: BufRead, W: Write>(
+        &self,
+        page_size: Option<usize>,
+        input: &mut R,
+        output: &mut W,
+        prompt: &str,
+    ) -> io::Result<()> {
+        for (index, line) in self.lines.iter().enumerate() {
+            if let Some(page_size) = page_size {
+                if index > 0 && index % page_size == 0 {
+                    write!(output, "{}", prompt)?;
+                    output.flush()?;
+                    let mut answer = String::new();
+                    if input.read_line(&mut answer)? == 0 || answer.trim() == "q" {
+                        return Ok(());
+                    }
+                }
+            }
+            writeln!(output, "{}", line)?;
+        }
+        return Ok(());
+    }
+}
+
+impl Default for Pager {
+    fn default() -> Pager {
+        return Pager::new();
+    }
+}
+
+// 環境変数LINESがあれば、プロンプトの１行を除いた行数を１画面とする
+fn page_size() -> usize {
+    let lines: Option<usize> = env::var("LINES").ok().and_then(|lines| lines.parse().ok());
+    match lines {
+        Some(lines) if lines > 1 => return lines - 1,
+        _ => return DEFAULT_PAGE_SIZE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_pager(count: usize) -> Pager {
+        let mut pager = Pager::new();
+        for number in 1..=count {
+            pager.push(number.to_string());
+        }
+        return pager;
+    }
+
+    #[test]
+    fn write_pages_ok() {
+        let pager = generate_pager(5);
+        let mut input = "\n".as_bytes();
+        let mut output: Vec<u8> = Vec::new();
+        pager
+            .write(Some(2), &mut input, &mut output, "--more--")
+            .unwrap();
+        // 2ページ目の後で入力が終わるので、5行目は表示しない
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "1\n2\n--more--3\n4\n--more--"
+        );
+    }
+
+    #[test]
+    fn write_quit_ok() {
+        let pager = generate_pager(5);
+        let mut input = "q\n\n".as_bytes();
+        let mut output: Vec<u8> = Vec::new();
+        pager.write(Some(2), &mut input, &mut output, ">").unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "1\n2\n>");
+    }
+
+    #[test]
+    fn write_without_paging_ok() {
+        let pager = generate_pager(3);
+        let mut input = "".as_bytes();
+        let mut output: Vec<u8> = Vec::new();
+        pager.write(None, &mut input, &mut output, ">").unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "1\n2\n3\n");
+    }
+}
@@ -1,13 +1,51 @@
+use super::command::{CommandSpec, ParsedCommand};
+use super::filter::{Paging, PersonFilter};
+use super::message::Language;
+use std::error::Error;
+
+// 一覧表示の絞り込み条件(--filter)と表示範囲(--offset, --limit)
+// 値は未解析の文字列のまま持ち、実行時に解析する
+pub struct ListOptions {
+    pub filters: Vec<String>,
+    pub offset: Option<String>,
+    pub limit: Option<String>,
+}
+
+impl ListOptions {
+    fn new(parsed: &ParsedCommand) -> ListOptions {
+        return ListOptions {
+            filters: parsed.option_values("filter"),
+            offset: parsed.option("offset"),
+            limit: parsed.option("limit"),
+        };
+    }
+
+    pub fn filters(&self) -> Result<Vec<PersonFilter>, Box<dyn Error>> {
+        return self
+            .filters
+            .iter()
+            .map(|filter| PersonFilter::new(filter))
+            .collect();
+    }
+
+    pub fn paging(&self) -> Result<Paging, Box<dyn Error>> {
+        return Paging::new(self.offset.as_deref(), self.limit.as_deref());
+    }
+}
+
 pub enum Query {
     CreateDepartment {
         department_name: String,
     },
 
-    ShowAllDepartments,
+    ShowAllDepartments {
+        list: ListOptions,
+    },
 
     // DONE
     ShowDepartmentInfo {
         department_id: String,
+        list: ListOptions,
     },
 
     // DONE
@@ -19,10 +57,14 @@ pub enum Query {
     CreatePerson {
         last_name: String,
         first_name: Option<String>,
+        attributes: Vec<(String, String)>,
     },
 
     // Done
-    ShowAllPersons,
+    ShowAllPersons {
+        sort_key: Option<String>,
+        list: ListOptions,
+    },
 
     // DONE
     ShowPersonInfo {
@@ -43,191 +85,245 @@ pub enum Query {
         person_id: String,
         department_id: String,
     },
-    ShowAllPersonsByDepartment,
-    Help,
+    TransferPerson {
+        person_id: String,
+        from_department_id: String,
+        to_department_id: String,
+    },
+    MergeDepartments {
+        source_department_id: String,
+        target_department_id: String,
+    },
+    SplitDepartment {
+        department_id: String,
+        department_name: String,
+        person_ids: Vec<String>,
+    },
+    RenamePerson {
+        person_id: String,
+        last_name: String,
+        first_name: Option<String>,
+        attributes: Vec<(String, String)>,
+    },
+    RenameDepartment {
+        department_id: String,
+        department_name: String,
+    },
+    FindPerson {
+        text: String,
+    },
+    FindDepartment {
+        text: String,
+    },
+    RestorePerson {
+        person_id: String,
+    },
+    RestoreDepartment {
+        department_id: String,
+    },
+    ShowDeleted,
+    Purge {
+        days: Option<String>,
+        dry_run: bool,
+    },
+    ImportCsv {
+        target: String,
+        path: String,
+        dry_run: bool,
+    },
+    Export {
+        format: String,
+        path: String,
+        include_deleted: bool,
+    },
+    OrgChart {
+        format: String,
+        path: Option<String>,
+        skip_empty: bool,
+        unassigned: bool,
+    },
+    Stats {
+        format: Option<String>,
+        path: Option<String>,
+    },
+    Evaluate {
+        expression: String,
+    },
+    Undo,
+    Redo,
+    ShowAllPersonsByDepartment {
+        list: ListOptions,
+    },
+    Help {
+        command: Option<String>,
+    },
     Quit,
 }
 
 impl Query {
-    pub fn new(commands: String) -> Result<Query, String> {
-        let mut command = commands.split_whitespace();
-
-        match command.next() {
-            Some("create_department") => {
-                let name = match command.next() {
-                    Some(name) => name,
-                    None => {
-                        let message = Query::err_message(
-                            "department_name is missing",
-                            "create_department <department_name>",
-                        );
-                        return Err(message);
-                    }
-                };
-                return Ok(Query::CreateDepartment {
-                    department_name: String::from(name),
-                });
-            }
-            Some("show_all_departments") => return Ok(Query::ShowAllDepartments),
-            Some("department_info") => {
-                let id = match command.next() {
-                    Some(id) => String::from(id),
-                    None => {
-                        let message = Query::err_message(
-                            "department_id is missing",
-                            "department_info <department_id>",
-                        );
-                        return Err(message);
-                    }
-                };
-                return Ok(Query::ShowDepartmentInfo { department_id: id });
-            }
-            Some("delete_department") => {
-                let id = match command.next() {
-                    Some(id) => id,
-                    None => {
-                        let message = Query::err_message(
-                            "department_id is missing",
-                            "delete_department <department_id>",
-                        );
-                        return Err(message);
-                    }
-                };
-                return Ok(Query::DeleteDepartment {
-                    department_id: String::from(id),
-                });
-            }
-            Some("create_person") => {
-                let last_name = match command.next() {
-                    Some(name) => name,
-                    None => {
-                        let message = Query::err_message(
-                            "person_name is missing",
-                            "create_person <last_name> <first_name>(optional)",
-                        );
-                        return Err(message);
-                    }
-                };
-                let first_name: Option<String> = match command.next() {
-                    Some(name) => Some(String::from(name)),
-                    None => None,
-                };
-
-                return Ok(Query::CreatePerson {
-                    last_name: String::from(last_name),
-                    first_name: first_name,
-                });
-            }
-            Some("show_all_persons") => return Ok(Query::ShowAllPersons),
-            Some("person_info") => {
-                let id = match command.next() {
-                    Some(id) => String::from(id),
-                    None => {
-                        let message =
-                            Query::err_message("person_id is missing", "person_info <person_id>");
-                        return Err(message);
-                    }
-                };
-                return Ok(Query::ShowPersonInfo { person_id: id });
-            }
-            Some("delete_person") => {
-                let id: String = match command.next() {
-                    Some(id) => String::from(id),
-                    None => {
-                        let message =
-                            Query::err_message("person_id is missing", "delete_person <person_id>");
-                        return Err(message);
-                    }
-                };
-                return Ok(Query::DeletePerson { person_id: id });
-            }
-            Some("add_person") => {
-                let person_id = match command.next() {
-                    Some(id) => String::from(id),
-                    None => {
-                        let message = Query::err_message(
-                            "person_id is missing",
-                            "add_person <person_id> <department_id>",
-                        );
-                        return Err(message);
-                    }
-                };
-                let department_id = match command.next() {
-                    Some(id) => String::from(id),
-                    None => {
-                        let message = Query::err_message(
-                            "department_id is missing",
-                            "add_person <person_id> <department_id>",
-                        );
-                        return Err(message);
-                    }
-                };
-                return Ok(Query::AddPersonToDepartment {
-                    person_id: person_id,
-                    department_id: department_id,
-                });
-            }
-            Some("remove_person") => {
-                let person_id = match command.next() {
-                    Some(id) => String::from(id),
-                    None => {
-                        let message = Query::err_message(
-                            "person_id is missing",
-                            "remove_person <person_id> <department_id>",
-                        );
-                        return Err(message);
-                    }
-                };
-                let department_id = match command.next() {
-                    Some(id) => String::from(id),
-                    None => {
-                        let message = Query::err_message(
-                            "department_id is missing",
-                            "remove_person <person_id> <department_id>",
-                        );
-                        return Err(message);
-                    }
-                };
-                return Ok(Query::RemovePersonFromDepartment {
-                    person_id: person_id,
-                    department_id: department_id,
-                });
-            }
-            // TODO
-            Some("all_info") => return Ok(Query::ShowAllPersonsByDepartment),
-            Some("help") => return Ok(Query::Help),
-            Some("quit") => return Ok(Query::Quit),
-            _ => return Err(String::from("unrecognized command!")),
+    // データを変更して保存するコマンドかどうか
+    // 読み取り専用のセッションでは実行できず、実行前に他のプログラムによる変更を確認する
+    pub fn changes_data(&self) -> bool {
+        match self {
+            Query::CreateDepartment { .. }
+            | Query::DeleteDepartment { .. }
+            | Query::CreatePerson { .. }
+            | Query::DeletePerson { .. }
+            | Query::AddPersonToDepartment { .. }
+            | Query::RemovePersonFromDepartment { .. }
+            | Query::TransferPerson { .. }
+            | Query::MergeDepartments { .. }
+            | Query::SplitDepartment { .. }
+            | Query::RenamePerson { .. }
+            | Query::RenameDepartment { .. }
+            | Query::RestorePerson { .. }
+            | Query::RestoreDepartment { .. }
+            | Query::Undo
+            | Query::Redo => return true,
+            Query::Purge { dry_run, .. } | Query::ImportCsv { dry_run, .. } => return !dry_run,
+            _ => return false,
         }
     }
 
-    pub fn print_help() {
-        let command_list: Vec<&str> = vec![
-            "create_department <department_name>              : create a new department and add it to the department list",
-            "show_all_departments                             : show all departments in the list",
-            "department_info <department_id>                  : show an information of the department",
-            "delete_department <department_id>                : delete a department from the list",
-            "create_person <last_name> <first_name>(optional) : create a new person and add it to the person list",
-            "show_all_persons                                 : show all persons in the list",
-            "person_info <person_id>                          : show an information of the person",
-            "delete_person <person_id>                        : delete a person from the list",
-            "add_person <person_id> <department_id>           : add a person to the department",
-            "remove_person <person_id> <department_id>        : remove a person from the department",
-            "all_info                                         : show all persons by department",
-            "help                                             : show help ",
-            "quit                                             : finish this application",
-        ];
-        println!("~~HELP~~");
-        for text in command_list {
-            println!("{}", text);
-        }
+    // コマンドの表(command::COMMANDS)に従って入力を解析する
+    pub fn new(commands: String, language: Language) -> Result<Query, String> {
+        let mut tokens = commands.split_whitespace();
+        let name = match tokens.next() {
+            Some(name) => name,
+            None => return Err(CommandSpec::unrecognized("", language)),
+        };
+        let spec = match CommandSpec::find(name) {
+            Some(spec) => spec,
+            None => return Err(CommandSpec::unrecognized(name, language)),
+        };
+        let parsed = spec.parse(tokens, language)?;
+        return Query::from_parsed(parsed, language);
     }
 
-    fn err_message(message: &str, how_to_use: &str) -> String {
-        let mut err_message = String::new();
-        err_message.push_str(message);
-        err_message.push_str("\nhow to use:\n ");
-        err_message.push_str(how_to_use);
-        return err_message;
+    fn from_parsed(parsed: ParsedCommand, language: Language) -> Result<Query, String> {
+        let query = match parsed.spec.name {
+            "create_department" => Query::CreateDepartment {
+                department_name: parsed.argument(0),
+            },
+            "show_all_departments" => Query::ShowAllDepartments {
+                list: ListOptions::new(&parsed),
+            },
+            "department_info" => Query::ShowDepartmentInfo {
+                department_id: parsed.argument(0),
+                list: ListOptions::new(&parsed),
+            },
+            "delete_department" => Query::DeleteDepartment {
+                department_id: parsed.argument(0),
+            },
+            "create_person" => Query::CreatePerson {
+                last_name: parsed.argument(0),
+                first_name: parsed.optional_argument(1),
+                attributes: parsed.attributes(),
+            },
+            "show_all_persons" => Query::ShowAllPersons {
+                sort_key: parsed.optional_argument(0),
+                list: ListOptions::new(&parsed),
+            },
+            "person_info" => Query::ShowPersonInfo {
+                person_id: parsed.argument(0),
+            },
+            "delete_person" => Query::DeletePerson {
+                person_id: parsed.argument(0),
+            },
+            "add_person" => Query::AddPersonToDepartment {
+                person_id: parsed.argument(0),
+                department_id: parsed.argument(1),
+            },
+            "remove_person" => Query::RemovePersonFromDepartment {
+                person_id: parsed.argument(0),
+                department_id: parsed.argument(1),
+            },
+            "transfer_person" => Query::TransferPerson {
+                person_id: parsed.argument(0),
+                from_department_id: parsed.argument(1),
+                to_department_id: parsed.argument(2),
+            },
+            "merge_departments" => Query::MergeDepartments {
+                source_department_id: parsed.argument(0),
+                target_department_id: parsed.argument(1),
+            },
+            "split_department" => Query::SplitDepartment {
+                department_id: parsed.argument(0),
+                department_name: parsed.argument(1),
+                person_ids: parsed
+                    .argument(2)
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect(),
+            },
+            "rename_person" => Query::RenamePerson {
+                person_id: parsed.argument(0),
+                last_name: parsed.argument(1),
+                first_name: parsed.optional_argument(2),
+                attributes: parsed.attributes(),
+            },
+            "rename_department" => Query::RenameDepartment {
+                department_id: parsed.argument(0),
+                department_name: parsed.argument(1),
+            },
+            "find_person" => Query::FindPerson {
+                text: parsed.argument(0),
+            },
+            "find_department" => Query::FindDepartment {
+                text: parsed.argument(0),
+            },
+            "restore_person" => Query::RestorePerson {
+                person_id: parsed.argument(0),
+            },
+            "restore_department" => Query::RestoreDepartment {
+                department_id: parsed.argument(0),
+            },
+            "show_deleted" => Query::ShowDeleted,
+            "purge" => Query::Purge {
+                days: parsed.optional_argument(0),
+                dry_run: parsed.flag("dry-run"),
+            },
+            "import_csv" => Query::ImportCsv {
+                target: parsed.argument(0),
+                path: parsed.argument(1),
+                dry_run: parsed.flag("dry-run"),
+            },
+            "export" => Query::Export {
+                format: parsed.argument(0),
+                path: parsed.argument(1),
+                include_deleted: parsed.flag("include-deleted"),
+            },
+            "org_chart" => Query::OrgChart {
+                format: parsed.argument(0),
+                path: parsed.optional_argument(1),
+                skip_empty: parsed.flag("skip-empty"),
+                unassigned: parsed.flag("unassigned"),
+            },
+            "stats" => Query::Stats {
+                format: parsed.optional_argument(0),
+                path: parsed.optional_argument(1),
+            },
+            "query" => Query::Evaluate {
+                expression: parsed.argument(0),
+            },
+            "undo" => Query::Undo,
+            "redo" => Query::Redo,
+            "all_info" => Query::ShowAllPersonsByDepartment {
+                list: ListOptions::new(&parsed),
+            },
+            "help" => {
+                let command = match parsed.optional_argument(0) {
+                    Some(name) => match CommandSpec::find(&name) {
+                        Some(spec) => Some(String::from(spec.name)),
+                        None => return Err(CommandSpec::unrecognized(&name, language)),
+                    },
+                    None => None,
+                };
+                Query::Help { command: command }
+            }
+            "quit" => Query::Quit,
+            name => return Err(CommandSpec::unrecognized(name, language)),
+        };
+        return Ok(query);
     }
 }
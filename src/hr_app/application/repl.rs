@@ -0,0 +1,279 @@
+use super::command::{ArgumentKind, CommandSpec, COMMANDS};
+use super::expression::OPERATOR_CHARS;
+use super::hr_service::{DepartmentEntry, PersonEntry};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::error::Error;
+
+const PROMPT: &str = "> ";
+const HISTORY_FILE: &str = "/history.txt";
+
+// 補完候補となる個人または部署
+// idとキーワード(名前等)のどちらを入力しても補完できるようにする
+struct Entry {
+    id: String,
+    name: String,
+    keywords: Vec<String>,
+}
+
+// コマンド名と、コマンドの引数に応じた個人、部署のID、名前を補完する
+struct CommandHelper {
+    person_entries: Vec<Entry>,
+    department_entries: Vec<Entry>,
+}
+
+impl CommandHelper {
+    fn new() -> CommandHelper {
+        return CommandHelper {
+            person_entries: Vec::new(),
+            department_entries: Vec::new(),
+        };
+    }
+
+    // 入力途中の語wordに前方一致するコマンド名を返す
+    fn command_candidates(word: &str) -> Vec<Pair> {
+        return COMMANDS
+            .iter()
+            .filter(|spec| spec.name.starts_with(word))
+            .map(|spec| Pair {
+                display: String::from(spec.name),
+                replacement: String::from(spec.name),
+            })
+            .collect();
+    }
+
+    // 入力途中の語wordに前方一致する候補を返す
+    // 英字の大文字小文字は区別しない
+    fn entry_candidates(entries: &[Entry], word: &str) -> Vec<Pair> {
+        let word = word.to_lowercase();
+        let mut candidates: Vec<Pair> = Vec::new();
+        for entry in entries.iter() {
+            if entry.id.starts_with(&word) {
+                candidates.push(Pair {
+                    display: format!("{}: {}", entry.id, entry.name),
+                    replacement: entry.id.clone(),
+                });
+            }
+            for keyword in entry.keywords.iter() {
+                let exists = candidates.iter().any(|pair| pair.replacement == *keyword);
+                if keyword.to_lowercase().starts_with(&word) && !exists {
+                    candidates.push(Pair {
+                        display: keyword.clone(),
+                        replacement: keyword.clone(),
+                    });
+                }
+            }
+        }
+        return candidates;
+    }
+
+    // 式の中の "dept:" に続けて部署のIDと名前を補完する
+    // 演算子や括弧を含む名前は "..." で囲む
+    fn expression_candidates(entries: &[Entry], word: &str) -> Vec<Pair> {
+        let word = match word.strip_prefix("dept:") {
+            Some(word) => word,
+            None => return Vec::new(),
+        };
+        return CommandHelper::entry_candidates(entries, word)
+            .into_iter()
+            .map(|pair| {
+                let key = match pair.replacement.contains(OPERATOR_CHARS) {
+                    true => format!("\"{}\"", pair.replacement),
+                    false => pair.replacement,
+                };
+                Pair {
+                    display: pair.display,
+                    replacement: format!("dept:{}", key),
+                }
+            })
+            .collect();
+    }
+}
+
+impl Completer for CommandHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> Result<(usize, Vec<Pair>), ReadlineError> {
+        let line = &line[..pos];
+        let start = match line.rfind(char::is_whitespace) {
+            Some(index) => index + 1,
+            None => 0,
+        };
+        let word = &line[start..];
+        let words: Vec<&str> = line[..start].split_whitespace().collect();
+
+        let kind = match words.first() {
+            None => ArgumentKind::Command,
+            Some(command) => match CommandSpec::find(command) {
+                Some(spec) => spec.argument_kind(words.len() - 1),
+                None => ArgumentKind::Other,
+            },
+        };
+        let candidates = match kind {
+            ArgumentKind::Command => CommandHelper::command_candidates(word),
+            ArgumentKind::Person | ArgumentKind::Persons => {
+                CommandHelper::entry_candidates(&self.person_entries, word)
+            }
+            ArgumentKind::Department => {
+                CommandHelper::entry_candidates(&self.department_entries, word)
+            }
+            ArgumentKind::Expression => {
+                CommandHelper::expression_candidates(&self.department_entries, word)
+            }
+            ArgumentKind::Other => Vec::new(),
+        };
+        return Ok((start, candidates));
+    }
+}
+
+impl Hinter for CommandHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CommandHelper {}
+
+impl Validator for CommandHelper {}
+
+impl Helper for CommandHelper {}
+
+// 対話入力を行う
+// 行編集、データディレクトリへの入力履歴の保存、Tabキーによる補完に対応する
+pub struct Repl {
+    editor: Editor<CommandHelper, FileHistory>,
+    history_path: String,
+}
+
+impl Repl {
+    pub fn new(dir_path: &str) -> Result<Repl, Box<dyn Error>> {
+        let mut editor: Editor<CommandHelper, FileHistory> = Editor::new()?;
+        editor.set_helper(Some(CommandHelper::new()));
+        let history_path = format!("{}{}", dir_path, HISTORY_FILE);
+        // 初回起動時は履歴ファイルが存在しないので、読み込みの失敗は無視する
+        let _ = editor.load_history(&history_path);
+        return Ok(Repl {
+            editor: editor,
+            history_path: history_path,
+        });
+    }
+
+    // 補完候補を現在の個人、部署のリストに合わせて更新する
+    pub fn update_candidates(&mut self, persons: &[PersonEntry], departments: &[DepartmentEntry]) {
+        let mut person_entries: Vec<Entry> = Vec::new();
+        for entry in persons.iter() {
+            let person_name = entry.person.person_name();
+            let mut keywords: Vec<String> = vec![String::from(person_name.last_name())];
+            if let Some(first_name) = person_name.first_name() {
+                keywords.push(String::from(first_name));
+            }
+            if let Some(nickname) = person_name.nickname() {
+                keywords.push(String::from(nickname));
+            }
+            person_entries.push(Entry {
+                id: entry.id.to_string(),
+                name: entry.person.name(),
+                keywords: keywords,
+            });
+        }
+
+        let mut department_entries: Vec<Entry> = Vec::new();
+        for department in departments.iter() {
+            department_entries.push(Entry {
+                id: department.id.to_string(),
+                name: department.name.clone(),
+                keywords: vec![department.name.clone()],
+            });
+        }
+
+        if let Some(helper) = self.editor.helper_mut() {
+            helper.person_entries = person_entries;
+            helper.department_entries = department_entries;
+        }
+    }
+
+    // プロンプトを表示して１行読み込む
+    // Ctrl-D(入力の終わり)の場合はNoneを返す
+    // Ctrl-Cの場合は入力中の行を破棄して空文字列を返す
+    pub fn read_line(&mut self) -> Result<Option<String>, Box<dyn Error>> {
+        match self.editor.readline(PROMPT) {
+            Ok(line) => {
+                let line = line.trim().to_string();
+                if !line.is_empty() {
+                    self.editor.add_history_entry(line.as_str())?;
+                    self.editor.save_history(&self.history_path)?;
+                }
+                return Ok(Some(line));
+            }
+            Err(ReadlineError::Interrupted) => return Ok(Some(String::new())),
+            Err(ReadlineError::Eof) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyline::history::MemHistory;
+
+    fn complete(helper: &CommandHelper, line: &str) -> (usize, Vec<String>) {
+        let history = MemHistory::new();
+        let context = Context::new(&history);
+        let (start, candidates) = helper.complete(line, line.len(), &context).unwrap();
+        let replacements = candidates
+            .into_iter()
+            .map(|pair| pair.replacement)
+            .collect();
+        return (start, replacements);
+    }
+
+    fn generate_a_helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.person_entries.push(Entry {
+            id: String::from("1"),
+            name: String::from("Harry Potter"),
+            keywords: vec![String::from("Potter"), String::from("Harry")],
+        });
+        helper.department_entries.push(Entry {
+            id: String::from("2"),
+            name: String::from("Potions"),
+            keywords: vec![String::from("Potions")],
+        });
+        return helper;
+    }
+
+    #[test]
+    fn complete_command_name_ok() {
+        let helper = generate_a_helper();
+        let (start, candidates) = complete(&helper, "rest");
+        assert_eq!(start, 0);
+        assert_eq!(candidates, vec!["restore_person", "restore_department"]);
+    }
+
+    #[test]
+    fn complete_argument_by_kind_ok() {
+        let helper = generate_a_helper();
+        let (start, candidates) = complete(&helper, "add_person pot");
+        assert_eq!(start, 11);
+        assert_eq!(candidates, vec!["Potter"]);
+
+        let (_start, candidates) = complete(&helper, "add_person 1 pot");
+        assert_eq!(candidates, vec!["Potions"]);
+    }
+
+    #[test]
+    fn complete_id_ok() {
+        let helper = generate_a_helper();
+        let (_start, candidates) = complete(&helper, "person_info ");
+        assert_eq!(candidates, vec!["1", "Potter", "Harry"]);
+    }
+}
@@ -0,0 +1,910 @@
+use super::super::domain::department::id::DepartmentId;
+use super::super::domain::error::{DomainError, ErrorKind};
+use super::super::domain::person::id::PersonId;
+use super::super::domain::person::Person;
+use super::super::format::json::{self, Value};
+use super::export;
+use super::hr_service::{DepartmentEntry, HrService, PersonChanges, PersonEntry, PersonOrder};
+use super::message::{Language, Message};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+// 受け付けるリクエスト本文の最大の大きさ(バイト)
+const MAX_BODY_LENGTH: usize = 1024 * 1024;
+// 送信が止まった接続が他の接続を待たせ続けないように、リクエスト全体の読み込みを打ち切るまでの秒数
+// 1回の読み込みごとではなく、接続を受け付けてからの経過時間で判定する
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+
+// 受け取ったリクエストのうち、処理に必要な部分
+// ヘッダーはHost、Origin、Content-Typeのみを保持する
+pub struct Request {
+    method: String,
+    path: String,
+    host: Option<String>,
+    origin: Option<String>,
+    content_type: Option<String>,
+    body: String,
+}
+
+// 返す応答
+// 本文がない場合(204 No Content など)はbodyをNoneとする
+pub struct Response {
+    status: u16,
+    body: Option<Value>,
+}
+
+impl Response {
+    fn json(status: u16, body: Value) -> Response {
+        return Response {
+            status: status,
+            body: Some(body),
+        };
+    }
+
+    fn no_content() -> Response {
+        return Response {
+            status: 204,
+            body: None,
+        };
+    }
+
+    // エラーの種類からステータスコードを決め、{"error": メッセージ} を本文とする
+    fn error(error: &(dyn Error + 'static)) -> Response {
+        let message = error.to_string();
+        let status = status_of(error);
+        let body = Value::Object(vec![(String::from("error"), Value::String(message))]);
+        return Response::json(status, body);
+    }
+}
+
+// ステータスコードを指定して返すエラー
+// ルーティングやリクエストの形式の誤りなど、ドメインの外で発生するエラーに使う
+#[derive(Debug)]
+struct HttpError {
+    status: u16,
+    message: String,
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.message);
+    }
+}
+
+impl Error for HttpError {}
+
+fn http_error(status: u16, message: &str) -> Box<dyn Error> {
+    return Box::new(HttpError {
+        status: status,
+        message: String::from(message),
+    });
+}
+
+// HttpErrorは指定のステータスコード、DomainErrorは種類に応じたステータスコードとする
+// それ以外のエラーは保存先の読み書きなどサーバー側の問題として扱う
+fn status_of(error: &(dyn Error + 'static)) -> u16 {
+    if let Some(http_error) = error.downcast_ref::<HttpError>() {
+        return http_error.status;
+    }
+    if let Some(domain_error) = error.downcast_ref::<DomainError>() {
+        match domain_error.kind() {
+            ErrorKind::NotFound => return 404,
+            ErrorKind::Conflict => return 409,
+            ErrorKind::Invalid => return 400,
+            ErrorKind::ReadOnly => return 403,
+        }
+    }
+    if let Some(io_error) = error.downcast_ref::<io::Error>() {
+        if is_timeout(io_error) {
+            return 408;
+        }
+    }
+    return 500;
+}
+
+// 読み込みの打ち切りはプラットフォームによってWouldBlockまたはTimedOutとなる
+fn is_timeout(error: &io::Error) -> bool {
+    return matches!(
+        error.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    );
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => return "OK",
+        201 => return "Created",
+        204 => return "No Content",
+        400 => return "Bad Request",
+        403 => return "Forbidden",
+        404 => return "Not Found",
+        405 => return "Method Not Allowed",
+        408 => return "Request Timeout",
+        409 => return "Conflict",
+        413 => return "Payload Too Large",
+        415 => return "Unsupported Media Type",
+        _ => return "Internal Server Error",
+    }
+}
+
+// リクエスト行、ヘッダー、Content-Lengthの長さの本文を読み込む
+// ヘッダーはContent-Length、Host、Origin、Content-Type以外を無視する
+fn read_request<R: Read>(reader: R) -> Result<Request, Box<dyn Error>> {
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() != 3 || !parts[2].starts_with("HTTP/") {
+        return Err(http_error(400, "Invalid request line"));
+    }
+    let method = String::from(parts[0]);
+    let path = String::from(parts[1]);
+
+    let mut content_length: usize = 0;
+    let mut host: Option<String> = None;
+    let mut origin: Option<String> = None;
+    let mut content_type: Option<String> = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Err(http_error(400, "Unexpected end of headers"));
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let name = name.trim().to_ascii_lowercase();
+            let value = value.trim();
+            match name.as_str() {
+                "content-length" => {
+                    content_length = match value.parse() {
+                        Ok(length) => length,
+                        Err(_) => return Err(http_error(400, "Invalid Content-Length")),
+                    };
+                }
+                "host" => host = Some(String::from(value)),
+                "origin" => origin = Some(String::from(value)),
+                "content-type" => content_type = Some(String::from(value)),
+                _ => {}
+            }
+        }
+    }
+    if content_length > MAX_BODY_LENGTH {
+        return Err(http_error(413, "Request body is too large"));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let body = match String::from_utf8(body) {
+        Ok(body) => body,
+        Err(_) => return Err(http_error(400, "Request body must be UTF-8")),
+    };
+    return Ok(Request {
+        method: method,
+        path: path,
+        host: host,
+        origin: origin,
+        content_type: content_type,
+        body: body,
+    });
+}
+
+// 接続を受け付けてからの期限までに読み込みが終わらなければTimedOutとする
+// 読み込みのたびに残り時間をタイムアウトとして設定し直す
+struct DeadlineStream<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl<'a> Read for DeadlineStream<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Request timed out"));
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        return self.stream.read(buf);
+    }
+}
+
+// 接続は応答ごとに閉じる
+fn write_response<W: Write>(mut writer: W, response: &Response) -> Result<(), Box<dyn Error>> {
+    let body = match &response.body {
+        Some(value) => value.to_json(),
+        None => String::new(),
+    };
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\n",
+        response.status,
+        reason_phrase(response.status)
+    );
+    if response.body.is_some() {
+        head.push_str("Content-Type: application/json; charset=utf-8\r\n");
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    ));
+    writer.write_all(head.as_bytes())?;
+    writer.write_all(body.as_bytes())?;
+    writer.flush()?;
+    return Ok(());
+}
+
+// 本文のJSONオブジェクトから項目を (キー, 値) の一覧として取り出す
+// 値は文字列かnullのみを受け付け、nullは空文字列(項目の削除)として扱う
+fn body_fields(body: &str, keys: &[&str]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let value = match json::parse(body) {
+        Ok(value) => value,
+        Err(e) => return Err(http_error(400, &e.to_string())),
+    };
+    let members = match value {
+        Value::Object(members) => members,
+        _ => return Err(http_error(400, "Request body must be a JSON object")),
+    };
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for (key, value) in members {
+        if !keys.contains(&key.as_str()) {
+            return Err(http_error(400, &format!("Unknown key: {}", key)));
+        }
+        let value = match value {
+            Value::String(value) => value,
+            Value::Null => String::new(),
+            _ => {
+                return Err(http_error(
+                    400,
+                    &format!("{} must be a string or null", key),
+                ))
+            }
+        };
+        fields.push((key, value));
+    }
+    return Ok(fields);
+}
+
+fn field<'a>(fields: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    return fields
+        .iter()
+        .find(|(field_key, _value)| field_key == key)
+        .map(|(_key, value)| value.as_str());
+}
+
+// 名前以外の項目(Person::ATTRIBUTE_KEYS)
+fn attribute_fields(fields: &[(String, String)]) -> Vec<(String, String)> {
+    return fields
+        .iter()
+        .filter(|(key, _value)| Person::ATTRIBUTE_KEYS.contains(&key.as_str()))
+        .cloned()
+        .collect();
+}
+
+fn department_name(body: &str) -> Result<String, Box<dyn Error>> {
+    let fields = body_fields(body, &["name"])?;
+    match field(&fields, "name") {
+        Some(name) => return Ok(String::from(name)),
+        None => return Err(http_error(400, "name is required")),
+    }
+}
+
+// 個人は出力(export)のJSONと同じ列名のオブジェクトとする
+fn person_value(entry: &PersonEntry) -> Value {
+    return export::person_value(entry.id, &entry.person);
+}
+
+fn persons_value(entries: &[PersonEntry]) -> Value {
+    return Value::Array(entries.iter().map(person_value).collect());
+}
+
+fn department_value(department: &DepartmentEntry) -> Value {
+    return export::department_value(department.id, &department.name);
+}
+
+fn departments_value(departments: &[DepartmentEntry]) -> Value {
+    return Value::Array(departments.iter().map(department_value).collect());
+}
+
+fn person_keys() -> Vec<&'static str> {
+    let mut keys = vec!["last_name", "first_name"];
+    keys.extend(Person::ATTRIBUTE_KEYS.iter());
+    return keys;
+}
+
+// パスのIDは数値のみを受け付ける
+// 数値でないIDはどのデータも指さないので404とする
+fn parse_id(id: &str) -> Result<u64, Box<dyn Error>> {
+    match id.parse() {
+        Ok(id) => return Ok(id),
+        Err(_) => return Err(http_error(404, "Id is empty!")),
+    }
+}
+
+// REST APIでHrServiceを操作する
+// 変更の反映、保存、監査ログへの記録はREPLと同じくHrServiceが行う
+// portは待ち受けているポートで、Hostヘッダーの検証に使う
+pub struct Server<'a> {
+    service: &'a mut HrService,
+    port: u16,
+}
+
+impl<'a> Server<'a> {
+    pub fn new(service: &'a mut HrService, port: u16) -> Server<'a> {
+        return Server {
+            service: service,
+            port: port,
+        };
+    }
+
+    // リクエストを処理して応答を返す
+    // 他のセッションが保存先を変更していれば、先に読み込み直す
+    pub fn handle(&mut self, request: &Request) -> Response {
+        let result = match self.check_headers(request) {
+            Ok(_) => match self.reload_if_changed() {
+                Ok(_) => self.route(request),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(response) => return response,
+            Err(e) => return Response::error(&*e),
+        }
+    }
+
+    // ブラウザーで開いた他のサイトからのリクエスト(DNSリバインディングを含む)を拒否する
+    // Hostは 127.0.0.1:<port> か localhost:<port> のみとし、Originのあるリクエストは受け付けない
+    // 本文のあるリクエストはContent-Type: application/jsonとする
+    fn check_headers(&self, request: &Request) -> Result<(), Box<dyn Error>> {
+        let host = request.host.as_deref().unwrap_or("");
+        let allowed_hosts = [
+            format!("127.0.0.1:{}", self.port),
+            format!("localhost:{}", self.port),
+        ];
+        if !allowed_hosts
+            .iter()
+            .any(|allowed| host.eq_ignore_ascii_case(allowed))
+        {
+            return Err(http_error(403, "Host is not allowed"));
+        }
+        if request.origin.is_some() {
+            return Err(http_error(403, "Cross-origin requests are not allowed"));
+        }
+        if !request.body.is_empty() {
+            let content_type = request.content_type.as_deref().unwrap_or("");
+            let media_type = content_type.split(';').next().unwrap_or("").trim();
+            if !media_type.eq_ignore_ascii_case("application/json") {
+                return Err(http_error(415, "Content-Type must be application/json"));
+            }
+        }
+        return Ok(());
+    }
+
+    fn reload_if_changed(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.service.has_external_change()? {
+            self.service.clear_history();
+            self.service.reload()?;
+        }
+        return Ok(());
+    }
+
+    fn route(&mut self, request: &Request) -> Result<Response, Box<dyn Error>> {
+        let path = request.path.split('?').next().unwrap_or("");
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let body = request.body.as_str();
+        match (segments.as_slice(), request.method.as_str()) {
+            (["persons"], "GET") => return self.list_persons(),
+            (["persons"], "POST") => return self.create_person(body),
+            (["persons", id], "GET") => return self.get_person(id),
+            (["persons", id], "PUT") | (["persons", id], "PATCH") => {
+                return self.update_person(id, body)
+            }
+            (["persons", id], "DELETE") => return self.delete_person(id),
+            (["persons", id, "departments"], "GET") => return self.person_departments(id),
+            (["departments"], "GET") => return self.list_departments(),
+            (["departments"], "POST") => return self.create_department(body),
+            (["departments", id], "GET") => return self.get_department(id),
+            (["departments", id], "PUT") | (["departments", id], "PATCH") => {
+                return self.update_department(id, body)
+            }
+            (["departments", id], "DELETE") => return self.delete_department(id),
+            (["departments", id, "members"], "GET") => return self.department_members(id),
+            (["departments", id, "members", person_id], "PUT") => {
+                return self.add_member(id, person_id)
+            }
+            (["departments", id, "members", person_id], "DELETE") => {
+                return self.remove_member(id, person_id)
+            }
+            (["persons"], _)
+            | (["persons", _], _)
+            | (["persons", _, "departments"], _)
+            | (["departments"], _)
+            | (["departments", _], _)
+            | (["departments", _, "members"], _)
+            | (["departments", _, "members", _], _) => {
+                return Err(http_error(405, "Method not allowed"))
+            }
+            _ => return Err(http_error(404, "Not found")),
+        }
+    }
+
+    fn list_persons(&mut self) -> Result<Response, Box<dyn Error>> {
+        let persons = self.service.persons(PersonOrder::Id)?;
+        return Ok(Response::json(200, persons_value(&persons)));
+    }
+
+    fn get_person(&mut self, id: &str) -> Result<Response, Box<dyn Error>> {
+        let entry = self.service.person(PersonId::new(parse_id(id)?))?;
+        return Ok(Response::json(200, person_value(&entry)));
+    }
+
+    // {"last_name": "...", "first_name": "...", "employee_number": "...", ...}
+    fn create_person(&mut self, body: &str) -> Result<Response, Box<dyn Error>> {
+        let fields = body_fields(body, &person_keys())?;
+        let last_name = match field(&fields, "last_name") {
+            Some(last_name) => last_name,
+            None => return Err(http_error(400, "last_name is required")),
+        };
+        let first_name = field(&fields, "first_name").filter(|name| !name.is_empty());
+        let attributes = attribute_fields(&fields);
+        let entry = self
+            .service
+            .create_person(last_name, first_name, &attributes)?;
+        return Ok(Response::json(201, person_value(&entry)));
+    }
+
+    // 本文に含まれる項目だけを変更する
+    fn update_person(&mut self, id: &str, body: &str) -> Result<Response, Box<dyn Error>> {
+        let id = PersonId::new(parse_id(id)?);
+        let fields = body_fields(body, &person_keys())?;
+        let changes = PersonChanges {
+            last_name: field(&fields, "last_name").map(String::from),
+            first_name: field(&fields, "first_name")
+                .map(|name| Some(String::from(name)).filter(|name| !name.is_empty())),
+            attributes: attribute_fields(&fields),
+        };
+        let entry = self.service.update_person(id, changes)?;
+        return Ok(Response::json(200, person_value(&entry)));
+    }
+
+    fn delete_person(&mut self, id: &str) -> Result<Response, Box<dyn Error>> {
+        self.service.delete_person(PersonId::new(parse_id(id)?))?;
+        return Ok(Response::no_content());
+    }
+
+    fn person_departments(&mut self, id: &str) -> Result<Response, Box<dyn Error>> {
+        let departments = self
+            .service
+            .person_departments(PersonId::new(parse_id(id)?))?;
+        return Ok(Response::json(200, departments_value(&departments)));
+    }
+
+    fn list_departments(&mut self) -> Result<Response, Box<dyn Error>> {
+        let departments = self.service.departments();
+        return Ok(Response::json(200, departments_value(&departments)));
+    }
+
+    fn get_department(&mut self, id: &str) -> Result<Response, Box<dyn Error>> {
+        let department = self.service.department(DepartmentId::new(parse_id(id)?))?;
+        return Ok(Response::json(200, department_value(&department)));
+    }
+
+    // {"name": "..."}
+    fn create_department(&mut self, body: &str) -> Result<Response, Box<dyn Error>> {
+        let name = department_name(body)?;
+        let department = self.service.create_department(&name)?;
+        return Ok(Response::json(201, department_value(&department)));
+    }
+
+    fn update_department(&mut self, id: &str, body: &str) -> Result<Response, Box<dyn Error>> {
+        let id = DepartmentId::new(parse_id(id)?);
+        let name = department_name(body)?;
+        let department = self.service.rename_department(id, &name)?;
+        return Ok(Response::json(200, department_value(&department)));
+    }
+
+    fn delete_department(&mut self, id: &str) -> Result<Response, Box<dyn Error>> {
+        self.service
+            .delete_department(DepartmentId::new(parse_id(id)?))?;
+        return Ok(Response::no_content());
+    }
+
+    fn department_members(&mut self, id: &str) -> Result<Response, Box<dyn Error>> {
+        let members = self
+            .service
+            .department_members(DepartmentId::new(parse_id(id)?))?;
+        return Ok(Response::json(200, persons_value(&members)));
+    }
+
+    fn add_member(&mut self, id: &str, person_id: &str) -> Result<Response, Box<dyn Error>> {
+        self.service.add_member(
+            PersonId::new(parse_id(person_id)?),
+            DepartmentId::new(parse_id(id)?),
+        )?;
+        return Ok(Response::no_content());
+    }
+
+    fn remove_member(&mut self, id: &str, person_id: &str) -> Result<Response, Box<dyn Error>> {
+        self.service.remove_member(
+            PersonId::new(parse_id(person_id)?),
+            DepartmentId::new(parse_id(id)?),
+        )?;
+        return Ok(Response::no_content());
+    }
+
+    // 1つの接続で1つのリクエストを処理する
+    fn handle_connection(&mut self, stream: TcpStream) -> Result<(), Box<dyn Error>> {
+        let reader = DeadlineStream {
+            stream: &stream,
+            deadline: Instant::now() + Duration::from_secs(REQUEST_TIMEOUT_SECONDS),
+        };
+        let response = match read_request(reader) {
+            Ok(request) => {
+                let response = self.handle(&request);
+                println!("{} {} {}", request.method, request.path, response.status);
+                response
+            }
+            Err(e) => Response::error(&*e),
+        };
+        write_response(&stream, &response)?;
+        return Ok(());
+    }
+}
+
+// addressで接続を待ち受け、終了されるまでリクエストを処理する
+// 接続は1つずつ順番に処理するので、データの変更が同時に行われることはない
+pub fn serve(
+    address: &str,
+    service: &mut HrService,
+    language: Language,
+) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(address)?;
+    let local_address = listener.local_addr()?;
+    let mut server = Server::new(service, local_address.port());
+    let address = local_address.to_string();
+    println!("{}", language.format(Message::Serving, &[&address]));
+    for stream in listener.incoming() {
+        // 1つの接続の失敗でサーバーを止めない
+        let result = match stream {
+            Ok(stream) => server.handle_connection(stream),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            eprintln!("{}: {}", language.text(Message::ApplicationError), e);
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::datasource::audit::TextFileAudit;
+    use super::super::super::datasource::department::TextFileDepartment;
+    use super::super::super::datasource::member::TextFileMember;
+    use super::super::super::datasource::person::TextFilePerson;
+    use super::super::super::datasource::Storage;
+    use super::*;
+    use std::fs;
+
+    const PORT: u16 = 8080;
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        return Request {
+            method: String::from(method),
+            path: String::from(path),
+            host: Some(format!("127.0.0.1:{}", PORT)),
+            origin: None,
+            content_type: Some(String::from("application/json")),
+            body: String::from(body),
+        };
+    }
+
+    fn with_server(name: &str, test: fn(&mut Server)) {
+        let dir = std::env::temp_dir().join(format!("hr_server_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir_path = dir.to_str().unwrap();
+        for file_name in [
+            TextFilePerson::FILE_NAME,
+            TextFileDepartment::FILE_NAME,
+            TextFileMember::FILE_NAME,
+        ] {
+            fs::write(format!("{}{}", dir_path, file_name), "").unwrap();
+        }
+        let mut service = HrService::new(
+            Storage::Text.repositories(dir_path),
+            Box::new(TextFileAudit::new(dir_path)),
+            false,
+        )
+        .unwrap();
+        test(&mut Server::new(&mut service, PORT));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn status_of_domain_errors_ok() {
+        let status = |error: DomainError| status_of(&error);
+        assert_eq!(
+            status(DomainError::not_found("PersonList::person : Id is empty!")),
+            404
+        );
+        assert_eq!(
+            status(DomainError::conflict(
+                "DepartmentList::add_department : The same name already exists!"
+            )),
+            409
+        );
+        assert_eq!(
+            status(DomainError::invalid(
+                "EmployeeNumber::new : the length of employee number is invalid!"
+            )),
+            400
+        );
+        assert_eq!(
+            status(DomainError::new(
+                ErrorKind::ReadOnly,
+                "HrService : This session is read-only!"
+            )),
+            403
+        );
+        let error: Box<dyn Error> = "No such file or directory (os error 2)".into();
+        assert_eq!(status_of(&*error), 500);
+        let error = io::Error::new(io::ErrorKind::TimedOut, "Request timed out");
+        assert_eq!(status_of(&error), 408);
+    }
+
+    #[test]
+    fn person_and_member_requests_ok() {
+        with_server("person", |server| {
+            let response = server.handle(&request(
+                "POST",
+                "/persons",
+                r#"{"last_name": "Potter", "first_name": "Harry", "nickname": "Boy"}"#,
+            ));
+            assert_eq!(response.status, 201);
+            let body = response.body.unwrap();
+            assert_eq!(body.get("id").and_then(Value::as_u64), Some(1));
+            assert_eq!(body.get("nickname").and_then(Value::as_str), Some("Boy"));
+
+            let response = server.handle(&request(
+                "POST",
+                "/departments",
+                r#"{"name": "Gryffindor"}"#,
+            ));
+            assert_eq!(response.status, 201);
+            let response = server.handle(&request(
+                "POST",
+                "/departments",
+                r#"{"name": "Gryffindor"}"#,
+            ));
+            assert_eq!(response.status, 409);
+
+            assert_eq!(
+                server
+                    .handle(&request("PUT", "/departments/1/members/1", ""))
+                    .status,
+                204
+            );
+            assert_eq!(
+                server
+                    .handle(&request("PUT", "/departments/1/members/1", ""))
+                    .status,
+                409
+            );
+            let response = server.handle(&request("GET", "/departments/1/members", ""));
+            assert_eq!(response.status, 200);
+            assert_eq!(response.body.unwrap().as_array().map(Vec::len), Some(1));
+
+            let response = server.handle(&request("PATCH", "/persons/1", r#"{"nickname": null}"#));
+            assert_eq!(response.status, 200);
+            let body = response.body.unwrap();
+            assert_eq!(
+                body.get("first_name").and_then(Value::as_str),
+                Some("Harry")
+            );
+            assert_eq!(body.get("nickname"), Some(&Value::Null));
+
+            assert_eq!(
+                server.handle(&request("DELETE", "/persons/1", "")).status,
+                204
+            );
+            assert_eq!(server.handle(&request("GET", "/persons/1", "")).status, 404);
+            let response = server.handle(&request("GET", "/departments/1/members", ""));
+            assert_eq!(response.body.unwrap().as_array().map(Vec::len), Some(0));
+        });
+    }
+
+    #[test]
+    fn invalid_requests_err() {
+        with_server("invalid", |server| {
+            assert_eq!(server.handle(&request("GET", "/persons/9", "")).status, 404);
+            assert_eq!(
+                server.handle(&request("GET", "/persons/abc", "")).status,
+                404
+            );
+            assert_eq!(server.handle(&request("GET", "/teams", "")).status, 404);
+            assert_eq!(
+                server.handle(&request("POST", "/persons/1", "")).status,
+                405
+            );
+            assert_eq!(server.handle(&request("POST", "/persons", "{")).status, 400);
+            assert_eq!(
+                server
+                    .handle(&request("POST", "/persons", r#"{"age": "1"}"#))
+                    .status,
+                400
+            );
+            assert_eq!(
+                server
+                    .handle(&request("POST", "/persons", r#"{"first_name": "Harry"}"#))
+                    .status,
+                400
+            );
+            // 深すぎる入れ子の本文でサーバーを止めない
+            let body = "[".repeat(400000);
+            assert_eq!(
+                server
+                    .handle(&request("POST", "/departments", &body))
+                    .status,
+                400
+            );
+            let body = r#"{"last_name": "Potter", "employee_number": "!"}"#;
+            assert_eq!(
+                server.handle(&request("POST", "/persons", body)).status,
+                400
+            );
+        });
+    }
+
+    #[test]
+    fn name_with_separator_err() {
+        with_server("separator", |server| {
+            for (path, body) in [
+                (
+                    "/persons",
+                    r#"{"last_name": "Van Damme", "first_name": "Jean Claude"}"#,
+                ),
+                ("/persons", r#"{"last_name": "A=B"}"#),
+                (
+                    "/persons",
+                    r#"{"last_name": "Potter", "nickname": "The Boy"}"#,
+                ),
+                ("/departments", r#"{"name": "Human Resources"}"#),
+            ] {
+                assert_eq!(
+                    server.handle(&request("POST", path, body)).status,
+                    400,
+                    "{}",
+                    body
+                );
+            }
+            assert_eq!(
+                server
+                    .handle(&request(
+                        "POST",
+                        "/departments",
+                        r#"{"name": "Gryffindor"}"#
+                    ))
+                    .status,
+                201
+            );
+            assert_eq!(
+                server
+                    .handle(&request(
+                        "PUT",
+                        "/departments/1",
+                        r#"{"name": "Gryffindor Tower"}"#
+                    ))
+                    .status,
+                400
+            );
+            assert_eq!(
+                server
+                    .handle(&request("GET", "/persons", ""))
+                    .body
+                    .unwrap()
+                    .as_array()
+                    .map(Vec::len),
+                Some(0)
+            );
+        });
+    }
+
+    #[test]
+    fn foreign_host_err() {
+        with_server("host", |server| {
+            let mut get = request("GET", "/persons", "");
+            get.host = Some(format!("localhost:{}", PORT));
+            assert_eq!(server.handle(&get).status, 200);
+            for host in [
+                Some(String::from("evil.example:8080")),
+                Some(String::from("127.0.0.1:9999")),
+                Some(String::from("localhost")),
+                None,
+            ] {
+                get.host = host;
+                assert_eq!(server.handle(&get).status, 403);
+            }
+        });
+    }
+
+    #[test]
+    fn origin_header_err() {
+        with_server("origin", |server| {
+            let mut post = request("POST", "/departments", r#"{"name": "Gryffindor"}"#);
+            post.origin = Some(String::from("http://evil.example"));
+            assert_eq!(server.handle(&post).status, 403);
+            assert_eq!(
+                server
+                    .handle(&request("GET", "/departments", ""))
+                    .body
+                    .unwrap()
+                    .as_array()
+                    .map(Vec::len),
+                Some(0)
+            );
+        });
+    }
+
+    #[test]
+    fn content_type_err() {
+        with_server("content_type", |server| {
+            let mut post = request("POST", "/departments", r#"{"name": "Gryffindor"}"#);
+            post.content_type = Some(String::from("text/plain"));
+            assert_eq!(server.handle(&post).status, 415);
+            post.content_type = None;
+            assert_eq!(server.handle(&post).status, 415);
+            post.content_type = Some(String::from("application/json; charset=utf-8"));
+            assert_eq!(server.handle(&post).status, 201);
+            // 本文のないリクエストはContent-Typeを問わない
+            let mut put = request("PUT", "/departments/1", "");
+            put.content_type = None;
+            assert_eq!(server.handle(&put).status, 400);
+        });
+    }
+
+    #[test]
+    fn request_deadline_err() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        // 1回ごとの読み込みは待たせずに、ヘッダーを少しずつ送り続ける
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            for _ in 0..20 {
+                if stream.write_all(b"X").is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        });
+        let (stream, _address) = listener.accept().unwrap();
+        let reader = DeadlineStream {
+            stream: &stream,
+            deadline: Instant::now() + Duration::from_millis(200),
+        };
+        let error = read_request(reader).err().unwrap();
+        assert_eq!(status_of(&*error), 408);
+        drop(stream);
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn read_and_write_http_ok() {
+        let text = "POST /departments HTTP/1.1\r\nHost: localhost:8080\r\nOrigin: http://evil.example\r\nContent-Type: application/json\r\ncontent-length: 20\r\n\r\n{\"name\": \"Slythern\"}";
+        let request = read_request(text.as_bytes()).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/departments");
+        assert_eq!(request.host.as_deref(), Some("localhost:8080"));
+        assert_eq!(request.origin.as_deref(), Some("http://evil.example"));
+        assert_eq!(request.content_type.as_deref(), Some("application/json"));
+        assert_eq!(request.body, "{\"name\": \"Slythern\"}");
+        assert!(read_request("GET /\r\n\r\n".as_bytes()).is_err());
+
+        let mut output: Vec<u8> = Vec::new();
+        write_response(&mut output, &Response::no_content()).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "HTTP/1.1 204 No Content\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        );
+    }
+}
@@ -1,211 +1,414 @@
-use super::super::domain::department::id::DepartmentId;
-use super::super::domain::department::list::DepartmentList;
-use super::super::domain::department::Department;
-use super::super::domain::member::MemberList;
-use super::super::domain::person::id::PersonId;
-use super::super::domain::person::list::PersonList;
-use super::super::domain::person::Person;
-use super::super::repository::department::DepartmentRepository;
-use super::super::repository::member::MemberRepository;
-use super::super::repository::person::PersonRepository;
+use super::super::domain::timestamp::Timestamp;
+use super::command::{self, CommandSpec};
+use super::export::ExportFormat;
+use super::expression::Expression;
+use super::filter::Paging;
+use super::hr_service::{DeletedDepartment, DeletedPerson, HrService, PersonChanges, PersonOrder};
+use super::import::ImportTarget;
+use super::message::{Language, Message};
+use super::org_chart::{ChartFormat, ChartOptions};
+use super::pager::Pager;
 use super::query::Query;
-use std::collections::HashMap;
+use super::repl::Repl;
+use super::stats::{DepartmentHeadcount, Statistics};
 use std::error::Error;
-use std::io;
+use std::fs;
+
+// purgeコマンドで日数を省略した時に、削除済のデータを保持する日数
+const DEFAULT_RETENTION_DAYS: u64 = 30;
 
 // TODO
 // テスト
 // 大規模データ作成　性能テスト
 
-pub fn run<P, D, M>(
-    person_ripository: &P,
-    department_repository: &D,
-    member_repository: &M,
-) -> Result<(), Box<dyn Error>>
-where
-    P: PersonRepository,
-    D: DepartmentRepository,
-    M: MemberRepository,
-{
-    let mut person_list = PersonList::new();
-    match person_ripository.read_all_data(&mut person_list) {
-        Ok(_) => (),
-        Err(_) => panic!("person file: Invalid file format"),
-    }
-
-    let mut department_list = DepartmentList::new();
-    match department_repository.read_all_data(&mut department_list) {
-        Ok(_) => (),
-        Err(_) => panic!("department file: Invalid file format"),
-    }
-
-    let mut member_list = MemberList::new();
-    match member_repository.read_all_data(&mut member_list, &mut person_list, &mut department_list)
-    {
-        Ok(_) => (),
-        Err(_) => panic!("member file: Invalid file format"),
-    }
-
-    println!("input a command");
-    println!("if you need some help, input 'help'");
+// replは入力履歴を引き継ぐために呼び出し側で保持する
+// languageは画面に表示するメッセージの言語
+// serviceが読み取り専用の場合はデータを変更するコマンドを実行しない
+pub fn run(
+    service: &mut HrService,
+    repl: &mut Repl,
+    language: Language,
+) -> Result<(), Box<dyn Error>> {
+    println!("{}", language.text(Message::InputCommand));
+    println!("{}", language.text(Message::HelpHint));
 
     loop {
-        let mut command = String::new();
-
-        io::stdin().read_line(&mut command)?;
-        let command = command.trim().to_string();
+        repl.update_candidates(&service.persons(PersonOrder::Id)?, &service.departments());
+        // Ctrl-D(入力の終わり)はquitと同じく終了する
+        let command = match repl.read_line()? {
+            Some(command) => command,
+            None => break,
+        };
+        if command.is_empty() {
+            continue;
+        }
 
-        let query = Query::new(command);
+        let query = Query::new(command, language);
         let query = match query {
             Ok(q) => q,
             Err(e) => {
-                eprintln!("query error: {}", e);
+                eprintln!("{}: {}", language.text(Message::QueryError), e);
                 continue;
             }
         };
         println!();
 
+        // データを変更する前に、読み込んだ後で他のプログラムが保存先を変更していないか確かめる
+        // 変更されている場合は、読み込み直すか、このコマンドを中止するかを選んでもらう
+        if query.changes_data() {
+            if service.is_read_only() {
+                eprintln!("{}", language.text(Message::ReadOnlySession));
+                continue;
+            }
+            if service.has_external_change()? {
+                println!("{}", language.text(Message::ExternalChange));
+                let answer = repl.read_line()?.unwrap_or_default();
+                if answer == "r" || answer == "reload" {
+                    // 取り消しの履歴は読み込み直したデータと合わないので破棄する
+                    service.clear_history();
+                    service.reload()?;
+                } else {
+                    println!("{}", language.text(Message::CommandAborted));
+                }
+                continue;
+            }
+        }
+
         match query {
             Query::CreateDepartment { department_name } => {
-                let new_department: Department = Department::new(&department_name)?;
-                department_list.add_department(new_department, None)?;
-                department_repository.overwrite_all_data(&mut department_list)?;
+                service.create_department(&department_name)?;
             }
-            Query::ShowAllDepartments => {
-                let list = &mut department_list;
-                for (id, department_name) in list.into_iter() {
-                    println!("{}: {}", id.to_string(), department_name);
-                }
+            Query::ShowAllDepartments { list } => {
+                let mut pager = Pager::new();
+                push_page(
+                    &mut pager,
+                    service.departments(),
+                    list.paging()?,
+                    language,
+                    |d| format!("{}: {}", d.id, d.name),
+                );
+                pager.show(language)?;
             }
-            Query::ShowDepartmentInfo { department_id } => {
-                let department_id: u64 = department_id.parse()?;
-                let department_id = DepartmentId::new(department_id);
-                let department = department_list.department(department_id)?;
-                let department_name = department.name();
-                let person_list =
-                    member_list.person_list_by_department(department_id, &person_list)?;
-                println!("{}:", department_name);
-                for person in person_list.iter() {
-                    println!(" {}", person.name());
-                }
+            Query::ShowDepartmentInfo {
+                department_id,
+                list,
+            } => {
+                let department_id = service.resolve_department(&department_id)?;
+                let department = service.department(department_id)?;
+                let members = service.department_members(department_id)?;
+                let members = service.filter_persons(members, &list.filters()?)?;
+                let mut pager = Pager::new();
+                pager.push(format!("{}:", department.name));
+                push_page(&mut pager, members, list.paging()?, language, |member| {
+                    format!(" {}", member.person.name())
+                });
+                pager.show(language)?;
             }
             Query::DeleteDepartment { department_id } => {
-                let id: u64 = department_id.parse()?;
-                let id = DepartmentId::new(id);
-                department_list.delete_department(id)?;
-                department_repository.overwrite_all_data(&mut department_list)?;
+                let id = service.resolve_department(&department_id)?;
+                service.delete_department(id)?;
             }
             Query::CreatePerson {
                 last_name,
                 first_name,
+                attributes,
             } => {
-                let new_person = match first_name {
-                    Some(name) => {
-                        let first_name = name;
-                        Person::new(&last_name, Some(&first_name))?
-                    }
-                    None => Person::new(&last_name, None)?,
-                };
-                person_list.add_person(new_person, None)?;
-                person_ripository.overwrite_all_data(&mut person_list)?;
+                service.create_person(&last_name, first_name.as_deref(), &attributes)?;
             }
-            Query::ShowAllPersons => {
-                let list = &mut person_list;
-                for (id, person_name) in list.into_iter() {
-                    println!("{}: {}", id.to_string(), person_name);
-                }
+            Query::ShowAllPersons { sort_key, list } => {
+                let order = match sort_key.as_deref() {
+                    Some("reading") => PersonOrder::Reading,
+                    _ => PersonOrder::Id,
+                };
+                let persons = service.persons(order)?;
+                let persons = service.filter_persons(persons, &list.filters()?)?;
+                let mut pager = Pager::new();
+                push_page(&mut pager, persons, list.paging()?, language, |entry| {
+                    format!("{}: {}", entry.id, entry.person.name())
+                });
+                pager.show(language)?;
             }
             Query::ShowPersonInfo { person_id } => {
-                let person_id: u64 = person_id.parse()?;
-                let person_id = PersonId::new(person_id);
-                let person = person_list.person(person_id)?;
-                let person_name = person.name();
-                let list =
-                    member_list.department_list_by_person(person_id, &mut department_list)?;
+                let person_id = service.resolve_person(&person_id)?;
+                let person = service.person(person_id)?.person;
+                let mut person_name = person.name();
+                let reading = person.person_name().reading();
+                if reading != person_name {
+                    person_name.push_str(&format!(" [{}]", reading));
+                }
+                if let Some(nickname) = person.person_name().nickname() {
+                    person_name.push_str(&format!(" \"{}\"", nickname));
+                }
 
                 println!("{}:", person_name);
-                for department in list.iter() {
-                    println!(" {}", department.name());
+                if let Some(number) = person.employee_number() {
+                    println!(" {}", language.format(Message::EmployeeNumber, &[number]));
+                }
+                for department in service.person_departments(person_id)? {
+                    println!(" {}", department.name);
                 }
             }
             Query::DeletePerson { person_id } => {
-                let id: u64 = person_id.parse()?;
-                let id = PersonId::new(id);
-                person_list.delete_person(id)?;
-                person_ripository.overwrite_all_data(&mut person_list)?;
+                let id = service.resolve_person(&person_id)?;
+                service.delete_person(id)?;
             }
             Query::AddPersonToDepartment {
                 person_id,
                 department_id,
             } => {
-                let person_id: u64 = person_id.parse()?;
-                let person_id = PersonId::new(person_id);
-                let department_id: u64 = department_id.parse()?;
-                let department_id = DepartmentId::new(department_id);
-                member_list.add_person_to_department(
-                    person_id,
-                    department_id,
-                    &person_list,
-                    &department_list,
-                )?;
-                member_repository.overwrite_all_data(&mut member_list)?;
+                let person_id = service.resolve_person(&person_id)?;
+                let department_id = service.resolve_department(&department_id)?;
+                service.add_member(person_id, department_id)?;
             }
             Query::RemovePersonFromDepartment {
                 person_id,
                 department_id,
             } => {
-                let person_id: u64 = person_id.parse()?;
-                let person_id = PersonId::new(person_id);
-                let department_id: u64 = department_id.parse()?;
-                let department_id = DepartmentId::new(department_id);
-                member_list.remove_person_from_department(
-                    person_id,
-                    department_id,
-                    &person_list,
-                    &department_list,
-                )?;
-                member_repository.overwrite_all_data(&mut member_list)?;
-            }
-            Query::ShowAllPersonsByDepartment => {
-                let mut department_member_list: HashMap<String, Vec<String>> = HashMap::new();
-                let mut non_member_list: Vec<String> = Vec::new();
-                for (_id, department_name) in department_list.into_iter() {
-                    department_member_list.insert(department_name, Vec::new());
-                }
-                for (id, person_name) in person_list.into_iter() {
-                    let list = member_list.department_list_by_person(id, &mut department_list)?;
-                    if list.len() == 0 {
-                        non_member_list.push(person_name);
-                    } else {
-                        for department in list.iter() {
-                            let department_name = department.name();
-                            let member_name_list =
-                                match department_member_list.get_mut(&department_name) {
-                                    Some(list) => list,
-                                    None => {
-                                        let message =
-                                        "Query::ShowAllPersonsByDepartment : Department not found";
-                                        return Err(message.into());
-                                    }
-                                };
-                            member_name_list.push(person_name.clone());
+                let person_id = service.resolve_person(&person_id)?;
+                let department_id = service.resolve_department(&department_id)?;
+                service.remove_member(person_id, department_id)?;
+            }
+            Query::TransferPerson {
+                person_id,
+                from_department_id,
+                to_department_id,
+            } => {
+                let person_id = service.resolve_person(&person_id)?;
+                let from_department_id = service.resolve_department(&from_department_id)?;
+                let to_department_id = service.resolve_department(&to_department_id)?;
+                service.transfer_member(person_id, from_department_id, to_department_id)?;
+            }
+            Query::MergeDepartments {
+                source_department_id,
+                target_department_id,
+            } => {
+                let source_id = service.resolve_department(&source_department_id)?;
+                let target_id = service.resolve_department(&target_department_id)?;
+                let merged = service.merge_departments(source_id, target_id)?;
+                let message = language.format(
+                    Message::DepartmentsMerged,
+                    &[&merged.moved.to_string(), &merged.duplicated.to_string()],
+                );
+                println!("{}", message);
+            }
+            Query::SplitDepartment {
+                department_id,
+                department_name,
+                person_ids,
+            } => {
+                let department_id = service.resolve_department(&department_id)?;
+                let person_ids = person_ids
+                    .iter()
+                    .map(|person_id| service.resolve_person(person_id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let department =
+                    service.split_department(department_id, &department_name, &person_ids)?;
+                let count = service.department_members(department.id)?.len();
+                let message = language.format(
+                    Message::DepartmentSplit,
+                    &[
+                        &department.id.to_string(),
+                        &department.name,
+                        &count.to_string(),
+                    ],
+                );
+                println!("{}", message);
+            }
+            Query::RenamePerson {
+                person_id,
+                last_name,
+                first_name,
+                attributes,
+            } => {
+                let person_id = service.resolve_person(&person_id)?;
+                let changes = PersonChanges {
+                    last_name: Some(last_name),
+                    first_name: Some(first_name),
+                    attributes: attributes,
+                };
+                service.update_person(person_id, changes)?;
+            }
+            Query::RenameDepartment {
+                department_id,
+                department_name,
+            } => {
+                let department_id = service.resolve_department(&department_id)?;
+                service.rename_department(department_id, &department_name)?;
+            }
+            Query::RestorePerson { person_id } => {
+                let id = service.resolve_person(&person_id)?;
+                service.restore_person(id)?;
+            }
+            Query::RestoreDepartment { department_id } => {
+                let id = service.resolve_department(&department_id)?;
+                service.restore_department(id)?;
+            }
+            Query::ShowDeleted => {
+                print_deleted(
+                    &service.deleted_persons(),
+                    &service.deleted_departments(),
+                    true,
+                    language,
+                );
+            }
+            Query::Purge { days, dry_run } => {
+                let days: u64 = match days {
+                    Some(days) => days.parse()?,
+                    None => DEFAULT_RETENTION_DAYS,
+                };
+                let purged = service.purge(days, dry_run)?;
+                if dry_run {
+                    // 完全削除の対象を表示するだけで、リストやファイルは変更しない
+                    print_deleted(&purged.persons, &purged.departments, false, language);
+                } else {
+                    let purged = language.format(
+                        Message::Purged,
+                        &[
+                            &purged.persons.len().to_string(),
+                            &purged.departments.len().to_string(),
+                        ],
+                    );
+                    println!("{}", purged);
+                }
+            }
+            Query::ImportCsv {
+                target,
+                path,
+                dry_run,
+            } => {
+                let target = ImportTarget::new(&target)?;
+                let text = fs::read_to_string(&path)?;
+                let label = format!("import {} from {}", target, path);
+                let result = service.import_csv(target, &text, &label, dry_run)?;
+                for (line, error) in result.errors.iter() {
+                    let error = language.translate_error(error);
+                    println!(
+                        "{}",
+                        language.format(Message::LineError, &[&line.to_string(), &error])
+                    );
+                }
+                let summary = language.format(
+                    Message::ImportSummary,
+                    &[
+                        &result.created.to_string(),
+                        &result.updated.to_string(),
+                        &result.unchanged.to_string(),
+                    ],
+                );
+                println!("{}", summary);
+                // 誤りのある行が１行でもあれば、全ての行を取り込まない
+                if !result.errors.is_empty() {
+                    let count = result.errors.len().to_string();
+                    println!("{}", language.format(Message::ImportAborted, &[&count]));
+                } else if dry_run {
+                    println!("{}", language.text(Message::ImportDryRun));
+                }
+            }
+            Query::Export {
+                format,
+                path,
+                include_deleted,
+            } => {
+                let format = ExportFormat::new(&format)?;
+                for file_path in service.export(format, &path, include_deleted)? {
+                    println!("{}", language.format(Message::Exported, &[&file_path]));
+                }
+            }
+            Query::OrgChart {
+                format,
+                path,
+                skip_empty,
+                unassigned,
+            } => {
+                let format = ChartFormat::new(&format)?;
+                let options = ChartOptions {
+                    include_empty: !skip_empty,
+                    include_unassigned: unassigned,
+                };
+                let chart = service.org_chart(format, options)?;
+                // ファイルを省略した場合は画面に出力する
+                match path {
+                    Some(path) => {
+                        fs::write(&path, chart)?;
+                        println!("{}", language.format(Message::Exported, &[&path]));
+                    }
+                    None => print!("{}", chart),
+                }
+            }
+            Query::Stats { format, path } => {
+                let statistics = service.statistics()?;
+                match (format, path) {
+                    (Some(format), Some(path)) => {
+                        let format = ExportFormat::new(&format)?;
+                        for file_path in statistics.export(format, &path)? {
+                            println!("{}", language.format(Message::Exported, &[&file_path]));
                         }
                     }
+                    (Some(_format), None) => {
+                        eprintln!("{}", language.text(Message::FilePathMissing));
+                    }
+                    _ => print_statistics(&statistics, language),
+                }
+            }
+            Query::Evaluate { expression } => {
+                let expression = Expression::parse(&expression)?;
+                let persons = service.evaluate(&expression)?;
+                let count = persons.len().to_string();
+                let mut pager = Pager::new();
+                for entry in persons {
+                    pager.push(format!("{}: {}", entry.id, entry.person.name()));
                 }
-                for (department_name, person_list) in department_member_list.iter() {
-                    println!("{}:", department_name);
-                    for person in person_list.iter() {
-                        println!(" {}", person);
+                pager.push(language.format(Message::QueryMatched, &[&count]));
+                pager.show(language)?;
+            }
+            Query::Undo => {
+                let detail = service.undo()?;
+                println!("{}", language.format(Message::Undo, &[&detail]));
+            }
+            Query::Redo => {
+                let detail = service.redo()?;
+                println!("{}", language.format(Message::Redo, &[&detail]));
+            }
+            Query::ShowAllPersonsByDepartment { list } => {
+                let filters = list.filters()?;
+                let organization = service.organization()?;
+                let mut pager = Pager::new();
+                for department in organization.departments {
+                    pager.push(format!("{}:", department.department.name));
+                    for member in service.filter_persons(department.members, &filters)? {
+                        pager.push(format!(" {}", member.person.name()));
                     }
                 }
-                println!("Not member of any department:");
-                for person in non_member_list.iter() {
-                    println!(" {}", person);
+                pager.push(String::from(
+                    language.text(Message::NotMemberOfAnyDepartment),
+                ));
+                for entry in service.filter_persons(organization.unassigned, &filters)? {
+                    pager.push(format!(" {}", entry.person.name()));
                 }
+                pager.show(language)?;
             }
-            Query::Help => {
-                Query::print_help();
+            Query::FindPerson { text } => {
+                let result = service.find_persons(&text)?;
+                if result.is_empty() {
+                    println!("{}", language.text(Message::NoPersonFound));
+                }
+                for entry in result {
+                    println!("{}: {}", entry.id, entry.person.name());
+                }
+            }
+            Query::FindDepartment { text } => {
+                let result = service.find_departments(&text)?;
+                if result.is_empty() {
+                    println!("{}", language.text(Message::NoDepartmentFound));
+                }
+                for department in result {
+                    println!("{}: {}", department.id, department.name);
+                }
             }
+            Query::Help { command } => match command.as_deref().and_then(CommandSpec::find) {
+                Some(spec) => spec.print_help(language),
+                None => command::print_all_help(language),
+            },
             Query::Quit => {
                 break;
             }
@@ -213,3 +416,127 @@ where
     }
     return Ok(());
 }
+
+// 削除済の個人と部署を表示する
+// with_timeの場合は削除した日時も表示する
+fn print_deleted(
+    persons: &[DeletedPerson],
+    departments: &[DeletedDepartment],
+    with_time: bool,
+    language: Language,
+) {
+    let deleted_at = |timestamp: Timestamp| match with_time {
+        true => format!(
+            " ({})",
+            language.format(Message::DeletedAt, &[&timestamp.to_string()])
+        ),
+        false => String::new(),
+    };
+    println!("{}", language.text(Message::DeletedPersons));
+    for person in persons.iter() {
+        println!(
+            " {}: {}{}",
+            person.id,
+            person.name,
+            deleted_at(person.deleted_at)
+        );
+    }
+    println!("{}", language.text(Message::DeletedDepartments));
+    for department in departments.iter() {
+        println!(
+            " {}: {}{}",
+            department.id,
+            department.name,
+            deleted_at(department.deleted_at)
+        );
+    }
+}
+
+// 統計を表示する
+// 部署が無い場合は最大、最小、平均の行を表示しない
+fn print_statistics(statistics: &Statistics, language: Language) {
+    println!("{}", language.text(Message::StatsHeadcount));
+    for department in statistics.departments.iter() {
+        let line = language.format(
+            Message::StatsDepartment,
+            &[
+                &department.department.id.to_string(),
+                &department.department.name,
+                &department.headcount.to_string(),
+            ],
+        );
+        println!("{}", line);
+    }
+    // 同数の部署は名前を並べて、人数と共に表示する
+    let print_departments = |message: Message, departments: Vec<&DepartmentHeadcount>| {
+        let names: Vec<String> = departments
+            .iter()
+            .map(|department| department.department.name.clone())
+            .collect();
+        let size = departments.first().map(|department| department.headcount);
+        let size = size.unwrap_or(0).to_string();
+        println!("{}", language.format(message, &[&names.join(", "), &size]));
+    };
+    if let Some(average) = statistics.average_size() {
+        print_departments(Message::StatsLargest, statistics.largest());
+        print_departments(Message::StatsSmallest, statistics.smallest());
+        let average = format!("{:.2}", average);
+        println!("{}", language.format(Message::StatsAverage, &[&average]));
+    }
+
+    let multiple = statistics.multiple_departments();
+    let count = multiple.len().to_string();
+    println!(
+        "{}",
+        language.format(Message::StatsMultipleDepartments, &[&count])
+    );
+    for person in multiple {
+        let line = language.format(
+            Message::StatsPersonMemberships,
+            &[
+                &person.person.id.to_string(),
+                &person.person.person.name(),
+                &person.memberships.to_string(),
+            ],
+        );
+        println!("{}", line);
+    }
+    let unassigned = statistics.unassigned();
+    let count = unassigned.len().to_string();
+    println!("{}", language.format(Message::StatsUnassigned, &[&count]));
+    for person in unassigned {
+        println!(" {}: {}", person.person.id, person.person.person.name());
+    }
+    println!("{}", language.text(Message::StatsDistribution));
+    for (memberships, count) in statistics.distribution() {
+        let line = language.format(
+            Message::StatsDistributionRow,
+            &[&memberships.to_string(), &count.to_string()],
+        );
+        println!("{}", line);
+    }
+}
+
+// 表示範囲を適用した一覧をページャーに追加する
+// 範囲を指定した場合は、表示した範囲と全体の件数も追加する
+fn push_page<T, F>(pager: &mut Pager, items: Vec<T>, paging: Paging, language: Language, line: F)
+where
+    F: Fn(&T) -> String,
+{
+    let total = items.len();
+    let items = paging.apply(items);
+    for item in items.iter() {
+        pager.push(line(item));
+    }
+    if !paging.is_specified() {
+        return;
+    }
+    let total = total.to_string();
+    if items.is_empty() {
+        pager.push(language.format(Message::ShowingNone, &[&total]));
+    } else {
+        let from = (paging.offset + 1).to_string();
+        let to = (paging.offset + items.len()).to_string();
+        pager.push(language.format(Message::ShowingRange, &[&from, &to, &total]));
+    }
+}
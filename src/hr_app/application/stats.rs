@@ -0,0 +1,370 @@
+use super::super::domain::member::MemberList;
+use super::super::format::json::Value;
+use super::export::{self, ExportFormat, Table, SCHEMA_VERSION};
+use super::hr_service::{DepartmentEntry, PersonEntry};
+use std::error::Error;
+use std::fs;
+
+// 部署ごとの人数
+#[derive(Clone, Debug)]
+pub struct DepartmentHeadcount {
+    pub department: DepartmentEntry,
+    pub headcount: usize,
+}
+
+// 個人ごとの所属部署数
+#[derive(Clone, Debug)]
+pub struct PersonMemberships {
+    pub person: PersonEntry,
+    pub memberships: usize,
+}
+
+// 人員の統計
+// 削除済でない部署と個人をそれぞれIDの小さい順に持ち、集計値はメソッドで返す
+#[derive(Clone, Debug)]
+pub struct Statistics {
+    pub departments: Vec<DepartmentHeadcount>,
+    pub persons: Vec<PersonMemberships>,
+}
+
+// 部署と個人の一覧と所属から統計を作成する
+// 所属は有効なもの(削除された個人や部署の所属を除く)のみを数える
+pub fn compute(
+    departments: Vec<DepartmentEntry>,
+    persons: Vec<PersonEntry>,
+    member_list: &MemberList,
+) -> Statistics {
+    let departments = departments
+        .into_iter()
+        .map(|department| DepartmentHeadcount {
+            headcount: member_list.member_ids(department.id).len(),
+            department: department,
+        })
+        .collect();
+    let persons = persons
+        .into_iter()
+        .map(|person| PersonMemberships {
+            memberships: member_list.department_ids(person.id).len(),
+            person: person,
+        })
+        .collect();
+    return Statistics {
+        departments: departments,
+        persons: persons,
+    };
+}
+
+impl Statistics {
+    // 所属の延べ数
+    pub fn total_memberships(&self) -> usize {
+        return self
+            .departments
+            .iter()
+            .map(|department| department.headcount)
+            .sum();
+    }
+
+    // 2つ以上の部署に所属している人
+    pub fn multiple_departments(&self) -> Vec<&PersonMemberships> {
+        return self
+            .persons
+            .iter()
+            .filter(|person| person.memberships >= 2)
+            .collect();
+    }
+
+    // どの部署にも所属していない人
+    pub fn unassigned(&self) -> Vec<&PersonMemberships> {
+        return self
+            .persons
+            .iter()
+            .filter(|person| person.memberships == 0)
+            .collect();
+    }
+
+    // 人数が最も多い部署(同数の部署は全て)
+    pub fn largest(&self) -> Vec<&DepartmentHeadcount> {
+        let size = self.departments.iter().map(|d| d.headcount).max();
+        return self
+            .departments
+            .iter()
+            .filter(|department| Some(department.headcount) == size)
+            .collect();
+    }
+
+    // 人数が最も少ない部署(同数の部署は全て)
+    pub fn smallest(&self) -> Vec<&DepartmentHeadcount> {
+        let size = self.departments.iter().map(|d| d.headcount).min();
+        return self
+            .departments
+            .iter()
+            .filter(|department| Some(department.headcount) == size)
+            .collect();
+    }
+
+    // 部署の平均人数
+    // 部署が無い場合はNone
+    pub fn average_size(&self) -> Option<f64> {
+        if self.departments.is_empty() {
+            return None;
+        }
+        return Some(self.total_memberships() as f64 / self.departments.len() as f64);
+    }
+
+    // 所属部署数ごとの人数を、所属部署数0から最大値まで抜けなく返す
+    pub fn distribution(&self) -> Vec<(usize, usize)> {
+        let max = self.persons.iter().map(|p| p.memberships).max();
+        let max = match max {
+            Some(max) => max,
+            None => return Vec::new(),
+        };
+        return (0..=max)
+            .map(|memberships| {
+                let count = self
+                    .persons
+                    .iter()
+                    .filter(|person| person.memberships == memberships)
+                    .count();
+                (memberships, count)
+            })
+            .collect();
+    }
+
+    // 集計値を (キー, 値) の一覧で返す
+    // JSONの数値は整数のみなので、平均人数は小数点以下2桁の文字列とする
+    fn summary(&self) -> Vec<(&'static str, Value)> {
+        let size = |departments: Vec<&DepartmentHeadcount>| match departments.first() {
+            Some(department) => Value::Number(department.headcount as u64),
+            None => Value::Null,
+        };
+        let average_size = match self.average_size() {
+            Some(average) => Value::String(format!("{:.2}", average)),
+            None => Value::Null,
+        };
+        return vec![
+            ("persons", Value::Number(self.persons.len() as u64)),
+            ("departments", Value::Number(self.departments.len() as u64)),
+            (
+                "memberships",
+                Value::Number(self.total_memberships() as u64),
+            ),
+            (
+                "multiple_departments",
+                Value::Number(self.multiple_departments().len() as u64),
+            ),
+            ("unassigned", Value::Number(self.unassigned().len() as u64)),
+            ("largest_size", size(self.largest())),
+            ("smallest_size", size(self.smallest())),
+            ("average_size", average_size),
+        ];
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        let summary = self
+            .summary()
+            .into_iter()
+            .map(|(key, value)| vec![Value::String(String::from(key)), value])
+            .collect();
+        let departments = self
+            .departments
+            .iter()
+            .map(|department| {
+                vec![
+                    Value::Number(department.department.id.as_u64()),
+                    Value::String(department.department.name.clone()),
+                    Value::Number(department.headcount as u64),
+                ]
+            })
+            .collect();
+        let persons = self
+            .persons
+            .iter()
+            .map(|person| {
+                vec![
+                    Value::Number(person.person.id.as_u64()),
+                    Value::String(person.person.person.name()),
+                    Value::Number(person.memberships as u64),
+                ]
+            })
+            .collect();
+        let distribution = self
+            .distribution()
+            .into_iter()
+            .map(|(memberships, count)| {
+                vec![
+                    Value::Number(memberships as u64),
+                    Value::Number(count as u64),
+                ]
+            })
+            .collect();
+        return vec![
+            Table {
+                name: "summary",
+                columns: &["key", "value"],
+                rows: summary,
+            },
+            Table {
+                name: "departments",
+                columns: &["id", "name", "headcount"],
+                rows: departments,
+            },
+            Table {
+                name: "persons",
+                columns: &["id", "name", "memberships"],
+                rows: persons,
+            },
+            Table {
+                name: "distribution",
+                columns: &["memberships", "persons"],
+                rows: distribution,
+            },
+        ];
+    }
+
+    // {"schema_version": 1, "summary": {...}, "departments": [...], "persons": [...], "distribution": [...]}
+    // summaryはキーと値のオブジェクト、それ以外はエクスポートと同じく行の配列とする
+    pub fn to_json(&self) -> Value {
+        let summary = self
+            .summary()
+            .into_iter()
+            .map(|(key, value)| (String::from(key), value))
+            .collect();
+        let mut document = vec![
+            (
+                String::from("schema_version"),
+                Value::Number(SCHEMA_VERSION),
+            ),
+            (String::from("summary"), Value::Object(summary)),
+        ];
+        for table in self.tables().into_iter().skip(1) {
+            document.push((String::from(table.name), table.to_json()));
+        }
+        return Value::Object(document);
+    }
+
+    // 統計をファイルに出力し、出力したファイルのパスを返す
+    // CSVの場合はpathをディレクトリとして summary.csv, departments.csv, persons.csv, distribution.csv を作成する
+    pub fn export(&self, format: ExportFormat, path: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        match format {
+            ExportFormat::Csv => return export::write_csv(path, &self.tables()),
+            ExportFormat::Json => {
+                fs::write(path, self.to_json().to_json())?;
+                return Ok(vec![String::from(path)]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::domain::department::id::DepartmentId;
+    use super::super::super::domain::department::list::DepartmentList;
+    use super::super::super::domain::department::Department;
+    use super::super::super::domain::person::id::PersonId;
+    use super::super::super::domain::person::list::PersonList;
+    use super::super::super::domain::person::Person;
+    use super::*;
+
+    // 部署1: Harry, Ron / 部署2: Harry / 部署3: なし / Dracoはどの部署にも所属していない
+    fn generate_statistics() -> Statistics {
+        let mut person_list = PersonList::new();
+        let mut department_list = DepartmentList::new();
+        let mut member_list = MemberList::new();
+        let mut persons: Vec<PersonEntry> = Vec::new();
+        let names = [("Potter", "Harry"), ("Weasley", "Ron"), ("Malfoy", "Draco")];
+        for (index, (last_name, first_name)) in names.into_iter().enumerate() {
+            let id = PersonId::new(index as u64 + 1);
+            let person = Person::new(last_name, Some(first_name)).unwrap();
+            person_list.add_person(person.clone(), Some(id)).unwrap();
+            persons.push(PersonEntry {
+                id: id,
+                person: person,
+            });
+        }
+        let mut departments: Vec<DepartmentEntry> = Vec::new();
+        let names = ["Gryffindor", "Quidditch", "Hufflepuff"];
+        for (index, name) in names.into_iter().enumerate() {
+            let id = DepartmentId::new(index as u64 + 1);
+            let department = Department::new(name).unwrap();
+            department_list
+                .add_department(department, Some(id))
+                .unwrap();
+            departments.push(DepartmentEntry {
+                id: id,
+                name: String::from(name),
+            });
+        }
+        for (person_id, department_id) in [(1, 1), (2, 1), (1, 2)] {
+            member_list
+                .add_person_to_department(
+                    PersonId::new(person_id),
+                    DepartmentId::new(department_id),
+                    &person_list,
+                    &department_list,
+                )
+                .unwrap();
+        }
+        return compute(departments, persons, &member_list);
+    }
+
+    #[test]
+    fn compute_ok() {
+        let statistics = generate_statistics();
+        let headcounts: Vec<usize> = statistics.departments.iter().map(|d| d.headcount).collect();
+        assert_eq!(headcounts, vec![2, 1, 0]);
+        assert_eq!(statistics.total_memberships(), 3);
+        let multiple = statistics.multiple_departments();
+        assert_eq!(multiple.len(), 1);
+        assert_eq!(multiple[0].person.id, PersonId::new(1));
+        assert_eq!(statistics.unassigned()[0].person.id, PersonId::new(3));
+        assert_eq!(statistics.largest()[0].department.name, "Gryffindor");
+        assert_eq!(statistics.smallest()[0].department.name, "Hufflepuff");
+        assert_eq!(statistics.average_size(), Some(1.0));
+        assert_eq!(statistics.distribution(), vec![(0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn empty_statistics_ok() {
+        let statistics = compute(Vec::new(), Vec::new(), &MemberList::new());
+        assert!(statistics.largest().is_empty());
+        assert_eq!(statistics.average_size(), None);
+        assert!(statistics.distribution().is_empty());
+        let summary = statistics.to_json();
+        let summary = summary.get("summary").unwrap();
+        assert_eq!(summary.get("average_size"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn to_json_and_csv_ok() {
+        let statistics = generate_statistics();
+        let document = statistics.to_json();
+        let summary = document.get("summary").unwrap();
+        assert_eq!(summary.get("multiple_departments"), Some(&Value::Number(1)));
+        assert_eq!(
+            summary.get("average_size"),
+            Some(&Value::String(String::from("1.00")))
+        );
+        assert_eq!(
+            document
+                .get("distribution")
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let tables = statistics.tables();
+        let csv = tables[1].to_csv();
+        assert_eq!(
+            csv.lines().collect::<Vec<&str>>(),
+            vec![
+                "id,name,headcount",
+                "1,Gryffindor,2",
+                "2,Quidditch,1",
+                "3,Hufflepuff,0"
+            ]
+        );
+        assert!(tables[0].to_csv().contains("average_size,1.00"));
+    }
+}
@@ -0,0 +1,41 @@
+use super::super::domain::audit::AuditEvent;
+use super::super::repository::audit::AuditRepository;
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::prelude::*;
+
+// 監査ログはファイル末尾への追記のみを行う
+// 1行につき1件の操作を "日時<TAB>操作<TAB>詳細" の形式で記録する
+pub struct TextFileAudit {
+    dir_path: String,
+}
+
+impl TextFileAudit {
+    const FILE_NAME: &'static str = "/audit.txt";
+    pub fn new(path: &str) -> TextFileAudit {
+        let dir_path = String::from(path);
+        return TextFileAudit { dir_path: dir_path };
+    }
+}
+
+impl AuditRepository for TextFileAudit {
+    fn append_event(&self, event: &AuditEvent) -> Result<(), Box<dyn Error>> {
+        let mut file_path = String::new();
+        file_path.push_str(&self.dir_path[..]);
+        file_path.push_str(Self::FILE_NAME);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)?;
+
+        writeln!(
+            file,
+            "{}\t{}\t{}",
+            event.timestamp(),
+            event.action(),
+            event.detail()
+        )?;
+
+        return Ok(());
+    }
+}
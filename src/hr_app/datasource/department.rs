@@ -1,28 +1,56 @@
 use super::super::domain::department::id::DepartmentId;
 use super::super::domain::department::list::DepartmentList;
 use super::super::domain::department::Department;
+use super::super::domain::timestamp::Timestamp;
 use super::super::repository::department::DepartmentRepository;
+use super::file_fingerprint;
 use std::error::Error;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::prelude::*;
+use std::path::Path;
 
 pub struct TextFileDepartment {
     dir_path: String,
 }
 
 impl TextFileDepartment {
-    const FILE_NAME: &'static str = "/departmetn.txt";
+    pub const FILE_NAME: &'static str = "/department.txt";
+    // 以前のバージョンで使用していた綴りの誤ったファイル名
+    // 新しい名前のファイルが無ければこちらを読み込み、保存時に新しい名前に移す
+    pub const LEGACY_FILE_NAME: &'static str = "/departmetn.txt";
+    const DELETED_AT_PREFIX: &'static str = "deleted_at=";
+    const MAX_ID_PREFIX: &'static str = "max_id=";
     pub fn new(path: &str) -> TextFileDepartment {
         let dir_path = String::from(path);
         return TextFileDepartment { dir_path: dir_path };
     }
+
+    // 読み込むファイルのパスを返す
+    // 両方のファイルが存在して内容が異なる場合は、どちらが正しいか決められないのでエラーとする
+    pub fn file_path(&self) -> Result<String, Box<dyn Error>> {
+        let file_path = format!("{}{}", self.dir_path, Self::FILE_NAME);
+        let legacy_file_path = format!("{}{}", self.dir_path, Self::LEGACY_FILE_NAME);
+        if !Path::new(&legacy_file_path).exists() {
+            return Ok(file_path);
+        }
+        if !Path::new(&file_path).exists() {
+            return Ok(legacy_file_path);
+        }
+        if fs::read(&file_path)? != fs::read(&legacy_file_path)? {
+            let message = format!(
+                "TextFileDepartment::file_path : The new and legacy department files exist with different contents! ({}, {})",
+                Self::FILE_NAME.trim_start_matches('/'),
+                Self::LEGACY_FILE_NAME.trim_start_matches('/')
+            );
+            return Err(message.into());
+        }
+        return Ok(file_path);
+    }
 }
 
 impl DepartmentRepository for TextFileDepartment {
     fn read_all_data(&self, department_list: &mut DepartmentList) -> Result<(), Box<dyn Error>> {
-        let mut file_path = String::new();
-        file_path.push_str(&self.dir_path[..]);
-        file_path.push_str(&Self::FILE_NAME[..]);
+        let file_path = self.file_path()?;
         eprintln!("loading department file...");
         let mut file = match File::open(&file_path) {
             Ok(file) => file,
@@ -34,17 +62,36 @@ impl DepartmentRepository for TextFileDepartment {
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
 
+        // 1行の形式は "ID 部署名"
+        // 削除済の部署は "ID 部署名 deleted_at=削除日時(UNIX秒)"
+        // 完全削除したIDを再利用しないよう、最大のIDが最後の行のIDより大きい場合は "max_id=ID" の行を持つ
         let departments: Vec<&str> = contents.split_terminator('\n').collect();
         for department in departments {
+            if let Some(max_id) = department.trim().strip_prefix(Self::MAX_ID_PREFIX) {
+                department_list.reserve_ids(DepartmentId::new(max_id.parse()?));
+                continue;
+            }
             let info: Vec<&str> = department.split_whitespace().collect();
-            if info.len() != 2 {
+            if info.len() != 2 && info.len() != 3 {
                 return Err("TextFileDepartment::read_all_data : Invalid file format!".into());
             }
             let id: u64 = info[0].parse()?;
             let id = DepartmentId::new(id);
             let department_name = info[1];
             let new_department = Department::new(department_name)?;
-            department_list.add_department(new_department, Some(id))?;
+            if info.len() == 3 {
+                let deleted_at = match info[2].strip_prefix(Self::DELETED_AT_PREFIX) {
+                    Some(value) => Timestamp::new(value.parse()?),
+                    None => {
+                        return Err(
+                            "TextFileDepartment::read_all_data : Invalid file format!".into()
+                        );
+                    }
+                };
+                department_list.add_deleted_department(new_department, id, deleted_at)?;
+            } else {
+                department_list.add_department(new_department, Some(id))?;
+            }
         }
 
         return Ok(());
@@ -54,9 +101,11 @@ impl DepartmentRepository for TextFileDepartment {
         &self,
         department_list: &mut DepartmentList,
     ) -> Result<(), Box<dyn Error>> {
+        // 以前の名前のファイルと内容が異なる場合は上書きしない
+        self.file_path()?;
         let mut file_path = String::new();
         file_path.push_str(&self.dir_path[..]);
-        file_path.push_str(&Self::FILE_NAME[..]);
+        file_path.push_str(Self::FILE_NAME);
         let mut file = match File::create(file_path) {
             Ok(file) => file,
             Err(e) => {
@@ -64,10 +113,78 @@ impl DepartmentRepository for TextFileDepartment {
             }
         };
 
+        let mut lines: Vec<(DepartmentId, String)> = Vec::new();
         for (id, name) in department_list.into_iter() {
-            writeln!(file, "{} {}", id.to_string(), name)?;
+            lines.push((id, format!("{} {}", id, name)));
+        }
+        for (id, department, deleted_at) in department_list.deleted_departments() {
+            let line = format!(
+                "{} {} {}{}",
+                id,
+                department.name(),
+                Self::DELETED_AT_PREFIX,
+                deleted_at.seconds()
+            );
+            lines.push((id, line));
+        }
+        lines.sort_by_key(|(id, _line)| *id);
+        let last_id = match lines.last() {
+            Some((id, _line)) => *id,
+            None => DepartmentId::new(0),
+        };
+
+        for (_id, line) in lines {
+            writeln!(file, "{}", line)?;
+        }
+        let max_id = department_list.max_id();
+        if max_id.greater_than(last_id) {
+            writeln!(file, "{}{}", Self::MAX_ID_PREFIX, max_id)?;
+        }
+
+        // 新しい名前のファイルに保存できたので、以前の名前のファイルは削除する
+        let legacy_file_path = format!("{}{}", self.dir_path, Self::LEGACY_FILE_NAME);
+        if Path::new(&legacy_file_path).exists() {
+            fs::remove_file(legacy_file_path)?;
         }
 
         return Ok(());
     }
+
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(Some(file_fingerprint(&self.file_path()?)?));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_file_ok() {
+        let dir = std::env::temp_dir().join(format!("hr_legacy_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir_path = dir.to_str().unwrap();
+        let datasource = TextFileDepartment::new(dir_path);
+        let legacy_file_path = format!("{}{}", dir_path, TextFileDepartment::LEGACY_FILE_NAME);
+        let new_file_path = format!("{}{}", dir_path, TextFileDepartment::FILE_NAME);
+        fs::write(&legacy_file_path, "1 Gryffindor\n").unwrap();
+
+        let mut department_list = DepartmentList::new();
+        datasource.read_all_data(&mut department_list).unwrap();
+        assert!(department_list.contains(DepartmentId::new(1)));
+        datasource.overwrite_all_data(&mut department_list).unwrap();
+        assert!(!Path::new(&legacy_file_path).exists());
+        assert_eq!(
+            fs::read_to_string(&new_file_path).unwrap(),
+            "1 Gryffindor\n"
+        );
+
+        fs::write(&legacy_file_path, "1 Gryffindor\n").unwrap();
+        assert_eq!(datasource.file_path().unwrap(), new_file_path);
+        fs::write(&legacy_file_path, "1 Slytherin\n").unwrap();
+        assert!(datasource.file_path().is_err());
+        let mut department_list = DepartmentList::new();
+        assert!(datasource.read_all_data(&mut department_list).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,89 @@
+use super::super::super::domain::department::id::DepartmentId;
+use super::super::super::domain::department::list::DepartmentList;
+use super::super::super::domain::department::Department;
+use super::super::super::domain::timestamp::Timestamp;
+use super::super::super::format::json::Value;
+use super::super::super::repository::department::DepartmentRepository;
+use super::super::file_fingerprint;
+use super::{optional_number, optional_number_value, read_records_with_max_id};
+use super::{required_number, required_str, write_records_with_max_id};
+use std::error::Error;
+
+pub struct JsonFileDepartment {
+    dir_path: String,
+}
+
+impl JsonFileDepartment {
+    pub const FILE_NAME: &'static str = "/department.json";
+    const KEY: &'static str = "departments";
+    pub fn new(path: &str) -> JsonFileDepartment {
+        let dir_path = String::from(path);
+        return JsonFileDepartment { dir_path: dir_path };
+    }
+
+    fn file_path(&self) -> String {
+        return format!("{}{}", self.dir_path, Self::FILE_NAME);
+    }
+}
+
+impl DepartmentRepository for JsonFileDepartment {
+    // レコードの形式は {"id", "name", "deleted_at"(UNIX秒またはnull)}
+    fn read_all_data(&self, department_list: &mut DepartmentList) -> Result<(), Box<dyn Error>> {
+        eprintln!("loading department file...");
+        let (records, max_id) = match read_records_with_max_id(&self.file_path(), Self::KEY)? {
+            Some(document) => document,
+            None => {
+                eprintln!("department file not found");
+                return Ok(());
+            }
+        };
+        for record in records.iter() {
+            let id = DepartmentId::new(required_number(record, "id")?);
+            let department = Department::new(required_str(record, "name")?)?;
+            match optional_number(record, "deleted_at")? {
+                Some(seconds) => department_list.add_deleted_department(
+                    department,
+                    id,
+                    Timestamp::new(seconds),
+                )?,
+                None => department_list.add_department(department, Some(id))?,
+            }
+        }
+        if let Some(max_id) = max_id {
+            department_list.reserve_ids(DepartmentId::new(max_id));
+        }
+        return Ok(());
+    }
+
+    fn overwrite_all_data(
+        &self,
+        department_list: &mut DepartmentList,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut departments: Vec<(DepartmentId, String, Option<Timestamp>)> = Vec::new();
+        for (id, name) in department_list.into_iter() {
+            departments.push((id, name, None));
+        }
+        for (id, department, deleted_at) in department_list.deleted_departments() {
+            departments.push((id, department.name(), Some(deleted_at)));
+        }
+        departments.sort_by_key(|(id, _name, _deleted_at)| *id);
+
+        let mut records: Vec<Value> = Vec::new();
+        for (id, name, deleted_at) in departments {
+            records.push(Value::Object(vec![
+                (String::from("id"), Value::Number(id.as_u64())),
+                (String::from("name"), Value::String(name)),
+                (
+                    String::from("deleted_at"),
+                    optional_number_value(deleted_at.map(|deleted_at| deleted_at.seconds())),
+                ),
+            ]));
+        }
+        let max_id = department_list.max_id().as_u64();
+        return write_records_with_max_id(&self.file_path(), Self::KEY, records, max_id);
+    }
+
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(Some(file_fingerprint(&self.file_path())?));
+    }
+}
@@ -0,0 +1,98 @@
+use super::super::super::domain::department::id::DepartmentId;
+use super::super::super::domain::department::list::DepartmentList;
+use super::super::super::domain::member::MemberList;
+use super::super::super::domain::person::id::PersonId;
+use super::super::super::domain::person::list::PersonList;
+use super::super::super::format::json::Value;
+use super::super::super::repository::member::MemberRepository;
+use super::super::file_fingerprint;
+use super::{read_records, required_number, required_str, write_records};
+use std::error::Error;
+
+pub struct JsonFileMember {
+    dir_path: String,
+}
+
+impl JsonFileMember {
+    pub const FILE_NAME: &'static str = "/member.json";
+    const KEY: &'static str = "members";
+    const VALID: &'static str = "valid";
+    const SUSPENDED: &'static str = "suspended";
+    pub fn new(path: &str) -> JsonFileMember {
+        let dir_path = String::from(path);
+        return JsonFileMember { dir_path: dir_path };
+    }
+
+    fn file_path(&self) -> String {
+        return format!("{}{}", self.dir_path, Self::FILE_NAME);
+    }
+}
+
+impl MemberRepository for JsonFileMember {
+    // レコードの形式は {"department_id", "person_id", "status"("valid"または"suspended")}
+    fn read_all_data(
+        &self,
+        member_list: &mut MemberList,
+        person_list: &PersonList,
+        department_list: &DepartmentList,
+    ) -> Result<(), Box<dyn Error>> {
+        eprintln!("loading member file...");
+        let records = match read_records(&self.file_path(), Self::KEY)? {
+            Some(records) => records,
+            None => {
+                eprintln!("member file not found");
+                return Ok(());
+            }
+        };
+        for record in records.iter() {
+            let department_id = DepartmentId::new(required_number(record, "department_id")?);
+            let person_id = PersonId::new(required_number(record, "person_id")?);
+            match required_str(record, "status")? {
+                Self::VALID => member_list.add_person_to_department(
+                    person_id,
+                    department_id,
+                    person_list,
+                    department_list,
+                )?,
+                Self::SUSPENDED => member_list.add_suspended_member(
+                    person_id,
+                    department_id,
+                    person_list,
+                    department_list,
+                )?,
+                _ => return Err("JsonFileMember::read_all_data : Invalid status!".into()),
+            }
+        }
+        return Ok(());
+    }
+
+    fn overwrite_all_data(&self, member_list: &mut MemberList) -> Result<(), Box<dyn Error>> {
+        let mut members: Vec<(DepartmentId, PersonId, &str)> = Vec::new();
+        for (department_id, person_id_list) in member_list.into_iter() {
+            for person_id in person_id_list {
+                members.push((department_id, person_id, Self::VALID));
+            }
+        }
+        for (department_id, person_id) in member_list.suspended_members() {
+            members.push((department_id, person_id, Self::SUSPENDED));
+        }
+        members.sort();
+
+        let mut records: Vec<Value> = Vec::new();
+        for (department_id, person_id, status) in members {
+            records.push(Value::Object(vec![
+                (
+                    String::from("department_id"),
+                    Value::Number(department_id.as_u64()),
+                ),
+                (String::from("person_id"), Value::Number(person_id.as_u64())),
+                (String::from("status"), Value::String(String::from(status))),
+            ]));
+        }
+        return write_records(&self.file_path(), Self::KEY, records);
+    }
+
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(Some(file_fingerprint(&self.file_path())?));
+    }
+}
@@ -0,0 +1,279 @@
+pub mod department;
+pub mod member;
+pub mod person;
+
+use super::super::format::json::{self, Value};
+use std::error::Error;
+use std::fs;
+use std::io;
+
+// JSONファイルの形式を変更した場合に上げる
+// 読み込み時には、このバージョン以下のファイルのみを受け付ける
+pub const SCHEMA_VERSION: u64 = 1;
+
+// 古いバージョンの文書を１バージョンずつ現在の形式に変換する関数
+// UPGRADES[i] はバージョン i+1 の文書をバージョン i+2 の形式に変換する
+// 形式を変更する場合はSCHEMA_VERSIONを上げ、ここに変換を追加する
+type Upgrade = fn(Value) -> Result<Value, Box<dyn Error>>;
+const UPGRADES: &[Upgrade] = &[];
+
+// ファイルの形式は {"schema_version": 1, "max_id": 最大のID(個人と部署のみ), "<key>": [レコード, ...]}
+// ファイルが存在しない場合はNoneを返す
+// 読み込めない場合(権限が無い、UTF-8でないなど)は、空のデータで上書きしないようエラーを返す
+fn read_document(file_path: &str) -> Result<Option<(u64, Value)>, Box<dyn Error>> {
+    let contents = match fs::read_to_string(file_path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let document = json::parse(&contents)?;
+    let version = match document.get("schema_version").and_then(|v| v.as_u64()) {
+        Some(version) => version,
+        None => return Err("json::read_document : schema_version is missing!".into()),
+    };
+    if version == 0 || version > SCHEMA_VERSION {
+        let message = format!(
+            "json::read_document : Unsupported schema version! ({}, supported: 1-{})",
+            version, SCHEMA_VERSION
+        );
+        return Err(message.into());
+    }
+    return Ok(Some((version, document)));
+}
+
+// 古いバージョンのファイルは現在の形式に変換してから読み込む
+// レコードに未知のキーがあっても無視するので、項目の追加はバージョンを上げずに行える
+fn read_records(file_path: &str, key: &str) -> Result<Option<Vec<Value>>, Box<dyn Error>> {
+    let records = read_records_with_max_id(file_path, key)?;
+    return Ok(records.map(|(records, _max_id)| records));
+}
+
+// レコードと最上位の "max_id" の組
+type RecordsWithMaxId = (Vec<Value>, Option<u64>);
+
+// 個人と部署のファイルは、完全削除したIDを再利用しないよう最上位に "max_id" を持つ
+// "max_id" の無いファイル(以前のバージョンで保存したもの)はNoneを返す
+fn read_records_with_max_id(
+    file_path: &str,
+    key: &str,
+) -> Result<Option<RecordsWithMaxId>, Box<dyn Error>> {
+    let (version, mut document) = match read_document(file_path)? {
+        Some(document) => document,
+        None => return Ok(None),
+    };
+    for upgrade in UPGRADES.iter().skip((version - 1) as usize) {
+        document = upgrade(document)?;
+    }
+    let max_id = optional_number(&document, "max_id")?;
+    match document.get(key).and_then(|v| v.as_array()) {
+        Some(records) => return Ok(Some((records.clone(), max_id))),
+        None => return Err("json::read_records : Invalid file format!".into()),
+    }
+}
+
+// データファイルのうち最も古いschema_versionを返す
+// ファイルが１つも存在しない場合はNoneを返す
+pub fn schema_version(dir_path: &str) -> Result<Option<u64>, Box<dyn Error>> {
+    let file_names = [
+        person::JsonFilePerson::FILE_NAME,
+        department::JsonFileDepartment::FILE_NAME,
+        member::JsonFileMember::FILE_NAME,
+    ];
+    let mut oldest: Option<u64> = None;
+    for file_name in file_names {
+        let file_path = format!("{}{}", dir_path, file_name);
+        if let Some((version, _document)) = read_document(&file_path)? {
+            oldest = Some(oldest.map_or(version, |oldest| oldest.min(version)));
+        }
+    }
+    return Ok(oldest);
+}
+
+fn write_records(file_path: &str, key: &str, records: Vec<Value>) -> Result<(), Box<dyn Error>> {
+    let document = Value::Object(vec![
+        (
+            String::from("schema_version"),
+            Value::Number(SCHEMA_VERSION),
+        ),
+        (String::from(key), Value::Array(records)),
+    ]);
+    fs::write(file_path, document.to_json())?;
+    return Ok(());
+}
+
+fn write_records_with_max_id(
+    file_path: &str,
+    key: &str,
+    records: Vec<Value>,
+    max_id: u64,
+) -> Result<(), Box<dyn Error>> {
+    let document = Value::Object(vec![
+        (
+            String::from("schema_version"),
+            Value::Number(SCHEMA_VERSION),
+        ),
+        (String::from("max_id"), Value::Number(max_id)),
+        (String::from(key), Value::Array(records)),
+    ]);
+    fs::write(file_path, document.to_json())?;
+    return Ok(());
+}
+
+// レコードの必須の数値項目を返す
+fn required_number(record: &Value, key: &str) -> Result<u64, Box<dyn Error>> {
+    match record.get(key).and_then(|v| v.as_u64()) {
+        Some(value) => return Ok(value),
+        None => {
+            let message = format!("json::required_number : {} is missing!", key);
+            return Err(message.into());
+        }
+    }
+}
+
+// レコードの必須の文字列項目を返す
+fn required_str<'a>(record: &'a Value, key: &str) -> Result<&'a str, Box<dyn Error>> {
+    match record.get(key).and_then(|v| v.as_str()) {
+        Some(value) => return Ok(value),
+        None => {
+            let message = format!("json::required_str : {} is missing!", key);
+            return Err(message.into());
+        }
+    }
+}
+
+// 任意の文字列項目を返す
+// キーが存在しない場合とnullの場合はNoneとする
+fn optional_str<'a>(record: &'a Value, key: &str) -> Result<Option<&'a str>, Box<dyn Error>> {
+    match record.get(key) {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::String(value)) => return Ok(Some(value)),
+        Some(_) => {
+            let message = format!("json::optional_str : {} must be a string!", key);
+            return Err(message.into());
+        }
+    }
+}
+
+// 任意の数値項目を返す
+fn optional_number(record: &Value, key: &str) -> Result<Option<u64>, Box<dyn Error>> {
+    match record.get(key) {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::Number(value)) => return Ok(Some(*value)),
+        Some(_) => {
+            let message = format!("json::optional_number : {} must be a number!", key);
+            return Err(message.into());
+        }
+    }
+}
+
+fn optional_number_value(value: Option<u64>) -> Value {
+    match value {
+        Some(value) => return Value::Number(value),
+        None => return Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::domain::department::id::DepartmentId;
+    use super::super::super::domain::department::list::DepartmentList;
+    use super::super::super::domain::department::Department;
+    use super::super::super::domain::member::MemberList;
+    use super::super::super::domain::person::id::PersonId;
+    use super::super::super::domain::person::list::PersonList;
+    use super::super::super::domain::person::Person;
+    use super::super::super::repository::department::DepartmentRepository;
+    use super::super::super::repository::member::MemberRepository;
+    use super::super::super::repository::person::PersonRepository;
+    use super::department::JsonFileDepartment;
+    use super::member::JsonFileMember;
+    use super::person::JsonFilePerson;
+    use super::*;
+
+    #[test]
+    fn read_records_version_err() {
+        let dir = std::env::temp_dir().join(format!("hr_json_version_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("person.json");
+        let file_path = file_path.to_str().unwrap();
+        assert!(read_records(file_path, "persons").unwrap().is_none());
+
+        fs::write(file_path, "{\"schema_version\": 99, \"persons\": []}").unwrap();
+        assert!(read_records(file_path, "persons").is_err());
+        fs::write(file_path, "{\"persons\": []}").unwrap();
+        assert!(read_records(file_path, "persons").is_err());
+        // 存在しないファイル以外の読み込みの失敗は、ファイルが無いものとして扱わない
+        fs::write(file_path, [0xff, 0xfe, 0x00]).unwrap();
+        assert!(read_records(file_path, "persons").is_err());
+
+        write_records(file_path, "persons", vec![Value::Number(1)]).unwrap();
+        let records = read_records(file_path, "persons").unwrap().unwrap();
+        assert_eq!(records, vec![Value::Number(1)]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn overwrite_and_read_all_data_ok() {
+        let dir = std::env::temp_dir().join(format!("hr_json_round_trip_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir_path = dir.to_str().unwrap();
+
+        let mut person_list = PersonList::new();
+        let mut harry = Person::new("Potter", Some("Harry")).unwrap();
+        harry.set_attribute("employee_number", "E001").unwrap();
+        harry.set_attribute("reading_last", "ぽったー").unwrap();
+        person_list.add_person(harry, None).unwrap();
+        let ron = Person::new("Weasley", None).unwrap();
+        person_list.add_person(ron, None).unwrap();
+        let mut department_list = DepartmentList::new();
+        let department = Department::new("Gryffindor").unwrap();
+        department_list.add_department(department, None).unwrap();
+        let mut member_list = MemberList::new();
+        for id in [1, 2] {
+            member_list
+                .add_person_to_department(
+                    PersonId::new(id),
+                    DepartmentId::new(1),
+                    &person_list,
+                    &department_list,
+                )
+                .unwrap();
+        }
+        person_list.delete_person(PersonId::new(2)).unwrap();
+        member_list.suspend_person(PersonId::new(2));
+
+        JsonFilePerson::new(dir_path)
+            .overwrite_all_data(&mut person_list)
+            .unwrap();
+        JsonFileDepartment::new(dir_path)
+            .overwrite_all_data(&mut department_list)
+            .unwrap();
+        JsonFileMember::new(dir_path)
+            .overwrite_all_data(&mut member_list)
+            .unwrap();
+
+        let mut person_list = PersonList::new();
+        let mut department_list = DepartmentList::new();
+        let mut member_list = MemberList::new();
+        JsonFilePerson::new(dir_path)
+            .read_all_data(&mut person_list)
+            .unwrap();
+        JsonFileDepartment::new(dir_path)
+            .read_all_data(&mut department_list)
+            .unwrap();
+        JsonFileMember::new(dir_path)
+            .read_all_data(&mut member_list, &person_list, &department_list)
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let harry = person_list.person(PersonId::new(1)).unwrap();
+        assert_eq!(harry.employee_number(), Some("E001"));
+        assert_eq!(harry.attributes().len(), 2);
+        assert_eq!(person_list.deleted_persons().len(), 1);
+        assert!(member_list.is_member(PersonId::new(1), DepartmentId::new(1)));
+        assert_eq!(
+            member_list.suspended_members(),
+            vec![(DepartmentId::new(1), PersonId::new(2))]
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use super::super::super::domain::person::id::PersonId;
+use super::super::super::domain::person::list::PersonList;
+use super::super::super::domain::person::Person;
+use super::super::super::domain::timestamp::Timestamp;
+use super::super::super::format::json::Value;
+use super::super::super::repository::person::PersonRepository;
+use super::super::file_fingerprint;
+use super::{optional_number, optional_number_value, optional_str, read_records_with_max_id};
+use super::{required_number, required_str, write_records_with_max_id};
+use std::error::Error;
+
+pub struct JsonFilePerson {
+    dir_path: String,
+}
+
+impl JsonFilePerson {
+    pub const FILE_NAME: &'static str = "/person.json";
+    const KEY: &'static str = "persons";
+    pub fn new(path: &str) -> JsonFilePerson {
+        let dir_path = String::from(path);
+        return JsonFilePerson { dir_path: dir_path };
+    }
+
+    fn file_path(&self) -> String {
+        return format!("{}{}", self.dir_path, Self::FILE_NAME);
+    }
+}
+
+impl PersonRepository for JsonFilePerson {
+    // レコードの形式は
+    // {"id", "last_name", "first_name", Person::ATTRIBUTE_KEYSの各項目, "deleted_at"(UNIX秒)}
+    // 値のない項目はnull
+    fn read_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>> {
+        eprintln!("loading person file...");
+        let (records, max_id) = match read_records_with_max_id(&self.file_path(), Self::KEY)? {
+            Some(document) => document,
+            None => {
+                eprintln!("person file not found");
+                return Ok(());
+            }
+        };
+        for record in records.iter() {
+            let id = PersonId::new(required_number(record, "id")?);
+            let last_name = required_str(record, "last_name")?;
+            let first_name = optional_str(record, "first_name")?;
+            let mut person = Person::new(last_name, first_name)?;
+            for key in Person::ATTRIBUTE_KEYS.iter() {
+                if let Some(value) = optional_str(record, key)? {
+                    person.set_attribute(key, value)?;
+                }
+            }
+            match optional_number(record, "deleted_at")? {
+                Some(seconds) => {
+                    person_list.add_deleted_person(person, id, Timestamp::new(seconds))?
+                }
+                None => person_list.add_person(person, Some(id))?,
+            }
+        }
+        if let Some(max_id) = max_id {
+            person_list.reserve_ids(PersonId::new(max_id));
+        }
+        return Ok(());
+    }
+
+    fn overwrite_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>> {
+        let ids: Vec<PersonId> = person_list.into_iter().map(|(id, _name)| id).collect();
+        let mut persons: Vec<(PersonId, &Person, Option<Timestamp>)> = Vec::new();
+        for id in ids {
+            persons.push((id, person_list.person(id)?, None));
+        }
+        for (id, person, deleted_at) in person_list.deleted_persons() {
+            persons.push((id, person, Some(deleted_at)));
+        }
+        persons.sort_by_key(|(id, _person, _deleted_at)| *id);
+
+        let mut records: Vec<Value> = Vec::new();
+        for (id, person, deleted_at) in persons {
+            let person_name = person.person_name();
+            let mut record = vec![
+                (String::from("id"), Value::Number(id.as_u64())),
+                (
+                    String::from("last_name"),
+                    Value::String(String::from(person_name.last_name())),
+                ),
+                (
+                    String::from("first_name"),
+                    Value::optional_string(person_name.first_name()),
+                ),
+            ];
+            let attributes = person.attributes();
+            for key in Person::ATTRIBUTE_KEYS.iter() {
+                let value = attributes
+                    .iter()
+                    .find(|(attribute_key, _value)| attribute_key == key)
+                    .map(|(_key, value)| value.as_str());
+                record.push((String::from(*key), Value::optional_string(value)));
+            }
+            record.push((
+                String::from("deleted_at"),
+                optional_number_value(deleted_at.map(|deleted_at| deleted_at.seconds())),
+            ));
+            records.push(Value::Object(record));
+        }
+        let max_id = person_list.max_id().as_u64();
+        return write_records_with_max_id(&self.file_path(), Self::KEY, records, max_id);
+    }
+
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(Some(file_fingerprint(&self.file_path())?));
+    }
+}
@@ -4,6 +4,7 @@ use super::super::domain::member::MemberList;
 use super::super::domain::person::id::PersonId;
 use super::super::domain::person::list::PersonList;
 use super::super::repository::member::MemberRepository;
+use super::file_fingerprint;
 use std::error::Error;
 use std::fs::File;
 use std::io::prelude::*;
@@ -13,7 +14,8 @@ pub struct TextFileMember {
 }
 
 impl TextFileMember {
-    const FILE_NAME: &'static str = "/member.txt";
+    pub const FILE_NAME: &'static str = "/member.txt";
+    const SUSPENDED: &'static str = "suspended";
     pub fn new(path: &str) -> TextFileMember {
         let dir_path = String::from(path);
         return TextFileMember { dir_path: dir_path };
@@ -29,7 +31,7 @@ impl MemberRepository for TextFileMember {
     ) -> Result<(), Box<dyn Error>> {
         let mut file_path = String::new();
         file_path.push_str(&self.dir_path[..]);
-        file_path.push_str(&Self::FILE_NAME[..]);
+        file_path.push_str(Self::FILE_NAME);
         eprintln!("loading member file...");
         let mut file = match File::open(&file_path) {
             Ok(file) => file,
@@ -41,22 +43,33 @@ impl MemberRepository for TextFileMember {
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
 
+        // 1行の形式は "部署ID 個人ID"
+        // 個人または部署の削除により無効となっている所属は末尾に "suspended" を持つ
         let members: Vec<&str> = contents.split_terminator('\n').collect();
         for member in members {
             let info: Vec<&str> = member.split_whitespace().collect();
-            if info.len() != 2 {
+            if info.len() != 2 && !(info.len() == 3 && info[2] == Self::SUSPENDED) {
                 return Err("TextFileMember::read_all_data : Invalid file format!".into());
             }
             let department_id: u64 = info[0].parse()?;
             let department_id = DepartmentId::new(department_id);
             let person_id: u64 = info[1].parse()?;
             let person_id = PersonId::new(person_id);
-            member_list.add_person_to_department(
-                person_id,
-                department_id,
-                person_list,
-                department_list,
-            )?;
+            if info.len() == 3 {
+                member_list.add_suspended_member(
+                    person_id,
+                    department_id,
+                    person_list,
+                    department_list,
+                )?;
+            } else {
+                member_list.add_person_to_department(
+                    person_id,
+                    department_id,
+                    person_list,
+                    department_list,
+                )?;
+            }
         }
 
         return Ok(());
@@ -65,7 +78,7 @@ impl MemberRepository for TextFileMember {
     fn overwrite_all_data(&self, member_list: &mut MemberList) -> Result<(), Box<dyn Error>> {
         let mut file_path = String::new();
         file_path.push_str(&self.dir_path[..]);
-        file_path.push_str(&Self::FILE_NAME[..]);
+        file_path.push_str(Self::FILE_NAME);
         let mut file = match File::create(file_path) {
             Ok(file) => file,
             Err(e) => {
@@ -75,15 +88,21 @@ impl MemberRepository for TextFileMember {
 
         for (department_id, person_id_list) in member_list.into_iter() {
             for person_id in person_id_list {
-                writeln!(
-                    file,
-                    "{} {}",
-                    department_id.to_string(),
-                    person_id.to_string()
-                )?;
+                writeln!(file, "{} {}", department_id, person_id)?;
             }
         }
+        for (department_id, person_id) in member_list.suspended_members() {
+            writeln!(file, "{} {} {}", department_id, person_id, Self::SUSPENDED)?;
+        }
 
         return Ok(());
     }
+
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(Some(file_fingerprint(&format!(
+            "{}{}",
+            self.dir_path,
+            Self::FILE_NAME
+        ))?));
+    }
 }
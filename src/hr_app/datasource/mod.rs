@@ -1,3 +1,361 @@
+pub mod audit;
 pub mod department;
+pub mod json;
 pub mod member;
 pub mod person;
+pub mod sqlite;
+
+use super::domain::timestamp::Timestamp;
+use super::repository::Repositories;
+use department::TextFileDepartment;
+use json::department::JsonFileDepartment;
+use json::member::JsonFileMember;
+use json::person::JsonFilePerson;
+use member::TextFileMember;
+use person::TextFilePerson;
+use sqlite::department::SqliteDepartment;
+use sqlite::member::SqliteMember;
+use sqlite::person::SqlitePerson;
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+// データファイルの保存形式
+// 監査ログは形式によらず追記型のテキストファイルとする
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Storage {
+    Text,
+    Json,
+    Sqlite,
+}
+
+impl Storage {
+    pub fn new(name: &str) -> Option<Storage> {
+        match name {
+            "text" => return Some(Storage::Text),
+            "json" => return Some(Storage::Json),
+            "sqlite" => return Some(Storage::Sqlite),
+            _ => return None,
+        }
+    }
+
+    // コマンドライン引数の "--storage text|json|sqlite" から決める
+    // 指定がない場合は従来のテキストファイルとする
+    pub fn detect(args: &[String]) -> Result<Storage, String> {
+        return Storage::from_option(args, "--storage").map(|s| s.unwrap_or(Storage::Text));
+    }
+
+    // コマンドライン引数の "<option> text|json|sqlite" を読む
+    // optionが無い場合はNoneを返す
+    pub fn from_option(args: &[String], option: &str) -> Result<Option<Storage>, String> {
+        if let Some(index) = args.iter().position(|arg| arg == option) {
+            let name = match args.get(index + 1) {
+                Some(name) => name,
+                None => return Err(format!("{} requires text, json or sqlite", option)),
+            };
+            match Storage::new(name) {
+                Some(storage) => return Ok(Some(storage)),
+                None => return Err(format!("unsupported storage: {}", name)),
+            }
+        }
+        return Ok(None);
+    }
+
+    // データディレクトリ内のファイル名("/"から始まる)
+    pub fn file_names(&self) -> Vec<&'static str> {
+        match self {
+            Storage::Text => {
+                return vec![
+                    TextFilePerson::FILE_NAME,
+                    TextFileDepartment::FILE_NAME,
+                    TextFileDepartment::LEGACY_FILE_NAME,
+                    TextFileMember::FILE_NAME,
+                ]
+            }
+            Storage::Json => {
+                return vec![
+                    JsonFilePerson::FILE_NAME,
+                    JsonFileDepartment::FILE_NAME,
+                    JsonFileMember::FILE_NAME,
+                ]
+            }
+            Storage::Sqlite => return vec![sqlite::FILE_NAME],
+        }
+    }
+
+    // データファイルが１つでも存在するかどうか
+    pub fn exists(&self, dir_path: &str) -> bool {
+        return self
+            .file_names()
+            .iter()
+            .any(|file_name| Path::new(&format!("{}{}", dir_path, file_name)).exists());
+    }
+
+    // 保存されているデータの形式のバージョン
+    // テキストファイルはバージョンを持たないのでNoneを返す
+    pub fn schema_version(&self, dir_path: &str) -> Result<Option<u64>, Box<dyn Error>> {
+        match self {
+            Storage::Text => return Ok(None),
+            Storage::Json => return json::schema_version(dir_path),
+            Storage::Sqlite => return sqlite::schema_version(dir_path),
+        }
+    }
+
+    // このソフトウェアが書き込む形式のバージョン
+    pub fn current_schema_version(&self) -> Option<u64> {
+        match self {
+            Storage::Text => return None,
+            Storage::Json => return Some(json::SCHEMA_VERSION),
+            Storage::Sqlite => return Some(sqlite::SCHEMA_VERSION),
+        }
+    }
+
+    pub fn repositories(&self, dir_path: &str) -> Repositories {
+        match self {
+            Storage::Text => {
+                return Repositories {
+                    person: Box::new(TextFilePerson::new(dir_path)),
+                    department: Box::new(TextFileDepartment::new(dir_path)),
+                    member: Box::new(TextFileMember::new(dir_path)),
+                }
+            }
+            Storage::Json => {
+                return Repositories {
+                    person: Box::new(JsonFilePerson::new(dir_path)),
+                    department: Box::new(JsonFileDepartment::new(dir_path)),
+                    member: Box::new(JsonFileMember::new(dir_path)),
+                }
+            }
+            Storage::Sqlite => {
+                return Repositories {
+                    person: Box::new(SqlitePerson::new(dir_path)),
+                    department: Box::new(SqliteDepartment::new(dir_path)),
+                    member: Box::new(SqliteMember::new(dir_path)),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Storage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Storage::Text => return write!(f, "text"),
+            Storage::Json => return write!(f, "json"),
+            Storage::Sqlite => return write!(f, "sqlite"),
+        }
+    }
+}
+
+// 保存形式のデータファイルを "backup-<UNIX秒>" ディレクトリに複製し、複製したファイルのパスを返す
+// 同じ秒に複数回バックアップした場合は "backup-<UNIX秒>-2" のように番号を付け、以前のバックアップを上書きしない
+// 存在しないファイルは無視する
+pub fn backup(dir_path: &str, storages: &[Storage]) -> Result<Vec<String>, Box<dyn Error>> {
+    let name = format!("backup-{}", Timestamp::now().seconds());
+    let mut backup_dir = Path::new(dir_path).join(&name);
+    let mut number = 2;
+    while backup_dir.exists() {
+        backup_dir = Path::new(dir_path).join(format!("{}-{}", name, number));
+        number += 1;
+    }
+    let mut copied: Vec<String> = Vec::new();
+    for storage in storages.iter() {
+        for file_name in storage.file_names() {
+            let source = format!("{}{}", dir_path, file_name);
+            if !Path::new(&source).exists() {
+                continue;
+            }
+            fs::create_dir_all(&backup_dir)?;
+            let destination = backup_dir.join(file_name.trim_start_matches('/'));
+            fs::copy(&source, &destination)?;
+            copied.push(destination.to_string_lossy().to_string());
+        }
+    }
+    return Ok(copied);
+}
+
+// ファイルの内容のハッシュ値を返す
+// ファイルが存在しない場合は0とする
+pub fn file_fingerprint(file_path: &str) -> Result<u64, Box<dyn Error>> {
+    if !Path::new(file_path).exists() {
+        return Ok(0);
+    }
+    let mut hasher = DefaultHasher::new();
+    fs::read(file_path)?.hash(&mut hasher);
+    return Ok(hasher.finish());
+}
+
+// データディレクトリのロック
+// データを変更するセッションは排他ロックを取得し、同時に１つしか起動できないようにする
+// 読み取り専用のセッションは共有ロックを取得し、保存中の書きかけのファイルを読まないようにする
+// ロックはこの値が破棄される(ファイルが閉じられる)時に解放される
+pub struct DirectoryLock {
+    _file: File,
+}
+
+impl DirectoryLock {
+    const FILE_NAME: &'static str = "/.lock";
+
+    // 他のセッションがロックを取得している場合は待たずにエラーを返す
+    pub fn acquire(dir_path: &str) -> Result<DirectoryLock, Box<dyn Error>> {
+        return DirectoryLock::lock(dir_path, false);
+    }
+
+    // 共有ロックを取得する
+    // 読み取り専用のセッション同士は同時に起動できる
+    // データを変更するセッションがロックを取得している場合は待たずにエラーを返す
+    pub fn acquire_shared(dir_path: &str) -> Result<DirectoryLock, Box<dyn Error>> {
+        return DirectoryLock::lock(dir_path, true);
+    }
+
+    fn lock(dir_path: &str, shared: bool) -> Result<DirectoryLock, Box<dyn Error>> {
+        let file_path = format!("{}{}", dir_path, Self::FILE_NAME);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(file_path)?;
+        let result = if shared {
+            file.try_lock_shared()
+        } else {
+            file.try_lock()
+        };
+        match result {
+            Ok(()) => return Ok(DirectoryLock { _file: file }),
+            Err(TryLockError::WouldBlock) => {
+                return Err(
+                    "DirectoryLock::acquire : Another session is using the data directory!".into(),
+                )
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        return args.iter().map(|a| a.to_string()).collect();
+    }
+
+    #[test]
+    fn detect_ok() {
+        assert_eq!(Storage::detect(&args(&["hr"])), Ok(Storage::Text));
+        assert_eq!(
+            Storage::detect(&args(&["hr", "--storage", "json"])),
+            Ok(Storage::Json)
+        );
+        assert!(Storage::detect(&args(&["hr", "--storage", "xml"])).is_err());
+        assert!(Storage::detect(&args(&["hr", "--storage"])).is_err());
+    }
+
+    #[test]
+    fn directory_lock_ok() {
+        let dir = std::env::temp_dir().join(format!("hr_lock_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir_path = dir.to_str().unwrap();
+        let lock = DirectoryLock::acquire(dir_path).unwrap();
+        assert!(DirectoryLock::acquire(dir_path).is_err());
+        assert!(DirectoryLock::acquire_shared(dir_path).is_err());
+        drop(lock);
+        // 共有ロック同士は同時に取得できるが、排他ロックとは同時に取得できない
+        let shared = DirectoryLock::acquire_shared(dir_path).unwrap();
+        let another_shared = DirectoryLock::acquire_shared(dir_path).unwrap();
+        assert!(DirectoryLock::acquire(dir_path).is_err());
+        drop(shared);
+        drop(another_shared);
+        assert!(DirectoryLock::acquire(dir_path).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn backup_twice_ok() {
+        let dir = std::env::temp_dir().join(format!("hr_backup_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir_path = format!("{}/", dir.to_str().unwrap());
+        fs::write(
+            format!("{}{}", dir_path, TextFilePerson::FILE_NAME),
+            "1 Potter\n",
+        )
+        .unwrap();
+        let first = backup(&dir_path, &[Storage::Text]).unwrap();
+        let second = backup(&dir_path, &[Storage::Text]).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_ne!(first, second);
+        assert!(Path::new(&first[0]).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn from_option_ok() {
+        let args = args(&["hr", "migrate", "--to", "sqlite"]);
+        assert_eq!(
+            Storage::from_option(&args, "--to"),
+            Ok(Some(Storage::Sqlite))
+        );
+        assert_eq!(Storage::from_option(&args, "--from"), Ok(None));
+    }
+
+    #[test]
+    fn max_id_round_trip_ok() {
+        use super::super::domain::department::id::DepartmentId;
+        use super::super::domain::department::list::DepartmentList;
+        use super::super::domain::department::Department;
+        use super::super::domain::person::id::PersonId;
+        use super::super::domain::person::list::PersonList;
+        use super::super::domain::person::Person;
+
+        let dir = std::env::temp_dir().join(format!("hr_max_id_{}", std::process::id()));
+        for storage in [Storage::Text, Storage::Json, Storage::Sqlite] {
+            fs::create_dir_all(&dir).unwrap();
+            let dir_path = dir.to_str().unwrap();
+            let repositories = storage.repositories(dir_path);
+
+            // 最も大きいIDの個人と部署を完全削除しても、読み込み直した後に同じIDを割り振らない
+            let mut person_list = PersonList::new();
+            let mut department_list = DepartmentList::new();
+            for name in ["Gryffindor", "Slytherin"] {
+                let person = Person::new("Potter", None).unwrap();
+                person_list.add_person(person, None).unwrap();
+                let department = Department::new(name).unwrap();
+                department_list.add_department(department, None).unwrap();
+            }
+            person_list.delete_person(PersonId::new(2)).unwrap();
+            department_list
+                .delete_department(DepartmentId::new(2))
+                .unwrap();
+            person_list.purge_deleted_persons(Timestamp::now());
+            department_list.purge_deleted_departments(Timestamp::now());
+            repositories
+                .person
+                .overwrite_all_data(&mut person_list)
+                .unwrap();
+            repositories
+                .department
+                .overwrite_all_data(&mut department_list)
+                .unwrap();
+
+            let mut person_list = PersonList::new();
+            let mut department_list = DepartmentList::new();
+            repositories.person.read_all_data(&mut person_list).unwrap();
+            repositories
+                .department
+                .read_all_data(&mut department_list)
+                .unwrap();
+            assert_eq!(person_list.next_id(), PersonId::new(3), "{:?}", storage);
+            assert_eq!(
+                department_list.next_id(),
+                DepartmentId::new(3),
+                "{:?}",
+                storage
+            );
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use super::super::domain::person::id::PersonId;
 use super::super::domain::person::list::PersonList;
 use super::super::domain::person::Person;
+use super::super::domain::timestamp::Timestamp;
 use super::super::repository::person::PersonRepository;
+use super::file_fingerprint;
 use std::error::Error;
 use std::fs::File;
 use std::io::prelude::*;
@@ -11,7 +13,9 @@ pub struct TextFilePerson {
 }
 
 impl TextFilePerson {
-    const FILE_NAME: &'static str = "/person.txt";
+    pub const FILE_NAME: &'static str = "/person.txt";
+    const DELETED_AT_KEY: &'static str = "deleted_at";
+    const MAX_ID_PREFIX: &'static str = "max_id=";
     pub fn new(path: &str) -> TextFilePerson {
         let dir_path = String::from(path);
         return TextFilePerson { dir_path: dir_path };
@@ -22,7 +26,7 @@ impl PersonRepository for TextFilePerson {
     fn read_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>> {
         let mut file_path = String::new();
         file_path.push_str(&self.dir_path[..]);
-        file_path.push_str(&Self::FILE_NAME[..]);
+        file_path.push_str(Self::FILE_NAME);
         eprintln!("loading person file...");
         let mut file = match File::open(&file_path) {
             Ok(file) => file,
@@ -34,9 +38,18 @@ impl PersonRepository for TextFilePerson {
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
 
+        // 1行の形式は "ID 姓 名(任意) キー=値(任意、複数可)"
+        // 削除済の人は "deleted_at=削除日時(UNIX秒)" を持つ
+        // 完全削除したIDを再利用しないよう、最大のIDが最後の行のIDより大きい場合は "max_id=ID" の行を持つ
         let persons: Vec<&str> = contents.split_terminator('\n').collect();
         for person in persons {
-            let info: Vec<&str> = person.split_whitespace().collect();
+            if let Some(max_id) = person.trim().strip_prefix(Self::MAX_ID_PREFIX) {
+                person_list.reserve_ids(PersonId::new(max_id.parse()?));
+                continue;
+            }
+            let (info, attributes): (Vec<&str>, Vec<&str>) = person
+                .split_whitespace()
+                .partition(|token| !token.contains('='));
             if info.len() < 2 || info.len() > 3 {
                 return Err("TextFilePerson::read_all_data : Invalid file format!".into());
             }
@@ -44,8 +57,25 @@ impl PersonRepository for TextFilePerson {
             let id = PersonId::new(id);
             let last_name = info[1];
             let first_name = if info.len() == 3 { Some(info[2]) } else { None };
-            let new_person = Person::new(last_name, first_name)?;
-            person_list.add_person(new_person, Some(id))?;
+            let mut new_person = Person::new(last_name, first_name)?;
+            let mut deleted_at: Option<Timestamp> = None;
+            for attribute in attributes {
+                let (key, value) = match attribute.split_once('=') {
+                    Some(pair) => pair,
+                    None => {
+                        return Err("TextFilePerson::read_all_data : Invalid file format!".into());
+                    }
+                };
+                if key == Self::DELETED_AT_KEY {
+                    deleted_at = Some(Timestamp::new(value.parse()?));
+                } else {
+                    new_person.set_attribute(key, value)?;
+                }
+            }
+            match deleted_at {
+                Some(deleted_at) => person_list.add_deleted_person(new_person, id, deleted_at)?,
+                None => person_list.add_person(new_person, Some(id))?,
+            }
         }
 
         return Ok(());
@@ -54,7 +84,7 @@ impl PersonRepository for TextFilePerson {
     fn overwrite_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>> {
         let mut file_path = String::new();
         file_path.push_str(&self.dir_path[..]);
-        file_path.push_str(&Self::FILE_NAME[..]);
+        file_path.push_str(Self::FILE_NAME);
         let mut file = match File::create(file_path) {
             Ok(file) => file,
             Err(e) => {
@@ -62,10 +92,51 @@ impl PersonRepository for TextFilePerson {
             }
         };
 
-        for (id, name) in person_list.into_iter() {
-            writeln!(file, "{} {}", id.to_string(), name)?;
+        let ids: Vec<PersonId> = person_list.into_iter().map(|(id, _name)| id).collect();
+        let mut persons: Vec<(PersonId, &Person, Option<Timestamp>)> = Vec::new();
+        for id in ids {
+            persons.push((id, person_list.person(id)?, None));
+        }
+        for (id, person, deleted_at) in person_list.deleted_persons() {
+            persons.push((id, person, Some(deleted_at)));
+        }
+        persons.sort_by_key(|(id, _person, _deleted_at)| *id);
+        let last_id = match persons.last() {
+            Some((id, _person, _deleted_at)) => *id,
+            None => PersonId::new(0),
+        };
+
+        for (id, person, deleted_at) in persons {
+            let person_name = person.person_name();
+            let mut line = format!("{} {}", id, person_name.last_name());
+            if let Some(first_name) = person_name.first_name() {
+                line.push_str(&format!(" {}", first_name));
+            }
+            for (key, value) in person.attributes() {
+                line.push_str(&format!(" {}={}", key, value));
+            }
+            if let Some(deleted_at) = deleted_at {
+                line.push_str(&format!(
+                    " {}={}",
+                    Self::DELETED_AT_KEY,
+                    deleted_at.seconds()
+                ));
+            }
+            writeln!(file, "{}", line)?;
+        }
+        let max_id = person_list.max_id();
+        if max_id.greater_than(last_id) {
+            writeln!(file, "{}{}", Self::MAX_ID_PREFIX, max_id)?;
         }
 
         return Ok(());
     }
+
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(Some(file_fingerprint(&format!(
+            "{}{}",
+            self.dir_path,
+            Self::FILE_NAME
+        ))?));
+    }
 }
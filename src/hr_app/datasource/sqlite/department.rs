@@ -0,0 +1,86 @@
+use super::super::super::domain::department::id::DepartmentId;
+use super::super::super::domain::department::list::DepartmentList;
+use super::super::super::domain::department::Department;
+use super::super::super::domain::timestamp::Timestamp;
+use super::super::super::repository::department::DepartmentRepository;
+use super::super::file_fingerprint;
+use super::{exists, file_path, open, read_max_id, write_max_id};
+use rusqlite::params;
+use std::error::Error;
+
+pub struct SqliteDepartment {
+    dir_path: String,
+}
+
+impl SqliteDepartment {
+    pub fn new(path: &str) -> SqliteDepartment {
+        let dir_path = String::from(path);
+        return SqliteDepartment { dir_path: dir_path };
+    }
+}
+
+impl DepartmentRepository for SqliteDepartment {
+    fn read_all_data(&self, department_list: &mut DepartmentList) -> Result<(), Box<dyn Error>> {
+        eprintln!("loading department table...");
+        if !exists(&self.dir_path) {
+            eprintln!("database not found");
+            return Ok(());
+        }
+        let connection = open(&self.dir_path)?;
+        let mut statement =
+            connection.prepare("SELECT id, name, deleted_at FROM departments ORDER BY id")?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let id = DepartmentId::new(row.get(0)?);
+            let name: String = row.get(1)?;
+            let department = Department::new(&name)?;
+            let deleted_at: Option<u64> = row.get(2)?;
+            match deleted_at {
+                Some(seconds) => department_list.add_deleted_department(
+                    department,
+                    id,
+                    Timestamp::new(seconds),
+                )?,
+                None => department_list.add_department(department, Some(id))?,
+            }
+        }
+        if let Some(max_id) = read_max_id(&connection, "departments")? {
+            department_list.reserve_ids(DepartmentId::new(max_id));
+        }
+        return Ok(());
+    }
+
+    fn overwrite_all_data(
+        &self,
+        department_list: &mut DepartmentList,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut departments: Vec<(DepartmentId, String, Option<Timestamp>)> = Vec::new();
+        for (id, name) in department_list.into_iter() {
+            departments.push((id, name, None));
+        }
+        for (id, department, deleted_at) in department_list.deleted_departments() {
+            departments.push((id, department.name(), Some(deleted_at)));
+        }
+
+        let mut connection = open(&self.dir_path)?;
+        let transaction = connection.transaction()?;
+        transaction.execute("DELETE FROM departments", [])?;
+        {
+            let mut statement = transaction
+                .prepare("INSERT INTO departments (id, name, deleted_at) VALUES (?1, ?2, ?3)")?;
+            for (id, name, deleted_at) in departments {
+                let id = id.as_u64();
+                let deleted_at = deleted_at.map(|deleted_at| deleted_at.seconds());
+                statement.execute(params![id, name, deleted_at])?;
+            }
+        }
+        let max_id = department_list.max_id().as_u64();
+        write_max_id(&transaction, "departments", max_id)?;
+        transaction.commit()?;
+        return Ok(());
+    }
+
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(Some(file_fingerprint(&file_path(&self.dir_path))?));
+    }
+}
@@ -0,0 +1,97 @@
+use super::super::super::domain::department::id::DepartmentId;
+use super::super::super::domain::department::list::DepartmentList;
+use super::super::super::domain::member::MemberList;
+use super::super::super::domain::person::id::PersonId;
+use super::super::super::domain::person::list::PersonList;
+use super::super::super::repository::member::MemberRepository;
+use super::super::file_fingerprint;
+use super::{exists, file_path, open};
+use rusqlite::params;
+use std::error::Error;
+
+pub struct SqliteMember {
+    dir_path: String,
+}
+
+impl SqliteMember {
+    const VALID: &'static str = "valid";
+    const SUSPENDED: &'static str = "suspended";
+    pub fn new(path: &str) -> SqliteMember {
+        let dir_path = String::from(path);
+        return SqliteMember { dir_path: dir_path };
+    }
+}
+
+impl MemberRepository for SqliteMember {
+    fn read_all_data(
+        &self,
+        member_list: &mut MemberList,
+        person_list: &PersonList,
+        department_list: &DepartmentList,
+    ) -> Result<(), Box<dyn Error>> {
+        eprintln!("loading member table...");
+        if !exists(&self.dir_path) {
+            eprintln!("database not found");
+            return Ok(());
+        }
+        let connection = open(&self.dir_path)?;
+        let mut statement = connection.prepare(
+            "SELECT department_id, person_id, status FROM members \
+             ORDER BY department_id, person_id",
+        )?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let department_id = DepartmentId::new(row.get(0)?);
+            let person_id = PersonId::new(row.get(1)?);
+            let status: String = row.get(2)?;
+            match status.as_str() {
+                Self::VALID => member_list.add_person_to_department(
+                    person_id,
+                    department_id,
+                    person_list,
+                    department_list,
+                )?,
+                Self::SUSPENDED => member_list.add_suspended_member(
+                    person_id,
+                    department_id,
+                    person_list,
+                    department_list,
+                )?,
+                _ => return Err("SqliteMember::read_all_data : Invalid status!".into()),
+            }
+        }
+        return Ok(());
+    }
+
+    fn overwrite_all_data(&self, member_list: &mut MemberList) -> Result<(), Box<dyn Error>> {
+        let mut members: Vec<(DepartmentId, PersonId, &str)> = Vec::new();
+        for (department_id, person_id_list) in member_list.into_iter() {
+            for person_id in person_id_list {
+                members.push((department_id, person_id, Self::VALID));
+            }
+        }
+        for (department_id, person_id) in member_list.suspended_members() {
+            members.push((department_id, person_id, Self::SUSPENDED));
+        }
+
+        let mut connection = open(&self.dir_path)?;
+        let transaction = connection.transaction()?;
+        transaction.execute("DELETE FROM members", [])?;
+        {
+            let mut statement = transaction.prepare(
+                "INSERT INTO members (department_id, person_id, status) VALUES (?1, ?2, ?3)",
+            )?;
+            for (department_id, person_id, status) in members {
+                let department_id = department_id.as_u64();
+                let person_id = person_id.as_u64();
+                statement.execute(params![department_id, person_id, status])?;
+            }
+        }
+        transaction.commit()?;
+        return Ok(());
+    }
+
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(Some(file_fingerprint(&file_path(&self.dir_path))?));
+    }
+}
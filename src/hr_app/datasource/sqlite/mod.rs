@@ -0,0 +1,115 @@
+pub mod department;
+pub mod member;
+pub mod person;
+
+use rusqlite::{params, Connection};
+use std::error::Error;
+use std::path::Path;
+
+pub const FILE_NAME: &str = "/hr.sqlite3";
+
+// データベースの形式を１バージョンずつ変更するSQL
+// MIGRATIONS[i] はバージョン i のデータベースをバージョン i+1 に変換する
+// バージョンはPRAGMA user_versionに記録する(新規作成時は0)
+// 形式を変更する場合はここにSQLを追加する
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE persons (
+        id INTEGER PRIMARY KEY,
+        last_name TEXT NOT NULL,
+        first_name TEXT,
+        employee_number TEXT,
+        middle TEXT,
+        nickname TEXT,
+        reading_last TEXT,
+        reading_first TEXT,
+        name_order TEXT,
+        deleted_at INTEGER
+    );
+    CREATE TABLE departments (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        deleted_at INTEGER
+    );
+    CREATE TABLE members (
+        department_id INTEGER NOT NULL,
+        person_id INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        PRIMARY KEY (department_id, person_id)
+    );",
+    // 完全削除したIDを再利用しないよう、表ごとにこれまでに割り振った最大のIDを保存する
+    "CREATE TABLE max_ids (
+        table_name TEXT PRIMARY KEY,
+        max_id INTEGER NOT NULL
+    );",
+];
+
+pub const SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+fn file_path(dir_path: &str) -> String {
+    return format!("{}{}", dir_path, FILE_NAME);
+}
+
+fn user_version(connection: &Connection) -> Result<u64, Box<dyn Error>> {
+    let version: u64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    return Ok(version);
+}
+
+// データベースを開き、古いバージョンであれば現在の形式に変換する
+// データベースが存在しない場合は作成する
+fn open(dir_path: &str) -> Result<Connection, Box<dyn Error>> {
+    let mut connection = Connection::open(file_path(dir_path))?;
+    let version = user_version(&connection)?;
+    if version > SCHEMA_VERSION {
+        let message = format!(
+            "sqlite::open : Unsupported schema version! ({}, supported: 0-{})",
+            version, SCHEMA_VERSION
+        );
+        return Err(message.into());
+    }
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(sql)?;
+        transaction.pragma_update(None, "user_version", index as u64 + 1)?;
+        transaction.commit()?;
+    }
+    return Ok(connection);
+}
+
+// 表に保存されている最大のIDを返す
+// 以前のバージョンで保存したデータベースには無いので、その場合はNoneを返す
+fn read_max_id(connection: &Connection, table_name: &str) -> Result<Option<u64>, Box<dyn Error>> {
+    let mut statement = connection.prepare("SELECT max_id FROM max_ids WHERE table_name = ?1")?;
+    let mut rows = statement.query([table_name])?;
+    match rows.next()? {
+        Some(row) => return Ok(Some(row.get(0)?)),
+        None => return Ok(None),
+    }
+}
+
+fn write_max_id(
+    connection: &Connection,
+    table_name: &str,
+    max_id: u64,
+) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        "INSERT OR REPLACE INTO max_ids (table_name, max_id) VALUES (?1, ?2)",
+        params![table_name, max_id],
+    )?;
+    return Ok(());
+}
+
+// データベースが存在するかどうか
+// 読み込み時に空のデータベースを作成しないために使用する
+fn exists(dir_path: &str) -> bool {
+    return Path::new(&file_path(dir_path)).exists();
+}
+
+// データベースのバージョンを変換せずに返す
+// データベースが存在しない場合はNoneを返す
+pub fn schema_version(dir_path: &str) -> Result<Option<u64>, Box<dyn Error>> {
+    if !exists(dir_path) {
+        return Ok(None);
+    }
+    let connection = Connection::open(file_path(dir_path))?;
+    return Ok(Some(user_version(&connection)?));
+}
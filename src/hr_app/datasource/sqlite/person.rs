@@ -0,0 +1,126 @@
+use super::super::super::domain::person::id::PersonId;
+use super::super::super::domain::person::list::PersonList;
+use super::super::super::domain::person::Person;
+use super::super::super::domain::timestamp::Timestamp;
+use super::super::super::repository::person::PersonRepository;
+use super::super::file_fingerprint;
+use super::{exists, file_path, open, read_max_id, write_max_id};
+use rusqlite::params;
+use std::error::Error;
+
+pub struct SqlitePerson {
+    dir_path: String,
+}
+
+impl SqlitePerson {
+    // Person::ATTRIBUTE_KEYSの各項目に対応する列
+    // "order" はSQLの予約語なので "name_order" とする
+    const ATTRIBUTE_COLUMNS: [&'static str; 6] = [
+        "employee_number",
+        "middle",
+        "nickname",
+        "reading_last",
+        "reading_first",
+        "name_order",
+    ];
+    pub fn new(path: &str) -> SqlitePerson {
+        let dir_path = String::from(path);
+        return SqlitePerson { dir_path: dir_path };
+    }
+}
+
+impl PersonRepository for SqlitePerson {
+    fn read_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>> {
+        eprintln!("loading person table...");
+        if !exists(&self.dir_path) {
+            eprintln!("database not found");
+            return Ok(());
+        }
+        let connection = open(&self.dir_path)?;
+        let sql = format!(
+            "SELECT id, last_name, first_name, {}, deleted_at FROM persons ORDER BY id",
+            Self::ATTRIBUTE_COLUMNS.join(", ")
+        );
+        let mut statement = connection.prepare(&sql)?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let id = PersonId::new(row.get(0)?);
+            let last_name: String = row.get(1)?;
+            let first_name: Option<String> = row.get(2)?;
+            let mut person = Person::new(&last_name, first_name.as_deref())?;
+            for (index, key) in Person::ATTRIBUTE_KEYS.iter().enumerate() {
+                let value: Option<String> = row.get(3 + index)?;
+                if let Some(value) = value {
+                    person.set_attribute(key, &value)?;
+                }
+            }
+            let deleted_at: Option<u64> = row.get(3 + Person::ATTRIBUTE_KEYS.len())?;
+            match deleted_at {
+                Some(seconds) => {
+                    person_list.add_deleted_person(person, id, Timestamp::new(seconds))?
+                }
+                None => person_list.add_person(person, Some(id))?,
+            }
+        }
+        if let Some(max_id) = read_max_id(&connection, "persons")? {
+            person_list.reserve_ids(PersonId::new(max_id));
+        }
+        return Ok(());
+    }
+
+    // 全ての行を削除してから書き込む
+    // 途中で失敗した場合は元のデータのままとなる
+    fn overwrite_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>> {
+        let ids: Vec<PersonId> = person_list.into_iter().map(|(id, _name)| id).collect();
+        let mut persons: Vec<(PersonId, &Person, Option<Timestamp>)> = Vec::new();
+        for id in ids {
+            persons.push((id, person_list.person(id)?, None));
+        }
+        for (id, person, deleted_at) in person_list.deleted_persons() {
+            persons.push((id, person, Some(deleted_at)));
+        }
+
+        let mut connection = open(&self.dir_path)?;
+        let transaction = connection.transaction()?;
+        transaction.execute("DELETE FROM persons", [])?;
+        let sql = format!(
+            "INSERT INTO persons (id, last_name, first_name, {}, deleted_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            Self::ATTRIBUTE_COLUMNS.join(", ")
+        );
+        {
+            let mut statement = transaction.prepare(&sql)?;
+            for (id, person, deleted_at) in persons {
+                let person_name = person.person_name();
+                let attributes = person.attributes();
+                let attribute = |key: &str| {
+                    return attributes
+                        .iter()
+                        .find(|(attribute_key, _value)| *attribute_key == key)
+                        .map(|(_key, value)| value.clone());
+                };
+                let id = id.as_u64();
+                statement.execute(params![
+                    id,
+                    person_name.last_name(),
+                    person_name.first_name(),
+                    attribute(Person::ATTRIBUTE_KEYS[0]),
+                    attribute(Person::ATTRIBUTE_KEYS[1]),
+                    attribute(Person::ATTRIBUTE_KEYS[2]),
+                    attribute(Person::ATTRIBUTE_KEYS[3]),
+                    attribute(Person::ATTRIBUTE_KEYS[4]),
+                    attribute(Person::ATTRIBUTE_KEYS[5]),
+                    deleted_at.map(|deleted_at| deleted_at.seconds()),
+                ])?;
+            }
+        }
+        let max_id = person_list.max_id().as_u64();
+        write_max_id(&transaction, "persons", max_id)?;
+        transaction.commit()?;
+        return Ok(());
+    }
+
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(Some(file_fingerprint(&file_path(&self.dir_path))?));
+    }
+}
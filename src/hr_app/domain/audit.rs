@@ -0,0 +1,32 @@
+use super::timestamp::Timestamp;
+
+// 監査ログに記録する１件の操作を格納する
+// actionは操作の種類(コマンド名)、detailは操作対象の説明
+pub struct AuditEvent {
+    timestamp: Timestamp,
+    action: String,
+    detail: String,
+}
+
+impl AuditEvent {
+    // 現在時刻で操作を記録する
+    pub fn new(action: &str, detail: &str) -> AuditEvent {
+        return AuditEvent {
+            timestamp: Timestamp::now(),
+            action: String::from(action),
+            detail: String::from(detail),
+        };
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        return self.timestamp;
+    }
+
+    pub fn action(&self) -> &str {
+        return &self.action;
+    }
+
+    pub fn detail(&self) -> &str {
+        return &self.detail;
+    }
+}
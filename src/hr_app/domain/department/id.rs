@@ -1,7 +1,10 @@
 use super::super::id_type::Id;
+use std::fmt;
 
 // Department用のIDを格納する
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct DepartmentId {
     value: Id,
 }
@@ -26,7 +29,13 @@ impl DepartmentId {
         return self.value.greater_than(another_id.value());
     }
 
-    pub fn to_string(&self) -> String {
-        return self.value.to_string();
+    pub fn as_u64(&self) -> u64 {
+        return self.value.as_u64();
+    }
+}
+
+impl fmt::Display for DepartmentId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.value);
     }
 }
@@ -1,16 +1,22 @@
+use super::super::error::DomainError;
+use super::super::search::{self, MatchLevel};
+use super::super::timestamp::Timestamp;
 use super::id::DepartmentId;
 use super::*;
 use std::collections::HashMap;
+use std::error::Error;
 
 // Departmentの一覧を格納する
 // 一覧はDepartmentIdをキー、Option<Department>を値に持つHashMapとする
 // max_idはHashMap内にある最も値の大きいIDを保持する
 // max_idは新規にDepartmentを追加する際のIDの割り振りなどに使用する
 // iter_idはIterator実装のためのメンバー変数
+// 削除されたDepartmentは削除日時と共にdeleted_listに移し、復元や完全削除(purge)に備える
 pub struct DepartmentList {
     max_id: DepartmentId,
     iter_id: DepartmentId,
     department_list: HashMap<DepartmentId, Option<Department>>,
+    deleted_list: HashMap<DepartmentId, (Department, Timestamp)>,
 }
 
 impl DepartmentList {
@@ -24,9 +30,22 @@ impl DepartmentList {
             max_id: max_id,
             iter_id: iter_id,
             department_list: list,
+            deleted_list: HashMap::new(),
         };
     }
 
+    // 削除済でない部署(exceptを除く)に同じ名前の部署があるかどうかを返す
+    // into_iterは途中で抜けると次の呼び出しが途中のIDから始まってしまうので、ここでは使わない
+    fn has_name(&self, name: &str, except: Option<DepartmentId>) -> bool {
+        return self
+            .department_list
+            .iter()
+            .any(|(id, department)| match department {
+                Some(department) => Some(*id) != except && department.name() == name,
+                None => false,
+            });
+    }
+
     // Departmentを渡してリストに追加する
     // 必要であればIDを指定することが出来る(プログラム起動時のファイル読み込みで使用)
     // 同名の部署は複数存在できない
@@ -36,24 +55,22 @@ impl DepartmentList {
         &mut self,
         department: Department,
         id: Option<DepartmentId>,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), DomainError> {
         let new_department_name = department.name();
-        for (_id, old_department_name) in self.into_iter() {
-            if new_department_name == old_department_name {
-                let message = "DepartmetList::add_department : The same name already exists!";
-                return Err(message);
-            }
+        if self.has_name(&new_department_name, None) {
+            let error = DomainError::conflict(
+                "DepartmetList::add_department : The same name already exists!",
+            );
+            return Err(error);
         }
 
         match id {
             Some(id) => {
-                match self.department_list.get(&id) {
-                    Some(_) => {
-                        let message =
-                            "DepartmentList::add_department : The same id has already existed";
-                        return Err(message.into());
-                    }
-                    None => (),
+                if self.department_list.contains_key(&id) {
+                    let error = DomainError::conflict(
+                        "DepartmentList::add_department : The same id has already existed",
+                    );
+                    return Err(error);
                 }
                 self.department_list.insert(id, Some(department));
                 if id.greater_than(self.max_id) {
@@ -70,44 +87,261 @@ impl DepartmentList {
         return Ok(());
     }
 
+    // 削除済のDepartmentを削除日時と共にリストに追加する(プログラム起動時のファイル読み込みで使用)
+    // 既にリストに存在しているIDと同じIDを重複して指定することは出来ない
+    pub fn add_deleted_department(
+        &mut self,
+        department: Department,
+        id: DepartmentId,
+        deleted_at: Timestamp,
+    ) -> Result<(), DomainError> {
+        if self.department_list.contains_key(&id) {
+            return Err(DomainError::conflict(
+                "DepartmentList::add_deleted_department : The same id has already existed",
+            ));
+        }
+        self.department_list.insert(id, None);
+        self.deleted_list.insert(id, (department, deleted_at));
+        if id.greater_than(self.max_id) {
+            self.max_id = id;
+        }
+        return Ok(());
+    }
+
+    // 次にadd_departmentでIDを指定せずに追加した時に割り振られるIDを返す
+    pub fn next_id(&self) -> DepartmentId {
+        return self.max_id.next_id();
+    }
+
+    // これまでに割り振った最も大きいIDを返す
+    // 完全削除されたIDも含むので、保存先はこの値を保存してIDの再利用を防ぐ
+    pub fn max_id(&self) -> DepartmentId {
+        return self.max_id;
+    }
+
+    // 保存先から読み込んだmax_idを反映する(プログラム起動時のファイル読み込みで使用)
+    // 現在のmax_idより小さい値は無視する
+    pub fn reserve_ids(&mut self, max_id: DepartmentId) {
+        if max_id.greater_than(self.max_id) {
+            self.max_id = max_id;
+        }
+    }
+
+    // IDを指定してDepartmentをリストから取り除き、そのDepartmentを返す
+    // delete_departmentと異なりIDのキーも取り除く(作成の取り消しに使用する)
+    // 削除済のIDを指定するとエラーを返す
+    pub fn take_department(
+        &mut self,
+        department_id: DepartmentId,
+    ) -> Result<Department, DomainError> {
+        let _department = self.department(department_id)?;
+        match self.department_list.remove(&department_id) {
+            Some(Some(department)) => return Ok(department),
+            _ => {
+                return Err(DomainError::not_found(
+                    "DepartmentList::take_department : This id is empty!",
+                ))
+            }
+        }
+    }
+
     // IDを指定してDepartmentを削除する
     // HashMapのキーとなるIDは残し、値であるOption<Department>をNoneに変更する
-    pub fn delete_department(&mut self, department_id: DepartmentId) -> Result<(), &'static str> {
+    // 削除したDepartmentは現在時刻と共にdeleted_listに移す
+    pub fn delete_department(&mut self, department_id: DepartmentId) -> Result<(), DomainError> {
         match self.department_list.get(&department_id) {
             Some(option) => match option {
                 Some(_) => {
-                    self.department_list.insert(department_id, None);
+                    if let Some(Some(department)) = self.department_list.insert(department_id, None)
+                    {
+                        self.deleted_list
+                            .insert(department_id, (department, Timestamp::now()));
+                    }
                     return Ok(());
                 }
                 None => {
-                    return Err("DepartmentList::delete_department : This department has already been deleted!");
+                    return Err(DomainError::not_found("DepartmentList::delete_department : This department has already been deleted!"));
                 }
             },
             None => {
-                return Err("DepartmentList::delete_department : Received id is empty!");
+                return Err(DomainError::not_found(
+                    "DepartmentList::delete_department : Received id is empty!",
+                ));
+            }
+        }
+    }
+
+    // 削除済のDepartmentを元のIDのまま復元する
+    // 削除中に同名の部署が作られていた場合は復元できない
+    pub fn restore_department(&mut self, department_id: DepartmentId) -> Result<(), DomainError> {
+        let name = match self.deleted_list.get(&department_id) {
+            Some((department, _deleted_at)) => department.name(),
+            None => {
+                return Err(DomainError::not_found("DepartmentList::restore_department : This department is not in the deleted list!"));
             }
+        };
+        if self.has_name(&name, None) {
+            return Err(DomainError::conflict(
+                "DepartmentList::restore_department : The same name already exists!",
+            ));
+        }
+        if let Some((department, _deleted_at)) = self.deleted_list.remove(&department_id) {
+            self.department_list.insert(department_id, Some(department));
         }
+        return Ok(());
+    }
+
+    // 削除済のDepartmentのIDと参照、削除日時をIDの小さい順に返す
+    pub fn deleted_departments(&self) -> Vec<(DepartmentId, &Department, Timestamp)> {
+        let mut result: Vec<(DepartmentId, &Department, Timestamp)> = self
+            .deleted_list
+            .iter()
+            .map(|(id, (department, deleted_at))| (*id, department, *deleted_at))
+            .collect();
+        result.sort_by_key(|(id, _department, _deleted_at)| *id);
+        return result;
+    }
+
+    // 指定した日時以前に削除されたDepartmentを完全に削除し、そのIDを返す
+    // IDの再利用を防ぐためHashMapのキーは残す
+    // 再読み込み後も再利用しないよう、保存先はmax_idも保存する
+    pub fn purge_deleted_departments(&mut self, before: Timestamp) -> Vec<DepartmentId> {
+        let mut purged: Vec<DepartmentId> = self
+            .deleted_list
+            .iter()
+            .filter(|(_id, (_department, deleted_at))| *deleted_at <= before)
+            .map(|(id, _value)| *id)
+            .collect();
+        purged.sort();
+        for id in purged.iter() {
+            self.deleted_list.remove(id);
+        }
+        return purged;
+    }
+
+    // IDがリストに存在するかどうかを返す
+    // 削除済(完全削除されていないもの)のDepartmentも含む
+    pub fn contains(&self, department_id: DepartmentId) -> bool {
+        return self.department(department_id).is_ok()
+            || self.deleted_list.contains_key(&department_id);
     }
 
     // IDを指定してDepartmentの参照を取得する
     // 存在しないIDや削除済のIDを指定するとエラーを返す
-    pub fn department(&self, department_id: DepartmentId) -> Result<&Department, &'static str> {
+    pub fn department(&self, department_id: DepartmentId) -> Result<&Department, DomainError> {
         let department = match self.department_list.get(&department_id) {
-            Some(option) => match option {
-                Some(department) => department,
-                None => {
-                    let message =
-                        "DepartmentList::department : This department has already been deleted!";
-                    return Err(message);
+            Some(option) => {
+                match option {
+                    Some(department) => department,
+                    None => {
+                        let error = DomainError::not_found("DepartmentList::department : This department has already been deleted!");
+                        return Err(error);
+                    }
                 }
-            },
+            }
             None => {
-                let message = "DepartmentLsit:department : This id is empty!";
-                return Err(message);
+                let error =
+                    DomainError::not_found("DepartmentList::department : This id is empty!");
+                return Err(error);
             }
         };
         return Ok(department);
     }
+
+    // IDを指定してDepartmentの名前を変更する
+    // 他の部署と同じ名前に変更することは出来ない
+    // 存在しないIDや削除済のIDを指定するとエラーを返す
+    pub fn rename_department(
+        &mut self,
+        department_id: DepartmentId,
+        name: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let _department = self.department(department_id)?;
+        if self.has_name(name, Some(department_id)) {
+            let error = DomainError::conflict(
+                "DepartmentList::rename_department : The same name already exists!",
+            );
+            return Err(error.into());
+        }
+
+        match self.department_list.get_mut(&department_id) {
+            Some(Some(department)) => department.rename(name)?,
+            _ => {
+                return Err(DomainError::not_found(
+                    "DepartmentList::rename_department : This id is empty!",
+                )
+                .into())
+            }
+        }
+        return Ok(());
+    }
+
+    // 名前で検索して一致したDepartmentのIDと一致度を返す
+    // 大文字と小文字、かなとローマ字の違いは無視する
+    // 一致度の高い順、同じ場合はIDの小さい順に並べる
+    pub fn find_departments(&self, query: &str) -> Vec<(DepartmentId, MatchLevel)> {
+        let mut result: Vec<(DepartmentId, MatchLevel)> = Vec::new();
+        for (id, option) in self.department_list.iter() {
+            if let Some(department) = option {
+                if let Some(level) = search::match_level(query, &department.name()) {
+                    result.push((*id, level));
+                }
+            }
+        }
+        result.sort_by_key(|(id, level)| (*level, *id));
+        return result;
+    }
+
+    // 名前が完全に一致する(削除されていない)DepartmentのIDを返す
+    // 同名の部署は存在しないので、一致するのは多くても１つ
+    pub fn find_by_name(&self, name: &str) -> Option<DepartmentId> {
+        for (id, option) in self.department_list.iter() {
+            if let Some(department) = option {
+                if department.name() == name {
+                    return Some(*id);
+                }
+            }
+        }
+        return None;
+    }
+
+    // コマンドで指定されたIDまたは名前からDepartmentIdを特定する
+    // 数値の場合はIDとして扱う(存在の確認は行わない)
+    // 名前の場合は完全一致する部署のIDを返す
+    pub fn resolve_department(&self, key: &str) -> Result<DepartmentId, Box<dyn Error>> {
+        if let Ok(id) = key.parse::<u64>() {
+            return Ok(DepartmentId::new(id));
+        }
+        let ids: Vec<DepartmentId> = self
+            .find_departments(key)
+            .into_iter()
+            .filter(|(_id, level)| *level == MatchLevel::Exact)
+            .map(|(id, _level)| id)
+            .collect();
+        match ids.len() {
+            1 => return Ok(ids[0]),
+            0 => {
+                return Err(DomainError::not_found(
+                    "DepartmentList::resolve_department : No department has this name!",
+                )
+                .into())
+            }
+            _ => {
+                let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+                let message = format!(
+                    "DepartmentList::resolve_department : Several departments match this name! (ids: {})",
+                    ids.join(", ")
+                );
+                return Err(DomainError::invalid(&message).into());
+            }
+        }
+    }
+}
+
+impl Default for DepartmentList {
+    fn default() -> DepartmentList {
+        return DepartmentList::new();
+    }
 }
 
 // IDの小さい順にDepartmentIdと対応するDepartmentの名前を文字列として返す
@@ -121,11 +355,8 @@ impl Iterator for &mut DepartmentList {
                 self.iter_id = DepartmentId::new(0);
                 return None;
             }
-            match self.department(self.iter_id) {
-                Ok(department) => {
-                    return Some((self.iter_id, department.name()));
-                }
-                Err(_) => (),
+            if let Ok(department) = self.department(self.iter_id) {
+                return Some((self.iter_id, department.name()));
             }
         }
     }
@@ -149,6 +380,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn find_by_name_ok() {
+        let mut department_list = DepartmentList::new();
+        department_list
+            .add_department(Department::new("Defence").unwrap(), None)
+            .unwrap();
+        assert_eq!(
+            department_list.find_by_name("Defence"),
+            Some(DepartmentId::new(1))
+        );
+        assert_eq!(department_list.find_by_name("defence"), None);
+    }
+
     #[test]
     fn add_department_no_id_ok() {
         let mut department_list = DepartmentList::new();
@@ -197,6 +441,20 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn iterate_after_same_name_err_ok() {
+        let mut department_list = DepartmentList::new();
+        for s in ["1", "2", "3"] {
+            department_list
+                .add_department(generate_a_department(s), None)
+                .unwrap();
+        }
+        assert!(department_list
+            .add_department(generate_a_department("1"), None)
+            .is_err());
+        assert_eq!(department_list.into_iter().count(), 3);
+    }
+
     #[test]
     fn delete_department_ok() {
         let mut department_list = DepartmentList::new();
@@ -258,6 +516,183 @@ mod tests {
         assert!(department_list.delete_department(id).is_err());
     }
 
+    #[test]
+    fn rename_department_ok() {
+        let mut department_list = DepartmentList::new();
+        let department = generate_a_department("1");
+        let id = DepartmentId::new(1);
+        department_list
+            .add_department(department, Some(id))
+            .unwrap();
+        assert!(department_list.rename_department(id, "Sales").is_ok());
+        assert_eq!(
+            department_list.department(id).unwrap().name(),
+            String::from("Sales")
+        );
+    }
+
+    #[test]
+    fn rename_department_same_name_err() {
+        let mut department_list = DepartmentList::new();
+        let department1 = generate_a_department("1");
+        let department2 = generate_a_department("2");
+        department_list.add_department(department1, None).unwrap();
+        department_list.add_department(department2, None).unwrap();
+        assert!(department_list
+            .rename_department(DepartmentId::new(2), "Sample1")
+            .is_err());
+    }
+
+    #[test]
+    fn rename_department_to_own_name_ok() {
+        let mut department_list = DepartmentList::new();
+        let department = generate_a_department("1");
+        department_list.add_department(department, None).unwrap();
+        assert!(department_list
+            .rename_department(DepartmentId::new(1), "Sample1")
+            .is_ok());
+    }
+
+    #[test]
+    fn rename_department_after_same_name_err_ok() {
+        let mut department_list = DepartmentList::new();
+        for s in ["1", "2", "3"] {
+            department_list
+                .add_department(generate_a_department(s), None)
+                .unwrap();
+        }
+        assert!(department_list
+            .rename_department(DepartmentId::new(3), "Sample2")
+            .is_err());
+        // 直前の重複確認が途中で終わっていても、全ての部署と比較する
+        assert!(department_list
+            .rename_department(DepartmentId::new(2), "Sample1")
+            .is_err());
+        assert_eq!(department_list.into_iter().count(), 3);
+    }
+
+    #[test]
+    fn find_departments_ok() {
+        let mut department_list = DepartmentList::new();
+        department_list
+            .add_department(Department::new("Defence").unwrap(), None)
+            .unwrap();
+        department_list
+            .add_department(Department::new("営業部").unwrap(), None)
+            .unwrap();
+        department_list
+            .add_department(Department::new("Games").unwrap(), None)
+            .unwrap();
+
+        let ids: Vec<DepartmentId> = department_list
+            .find_departments("e")
+            .into_iter()
+            .map(|(id, _level)| id)
+            .collect();
+        assert_eq!(ids, vec![DepartmentId::new(1), DepartmentId::new(3)]);
+        assert_eq!(department_list.find_departments("営業").len(), 1);
+    }
+
+    #[test]
+    fn resolve_department_ok() {
+        let mut department_list = DepartmentList::new();
+        department_list
+            .add_department(Department::new("Defence").unwrap(), None)
+            .unwrap();
+        assert_eq!(
+            department_list.resolve_department("defence").unwrap(),
+            DepartmentId::new(1)
+        );
+        assert_eq!(
+            department_list.resolve_department("7").unwrap(),
+            DepartmentId::new(7)
+        );
+        assert!(department_list.resolve_department("Games").is_err());
+    }
+
+    #[test]
+    fn restore_department_ok() {
+        let mut department_list = DepartmentList::new();
+        let id = DepartmentId::new(1);
+        department_list
+            .add_department(generate_a_department("1"), Some(id))
+            .unwrap();
+        department_list.delete_department(id).unwrap();
+        assert_eq!(department_list.deleted_departments().len(), 1);
+        assert!(department_list.restore_department(id).is_ok());
+        assert!(department_list.department(id).is_ok());
+    }
+
+    #[test]
+    fn restore_department_same_name_err() {
+        let mut department_list = DepartmentList::new();
+        let id = DepartmentId::new(1);
+        department_list
+            .add_department(generate_a_department("1"), Some(id))
+            .unwrap();
+        department_list.delete_department(id).unwrap();
+        department_list
+            .add_department(generate_a_department("1"), None)
+            .unwrap();
+        assert!(department_list.restore_department(id).is_err());
+    }
+
+    #[test]
+    fn restore_department_after_same_name_err_ok() {
+        let mut department_list = DepartmentList::new();
+        for s in ["1", "2", "3"] {
+            department_list
+                .add_department(generate_a_department(s), None)
+                .unwrap();
+        }
+        department_list
+            .delete_department(DepartmentId::new(1))
+            .unwrap();
+        department_list
+            .add_department(generate_a_department("1"), None)
+            .unwrap();
+        assert!(department_list
+            .restore_department(DepartmentId::new(1))
+            .is_err());
+        assert_eq!(department_list.into_iter().count(), 3);
+    }
+
+    #[test]
+    fn purge_deleted_departments_ok() {
+        let mut department_list = DepartmentList::new();
+        department_list
+            .add_deleted_department(
+                generate_a_department("1"),
+                DepartmentId::new(1),
+                Timestamp::new(100),
+            )
+            .unwrap();
+        department_list
+            .add_deleted_department(
+                generate_a_department("2"),
+                DepartmentId::new(2),
+                Timestamp::new(300),
+            )
+            .unwrap();
+
+        let purged = department_list.purge_deleted_departments(Timestamp::new(200));
+        assert_eq!(purged, vec![DepartmentId::new(1)]);
+        assert!(!department_list.contains(DepartmentId::new(1)));
+        assert!(department_list.contains(DepartmentId::new(2)));
+    }
+
+    #[test]
+    fn take_department_ok() {
+        let mut department_list = DepartmentList::new();
+        let id = department_list.next_id();
+        department_list
+            .add_department(generate_a_department("1"), None)
+            .unwrap();
+        let department = department_list.take_department(id).unwrap();
+        assert!(!department_list.contains(id));
+        assert!(department_list.add_department(department, Some(id)).is_ok());
+    }
+
     #[test]
     fn iterator_ok_1() {
         let mut department_list = DepartmentList::new();
@@ -7,6 +7,12 @@ use std::error::Error;
 
 // 部署に関する情報を格納する
 // 情報として持っているのは名前のみ
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "DepartmentRecord", into = "DepartmentRecord")
+)]
 pub struct Department {
     name: DepartmentName,
 }
@@ -20,6 +26,40 @@ impl Department {
     pub fn name(&self) -> String {
         return self.name.value();
     }
+
+    // 名前を変更する
+    // 新しい名前が不正な場合は元の名前のままエラーを返す
+    pub fn rename(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        let name = DepartmentName::new(name)?;
+        self.name = name;
+        return Ok(());
+    }
+}
+
+// serde用の中間表現
+// 読み込み時はDepartment::newを通すため、不正な名前はエラーになる
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DepartmentRecord {
+    name: String,
+}
+
+#[cfg(feature = "serde")]
+impl From<Department> for DepartmentRecord {
+    fn from(department: Department) -> DepartmentRecord {
+        return DepartmentRecord {
+            name: department.name(),
+        };
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DepartmentRecord> for Department {
+    type Error = Box<dyn Error>;
+
+    fn try_from(record: DepartmentRecord) -> Result<Department, Box<dyn Error>> {
+        return Department::new(&record.name);
+    }
 }
 
 #[cfg(test)]
@@ -31,4 +71,22 @@ mod tests {
         let department = Department::new("Sales").unwrap();
         assert_eq!(department.name(), String::from("Sales"));
     }
+
+    #[test]
+    fn rename_ok() {
+        let mut department = Department::new("Sales").unwrap();
+        assert!(department.rename("Marketing").is_ok());
+        assert_eq!(department.name(), String::from("Marketing"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_ok() {
+        let department = Department::new("Sales").unwrap();
+        let json = serde_json::to_string(&department).unwrap();
+        assert_eq!(json, r#"{"name":"Sales"}"#);
+        let restored: Department = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.name(), "Sales");
+        assert!(serde_json::from_str::<Department>(r#"{"name":""}"#).is_err());
+    }
 }
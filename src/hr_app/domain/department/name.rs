@@ -4,6 +4,7 @@ use std::error::Error;
 // Departmentの名前を格納する
 // フィールドは１つ
 // 最大長と最短長は固定値
+#[derive(Clone, Debug)]
 pub struct DepartmentName {
     name: NameType,
 }
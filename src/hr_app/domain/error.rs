@@ -0,0 +1,56 @@
+use std::error::Error;
+use std::fmt;
+
+// ドメインのエラーの種類
+// 呼び出し側(REST APIのステータスコードなど)はメッセージの文言ではなく種類でエラーを区別する
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ErrorKind {
+    // 指定したIDの個人、部署、所属が存在しない(削除済を含む)
+    NotFound,
+    // 名前、社員番号、ID、所属が既存のものと重複する
+    Conflict,
+    // 値が正しくない
+    Invalid,
+    // 読み取り専用のセッションで変更しようとした
+    ReadOnly,
+}
+
+// 種類と "Type::method : message" 形式のメッセージを持つエラー
+#[derive(PartialEq, Clone, Debug)]
+pub struct DomainError {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl DomainError {
+    pub fn new(kind: ErrorKind, message: &str) -> DomainError {
+        return DomainError {
+            kind: kind,
+            message: String::from(message),
+        };
+    }
+
+    pub fn not_found(message: &str) -> DomainError {
+        return DomainError::new(ErrorKind::NotFound, message);
+    }
+
+    pub fn conflict(message: &str) -> DomainError {
+        return DomainError::new(ErrorKind::Conflict, message);
+    }
+
+    pub fn invalid(message: &str) -> DomainError {
+        return DomainError::new(ErrorKind::Invalid, message);
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        return self.kind;
+    }
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.message);
+    }
+}
+
+impl Error for DomainError {}
@@ -1,6 +1,10 @@
+use std::fmt;
+
 // u64の整数値をラップする
 // HashMapのキーとして使用する
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Id {
     value: u64,
 }
@@ -29,9 +33,16 @@ impl Id {
         return false;
     }
 
-    // 画面出力用に文字列を返す
-    pub fn to_string(&self) -> String {
-        return self.value.to_string();
+    // 保存先やエクスポートで数値として書き出す時に使用する
+    pub fn as_u64(&self) -> u64 {
+        return self.value;
+    }
+}
+
+// 画面出力用の文字列
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.value);
     }
 }
 
@@ -50,21 +61,21 @@ mod tests {
     fn greater_than_true() {
         let small = Id::new(1);
         let large = Id::new(10);
-        assert_eq!(large.greater_than(small), true);
+        assert!(large.greater_than(small));
     }
 
     #[test]
     fn greater_than_false() {
         let small = Id::new(1);
         let large = Id::new(10);
-        assert_eq!(small.greater_than(large), false);
+        assert!(!small.greater_than(large));
     }
 
     #[test]
     fn greater_than_eaual_false() {
         let id1 = Id::new(5);
         let id2 = Id::new(5);
-        assert_eq!(id1.greater_than(id2), false);
+        assert!(!id1.greater_than(id2));
     }
 
     #[test]
@@ -73,4 +84,9 @@ mod tests {
         let id = Id::new(100);
         assert_eq!(id.to_string(), ans);
     }
+
+    #[test]
+    fn as_u64_ok() {
+        assert_eq!(Id::new(100).as_u64(), 100);
+    }
 }
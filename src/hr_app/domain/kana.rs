@@ -0,0 +1,170 @@
+// かな文字の正規化を行う
+// 読み仮名による並べ替えや名前の検索で表記の揺れを吸収するために使用する
+
+const KATAKANA_START: u32 = 0x30A1;
+const KATAKANA_END: u32 = 0x30F6;
+const KANA_OFFSET: u32 = 0x60;
+
+// カタカナをひらがなに変換する
+// カタカナ以外の文字はそのまま残す
+pub fn to_hiragana(text: &str) -> String {
+    let mut result = String::new();
+    for c in text.chars() {
+        let code = c as u32;
+        if (KATAKANA_START..=KATAKANA_END).contains(&code) {
+            match char::from_u32(code - KANA_OFFSET) {
+                Some(hiragana) => result.push(hiragana),
+                None => result.push(c),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    return result;
+}
+
+// ひらがな１文字に対応するヘボン式ローマ字
+// 小書き文字(ゃゅょっ)と長音符は別途処理する
+#[rustfmt::skip]
+const ROMAJI_TABLE: &[(char, &str)] = &[
+    ('あ', "a"), ('い', "i"), ('う', "u"), ('え', "e"), ('お', "o"),
+    ('か', "ka"), ('き', "ki"), ('く', "ku"), ('け', "ke"), ('こ', "ko"),
+    ('さ', "sa"), ('し', "shi"), ('す', "su"), ('せ', "se"), ('そ', "so"),
+    ('た', "ta"), ('ち', "chi"), ('つ', "tsu"), ('て', "te"), ('と', "to"),
+    ('な', "na"), ('に', "ni"), ('ぬ', "nu"), ('ね', "ne"), ('の', "no"),
+    ('は', "ha"), ('ひ', "hi"), ('ふ', "fu"), ('へ', "he"), ('ほ', "ho"),
+    ('ま', "ma"), ('み', "mi"), ('む', "mu"), ('め', "me"), ('も', "mo"),
+    ('や', "ya"), ('ゆ', "yu"), ('よ', "yo"),
+    ('ら', "ra"), ('り', "ri"), ('る', "ru"), ('れ', "re"), ('ろ', "ro"),
+    ('わ', "wa"), ('ゐ', "i"), ('ゑ', "e"), ('を', "o"), ('ん', "n"),
+    ('が', "ga"), ('ぎ', "gi"), ('ぐ', "gu"), ('げ', "ge"), ('ご', "go"),
+    ('ざ', "za"), ('じ', "ji"), ('ず', "zu"), ('ぜ', "ze"), ('ぞ', "zo"),
+    ('だ', "da"), ('ぢ', "ji"), ('づ', "zu"), ('で', "de"), ('ど', "do"),
+    ('ば', "ba"), ('び', "bi"), ('ぶ', "bu"), ('べ', "be"), ('ぼ', "bo"),
+    ('ぱ', "pa"), ('ぴ', "pi"), ('ぷ', "pu"), ('ぺ', "pe"), ('ぽ', "po"),
+    ('ぁ', "a"), ('ぃ', "i"), ('ぅ', "u"), ('ぇ', "e"), ('ぉ', "o"),
+    ('ゔ', "vu"), ('ゎ', "wa"),
+];
+
+fn romaji_of(c: char) -> Option<&'static str> {
+    for (kana, romaji) in ROMAJI_TABLE.iter() {
+        if *kana == c {
+            return Some(romaji);
+        }
+    }
+    return None;
+}
+
+// かな(ひらがな、カタカナ)をヘボン式ローマ字に変換する
+// 拗音(きゃ等)、促音(っ)、長音符(ー)にも対応する
+// かな以外の文字はそのまま残す
+pub fn to_romaji(text: &str) -> String {
+    let chars: Vec<char> = to_hiragana(text).chars().collect();
+    let mut result = String::new();
+    let mut double_next = false;
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let mut romaji = match (c, romaji_of(c)) {
+            ('っ', _) => {
+                double_next = true;
+                index += 1;
+                continue;
+            }
+            ('ー', _) => match result.chars().last() {
+                Some(vowel) if "aiueo".contains(vowel) => vowel.to_string(),
+                _ => String::new(),
+            },
+            (_, Some(romaji)) => String::from(romaji),
+            (_, None) => c.to_string(),
+        };
+
+        // 拗音: 直前の音の "i" を取り除いて "ya", "yu", "yo" と組み合わせる
+        let small_vowel = match chars.get(index + 1) {
+            Some('ゃ') => Some("a"),
+            Some('ゅ') => Some("u"),
+            Some('ょ') => Some("o"),
+            _ => None,
+        };
+        if let Some(vowel) = small_vowel {
+            if romaji.len() > 1 && romaji.ends_with('i') {
+                romaji.pop();
+                if !(romaji.ends_with("sh") || romaji.ends_with("ch") || romaji.ends_with('j')) {
+                    romaji.push('y');
+                }
+                romaji.push_str(vowel);
+                index += 1;
+            }
+        }
+
+        if double_next {
+            if let Some(consonant) = romaji.chars().next() {
+                if consonant.is_ascii_alphabetic() && !"aiueon".contains(consonant) {
+                    // "ch" の促音は "tch" と表記する
+                    result.push(if romaji.starts_with("ch") {
+                        't'
+                    } else {
+                        consonant
+                    });
+                }
+            }
+            double_next = false;
+        }
+        result.push_str(&romaji);
+        index += 1;
+    }
+    return result;
+}
+
+// 検索用に文字列を正規化する
+// 英字は小文字に、かなはローマ字に揃えるので、"シモミチ", "しもみち", "Shimomichi" は同じ文字列になる
+pub fn normalize(text: &str) -> String {
+    return to_romaji(&text.to_lowercase());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_hiragana_katakana_ok() {
+        assert_eq!(to_hiragana("ポッター"), String::from("ぽったー"));
+    }
+
+    #[test]
+    fn to_hiragana_mixed_ok() {
+        assert_eq!(
+            to_hiragana("Harry ハリー はりー"),
+            String::from("Harry はりー はりー")
+        );
+    }
+
+    #[test]
+    fn to_romaji_basic_ok() {
+        assert_eq!(to_romaji("しもみち"), String::from("shimomichi"));
+        assert_eq!(to_romaji("ユウタ"), String::from("yuuta"));
+    }
+
+    #[test]
+    fn to_romaji_youon_ok() {
+        assert_eq!(to_romaji("きょうこ"), String::from("kyouko"));
+        assert_eq!(to_romaji("しゅんすけ"), String::from("shunsuke"));
+        assert_eq!(to_romaji("じゅん"), String::from("jun"));
+    }
+
+    #[test]
+    fn to_romaji_sokuon_ok() {
+        assert_eq!(to_romaji("はっとり"), String::from("hattori"));
+        assert_eq!(to_romaji("まっちゃ"), String::from("matcha"));
+    }
+
+    #[test]
+    fn to_romaji_long_vowel_ok() {
+        assert_eq!(to_romaji("ハリー"), String::from("harii"));
+    }
+
+    #[test]
+    fn normalize_ok() {
+        assert_eq!(normalize("シモミチ"), normalize("Shimomichi"));
+    }
+}
@@ -1,19 +1,26 @@
 use super::department::id::DepartmentId;
 use super::department::list::DepartmentList;
 use super::department::*;
+use super::error::DomainError;
 use super::person::id::PersonId;
 use super::person::list::PersonList;
 use super::person::*;
 use std::collections::HashMap;
 
-// HashMapの値を埋めるために定義したが、プログラム内で使用しない
+// 所属の状態
+// Suspendedは個人または部署が削除(論理削除)されたために一時的に無効となった所属
+// 削除された個人や部署が復元されるとValidに戻る
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 enum MemberStatus {
     Valid,
+    Suspended,
 }
 
 // 個人と部署の紐付けの一覧を格納する
 //　DepartmentIdをキーとしたHashMap
-// 上記HashMapの値はPersonIdをキーとしたHashMapとなっている。このHashMapの値は使用しない
+// 上記HashMapの値はPersonIdをキーとしたHashMapとなっている。このHashMapの値は所属の状態を表す
 // 部署Aに個人Sが所属している時、キーAに対応するHashMapにキーSが存在する
 // 部署Aに所属する個人一覧はO(1)で取り出せる
 // 部署Aに個人Sが所属しているかどうかもO(1)で取り出せる
@@ -49,19 +56,19 @@ impl MemberList {
         department_id: DepartmentId,
         person_list: &PersonList,
         department_list: &DepartmentList,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), DomainError> {
         let _person = person_list.person(person_id)?;
         let _department = department_list.department(department_id)?;
 
-        let member_map_of_the_department = self.list.entry(department_id).or_insert(HashMap::new());
+        let member_map_of_the_department = self.list.entry(department_id).or_default();
         if department_id.greater_than(self.max_id) {
             self.max_id = department_id;
         }
 
         match member_map_of_the_department.get(&person_id) {
             Some(_) => {
-                let message = "MemberList::add_person_to_department : This person has already belonged to the department!";
-                return Err(message);
+                let error = DomainError::conflict("MemberList::add_person_to_department : This person has already belonged to the department!");
+                return Err(error);
             }
             None => {
                 member_map_of_the_department.insert(person_id, MemberStatus::Valid);
@@ -81,23 +88,26 @@ impl MemberList {
         department_id: DepartmentId,
         person_list: &PersonList,
         department_list: &DepartmentList,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), DomainError> {
         let _person = person_list.person(person_id)?;
         let _department = department_list.department(department_id)?;
 
         let member_map_of_the_department = match self.list.get_mut(&department_id) {
             Some(list) => list,
             None => {
-                let message = "MemberList::remove_person_from_department : DepartmentId is empty!";
-                return Err(message);
+                let error = DomainError::not_found(
+                    "MemberList::remove_person_from_department : DepartmentId is empty!",
+                );
+                return Err(error);
             }
         };
         match member_map_of_the_department.get(&person_id) {
-            Some(_) => (),
-            None => {
-                let message =
-                    "MemberList::remove_person_from_department : This person is not a member!";
-                return Err(message);
+            Some(MemberStatus::Valid) => (),
+            _ => {
+                let error = DomainError::not_found(
+                    "MemberList::remove_person_from_department : This person is not a member!",
+                );
+                return Err(error);
             }
         }
         member_map_of_the_department.remove(&person_id);
@@ -105,6 +115,100 @@ impl MemberList {
         return Ok(());
     }
 
+    // 個人の所属を別の部署へ移す
+    // 移動元への所属と移動先の両方を確認してから移すので、途中で失敗して所属が無くなることはない
+    // 所属の状態は移動元のものをそのまま引き継ぐ
+    // 一時的に無効となっている所属は、個人が削除済であっても移せる(部署の統合で使用)
+    // 移動元に所属していない個人や、移動先に既に所属している個人は移せない
+    pub fn transfer_person(
+        &mut self,
+        person_id: PersonId,
+        from_department_id: DepartmentId,
+        to_department_id: DepartmentId,
+        person_list: &PersonList,
+        department_list: &DepartmentList,
+    ) -> Result<(), DomainError> {
+        let _from_department = department_list.department(from_department_id)?;
+        let _to_department = department_list.department(to_department_id)?;
+        if from_department_id == to_department_id {
+            return Err(DomainError::invalid(
+                "MemberList::transfer_person : The source and destination are the same department!",
+            ));
+        }
+        let status = match self.list.get(&from_department_id) {
+            Some(members) => members.get(&person_id).copied(),
+            None => None,
+        };
+        match status {
+            Some(MemberStatus::Valid) => {
+                let _person = person_list.person(person_id)?;
+            }
+            Some(MemberStatus::Suspended) if person_list.contains(person_id) => (),
+            _ => {
+                return Err(DomainError::not_found(
+                    "MemberList::transfer_person : This person is not a member!",
+                ))
+            }
+        }
+        let already_member = match self.list.get(&to_department_id) {
+            Some(members) => members.contains_key(&person_id),
+            None => false,
+        };
+        if already_member {
+            return Err(DomainError::conflict(
+                "MemberList::transfer_person : This person has already belonged to the department!",
+            ));
+        }
+
+        let status = match self.list.get_mut(&from_department_id) {
+            Some(members) => members.remove(&person_id),
+            None => None,
+        };
+        let status = status.unwrap_or(MemberStatus::Valid);
+        self.list
+            .entry(to_department_id)
+            .or_default()
+            .insert(person_id, status);
+        if to_department_id.greater_than(self.max_id) {
+            self.max_id = to_department_id;
+        }
+        return Ok(());
+    }
+
+    // 個人が部署に(有効な状態で)所属しているかどうかを返す
+    pub fn is_member(&self, person_id: PersonId, department_id: DepartmentId) -> bool {
+        match self.list.get(&department_id) {
+            Some(members) => return members.get(&person_id) == Some(&MemberStatus::Valid),
+            None => return false,
+        }
+    }
+
+    // 部署に(有効な状態で)所属している個人のIDを小さい順に返す
+    pub fn member_ids(&self, department_id: DepartmentId) -> Vec<PersonId> {
+        let mut ids: Vec<PersonId> = match self.list.get(&department_id) {
+            Some(members) => members
+                .iter()
+                .filter(|(_id, status)| **status == MemberStatus::Valid)
+                .map(|(id, _status)| *id)
+                .collect(),
+            None => Vec::new(),
+        };
+        ids.sort();
+        return ids;
+    }
+
+    // 個人が(有効な状態で)所属している部署のIDを小さい順に返す
+    pub fn department_ids(&self, person_id: PersonId) -> Vec<DepartmentId> {
+        let mut ids: Vec<DepartmentId> = self
+            .list
+            .iter()
+            .filter(|(_id, members)| members.get(&person_id) == Some(&MemberStatus::Valid))
+            .map(|(id, _members)| *id)
+            .collect();
+        ids.sort();
+        return ids;
+    }
+
     // 部署を指定して、その部署に所属している個人の一覧を取得する
     // 指定された部署がMemberListに存在しない場合は空のリストが返される
     // ※HashMapのイテレータは毎回順序が変わるので、返される個人リストの順番も実行ごとに変わる
@@ -112,7 +216,7 @@ impl MemberList {
         &self,
         department_id: DepartmentId,
         person_list: &'a PersonList,
-    ) -> Result<Vec<&'a Person>, &'static str> {
+    ) -> Result<Vec<&'a Person>, DomainError> {
         let mut result_person_list: Vec<&Person> = Vec::new();
         let member_map_of_the_department = match self.list.get(&department_id) {
             Some(list) => list,
@@ -121,7 +225,10 @@ impl MemberList {
             }
         };
 
-        for (id, _status) in member_map_of_the_department.iter() {
+        for (id, status) in member_map_of_the_department.iter() {
+            if *status != MemberStatus::Valid {
+                continue;
+            }
             let person: &Person = match person_list.person(*id) {
                 Ok(person) => person,
                 Err(_) => continue,
@@ -137,16 +244,141 @@ impl MemberList {
         &self,
         person_id: PersonId,
         department_list: &'a mut DepartmentList,
-    ) -> Result<Vec<&'a Department>, &'static str> {
+    ) -> Result<Vec<&'a Department>, DomainError> {
         let mut result_department_list: Vec<&Department> = Vec::new();
         for (department_id, member_map_of_the_department) in self.list.iter() {
-            if member_map_of_the_department.contains_key(&person_id) {
+            if member_map_of_the_department.get(&person_id) == Some(&MemberStatus::Valid) {
                 let department: &Department = department_list.department(*department_id)?;
                 result_department_list.push(department);
             }
         }
         return Ok(result_department_list);
     }
+
+    // 一時的に無効となっている所属を追加する(プログラム起動時のファイル読み込みで使用)
+    // 個人と部署は削除済であってもリストに残っていれば良い
+    pub fn add_suspended_member(
+        &mut self,
+        person_id: PersonId,
+        department_id: DepartmentId,
+        person_list: &PersonList,
+        department_list: &DepartmentList,
+    ) -> Result<(), DomainError> {
+        if !person_list.contains(person_id) || !department_list.contains(department_id) {
+            return Err(DomainError::not_found(
+                "MemberList::add_suspended_member : Person or department does not exist!",
+            ));
+        }
+        let member_map_of_the_department = self.list.entry(department_id).or_default();
+        if department_id.greater_than(self.max_id) {
+            self.max_id = department_id;
+        }
+        if member_map_of_the_department.contains_key(&person_id) {
+            let error = DomainError::conflict("MemberList::add_suspended_member : This person has already belonged to the department!");
+            return Err(error);
+        }
+        member_map_of_the_department.insert(person_id, MemberStatus::Suspended);
+        return Ok(());
+    }
+
+    // 一時的に無効となっている所属を取り除く(部署の統合で使用)
+    // 有効な所属はremove_person_from_departmentで取り除く
+    pub fn remove_suspended_member(
+        &mut self,
+        person_id: PersonId,
+        department_id: DepartmentId,
+    ) -> Result<(), DomainError> {
+        let member_map_of_the_department = match self.list.get_mut(&department_id) {
+            Some(list) => list,
+            None => {
+                let error = DomainError::not_found(
+                    "MemberList::remove_suspended_member : DepartmentId is empty!",
+                );
+                return Err(error);
+            }
+        };
+        if member_map_of_the_department.get(&person_id) != Some(&MemberStatus::Suspended) {
+            let error = DomainError::not_found(
+                "MemberList::remove_suspended_member : This person is not a suspended member!",
+            );
+            return Err(error);
+        }
+        member_map_of_the_department.remove(&person_id);
+        return Ok(());
+    }
+
+    // 個人が削除された時に、その個人の所属を全て一時的に無効にする
+    pub fn suspend_person(&mut self, person_id: PersonId) {
+        for member_map_of_the_department in self.list.values_mut() {
+            if let Some(status) = member_map_of_the_department.get_mut(&person_id) {
+                *status = MemberStatus::Suspended;
+            }
+        }
+    }
+
+    // 部署が削除された時に、その部署への所属を全て一時的に無効にする
+    pub fn suspend_department(&mut self, department_id: DepartmentId) {
+        if let Some(member_map_of_the_department) = self.list.get_mut(&department_id) {
+            for status in member_map_of_the_department.values_mut() {
+                *status = MemberStatus::Suspended;
+            }
+        }
+    }
+
+    // 無効となっている所属のうち、個人と部署の両方が削除されていないものを有効に戻す
+    // 個人や部署を復元した後に呼び出す
+    // 有効に戻した所属の数を返す
+    pub fn reactivate(
+        &mut self,
+        person_list: &PersonList,
+        department_list: &DepartmentList,
+    ) -> usize {
+        let mut count = 0;
+        for (department_id, member_map_of_the_department) in self.list.iter_mut() {
+            if department_list.department(*department_id).is_err() {
+                continue;
+            }
+            for (person_id, status) in member_map_of_the_department.iter_mut() {
+                if *status == MemberStatus::Suspended && person_list.person(*person_id).is_ok() {
+                    *status = MemberStatus::Valid;
+                    count += 1;
+                }
+            }
+        }
+        return count;
+    }
+
+    // 個人が完全に削除された時に、その個人の所属を全て取り除く
+    pub fn remove_person_entirely(&mut self, person_id: PersonId) {
+        for member_map_of_the_department in self.list.values_mut() {
+            member_map_of_the_department.remove(&person_id);
+        }
+    }
+
+    // 部署が完全に削除された時に、その部署への所属を全て取り除く
+    pub fn remove_department_entirely(&mut self, department_id: DepartmentId) {
+        self.list.remove(&department_id);
+    }
+
+    // 一時的に無効となっている所属の一覧を部署ID、個人IDの小さい順に返す
+    pub fn suspended_members(&self) -> Vec<(DepartmentId, PersonId)> {
+        let mut result: Vec<(DepartmentId, PersonId)> = Vec::new();
+        for (department_id, member_map_of_the_department) in self.list.iter() {
+            for (person_id, status) in member_map_of_the_department.iter() {
+                if *status == MemberStatus::Suspended {
+                    result.push((*department_id, *person_id));
+                }
+            }
+        }
+        result.sort();
+        return result;
+    }
+}
+
+impl Default for MemberList {
+    fn default() -> MemberList {
+        return MemberList::new();
+    }
 }
 
 impl Iterator for &mut MemberList {
@@ -158,17 +390,69 @@ impl Iterator for &mut MemberList {
                 self.iter_id = DepartmentId::new(0);
                 return None;
             }
-            match self.list.get(&self.iter_id) {
-                Some(list) => {
-                    let mut person_id_list: Vec<PersonId> = Vec::new();
-                    for (id, _status) in list {
+            if let Some(list) = self.list.get(&self.iter_id) {
+                let mut person_id_list: Vec<PersonId> = Vec::new();
+                for (id, status) in list {
+                    if *status == MemberStatus::Valid {
                         person_id_list.push(*id);
                     }
-                    return Some((self.iter_id, person_id_list));
                 }
-                None => (),
+                return Some((self.iter_id, person_id_list));
+            }
+        }
+    }
+}
+
+// serde用の中間表現
+// MemberListは所属1件ごとのレコードを部署ID、個人IDの小さい順に並べた配列として出力する
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MemberRecord {
+    department_id: DepartmentId,
+    person_id: PersonId,
+    status: MemberStatus,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for MemberList {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut records: Vec<MemberRecord> = Vec::new();
+        for (department_id, member_map_of_the_department) in self.list.iter() {
+            for (person_id, status) in member_map_of_the_department.iter() {
+                records.push(MemberRecord {
+                    department_id: *department_id,
+                    person_id: *person_id,
+                    status: *status,
+                });
             }
         }
+        records.sort_by_key(|record| (record.department_id, record.person_id));
+        return serializer.collect_seq(records);
+    }
+}
+
+// 読み込み時は同じ部署に同じ個人が重複して所属しているデータをエラーにする
+// 個人や部署がリストに存在するかどうかはここでは確認できないため、
+// 利用側でPersonList、DepartmentListと突き合わせる必要がある
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MemberList {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<MemberList, D::Error> {
+        let records: Vec<MemberRecord> = serde::Deserialize::deserialize(deserializer)?;
+        let mut member_list = MemberList::new();
+        for record in records {
+            let member_map_of_the_department =
+                member_list.list.entry(record.department_id).or_default();
+            if member_map_of_the_department.contains_key(&record.person_id) {
+                let message =
+                    "MemberList::deserialize : This person has already belonged to the department!";
+                return Err(serde::de::Error::custom(message));
+            }
+            member_map_of_the_department.insert(record.person_id, record.status);
+            if record.department_id.greater_than(member_list.max_id) {
+                member_list.max_id = record.department_id;
+            }
+        }
+        return Ok(member_list);
     }
 }
 
@@ -237,6 +521,22 @@ mod tests {
             return Ok(());
         }
 
+        fn transfer_member(
+            &mut self,
+            person_id: PersonId,
+            from_department_id: DepartmentId,
+            to_department_id: DepartmentId,
+        ) -> Result<(), Box<dyn Error>> {
+            self.member_list.transfer_person(
+                person_id,
+                from_department_id,
+                to_department_id,
+                &self.person_list,
+                &self.department_list,
+            )?;
+            return Ok(());
+        }
+
         fn remove_member(
             &mut self,
             person_id: PersonId,
@@ -308,6 +608,132 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn suspend_person_and_reactivate_ok() {
+        let mut test_list = Lists::new();
+        test_list.create_person(2);
+        test_list.create_department(1);
+        test_list
+            .add_member(PersonId::new(1), DepartmentId::new(1))
+            .unwrap();
+        test_list
+            .add_member(PersonId::new(2), DepartmentId::new(1))
+            .unwrap();
+
+        test_list
+            .person_list
+            .delete_person(PersonId::new(1))
+            .unwrap();
+        test_list.member_list.suspend_person(PersonId::new(1));
+        assert_eq!(
+            test_list.member_list.suspended_members(),
+            vec![(DepartmentId::new(1), PersonId::new(1))]
+        );
+        assert!(test_list
+            .remove_member(PersonId::new(2), DepartmentId::new(1))
+            .is_ok());
+
+        test_list
+            .person_list
+            .restore_person(PersonId::new(1))
+            .unwrap();
+        let count = test_list
+            .member_list
+            .reactivate(&test_list.person_list, &test_list.department_list);
+        assert_eq!(count, 1);
+        assert!(test_list.member_list.suspended_members().is_empty());
+        assert!(test_list
+            .remove_member(PersonId::new(1), DepartmentId::new(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn reactivate_keeps_deleted_department_suspended_ok() {
+        let mut test_list = Lists::new();
+        test_list.create_person(1);
+        test_list.create_department(1);
+        test_list
+            .add_member(PersonId::new(1), DepartmentId::new(1))
+            .unwrap();
+
+        test_list
+            .person_list
+            .delete_person(PersonId::new(1))
+            .unwrap();
+        test_list.member_list.suspend_person(PersonId::new(1));
+        test_list
+            .department_list
+            .delete_department(DepartmentId::new(1))
+            .unwrap();
+        test_list
+            .member_list
+            .suspend_department(DepartmentId::new(1));
+
+        test_list
+            .person_list
+            .restore_person(PersonId::new(1))
+            .unwrap();
+        let count = test_list
+            .member_list
+            .reactivate(&test_list.person_list, &test_list.department_list);
+        assert_eq!(count, 0);
+        assert_eq!(test_list.member_list.suspended_members().len(), 1);
+    }
+
+    #[test]
+    fn department_list_by_person_skips_suspended_ok() {
+        let mut test_list = Lists::new();
+        test_list.create_person(1);
+        test_list.create_department(2);
+        test_list
+            .add_member(PersonId::new(1), DepartmentId::new(1))
+            .unwrap();
+        test_list
+            .add_member(PersonId::new(1), DepartmentId::new(2))
+            .unwrap();
+        test_list
+            .department_list
+            .delete_department(DepartmentId::new(1))
+            .unwrap();
+        test_list
+            .member_list
+            .suspend_department(DepartmentId::new(1));
+
+        let list = test_list
+            .member_list
+            .department_list_by_person(PersonId::new(1), &mut test_list.department_list)
+            .unwrap();
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn member_ids_and_department_ids_ok() {
+        let mut test_list = Lists::new();
+        test_list.create_person(3);
+        test_list.create_department(2);
+        for (person_id, department_id) in [(3, 1), (1, 1), (1, 2), (2, 2)] {
+            test_list
+                .add_member(PersonId::new(person_id), DepartmentId::new(department_id))
+                .unwrap();
+        }
+        test_list.member_list.suspend_person(PersonId::new(2));
+
+        let member_list = &test_list.member_list;
+        assert_eq!(
+            member_list.member_ids(DepartmentId::new(1)),
+            vec![PersonId::new(1), PersonId::new(3)]
+        );
+        assert_eq!(
+            member_list.member_ids(DepartmentId::new(2)),
+            vec![PersonId::new(1)]
+        );
+        assert_eq!(
+            member_list.department_ids(PersonId::new(1)),
+            vec![DepartmentId::new(1), DepartmentId::new(2)]
+        );
+        assert!(member_list.department_ids(PersonId::new(2)).is_empty());
+    }
+
     #[test]
     fn remove_person_from_department_ok() {
         let mut test_list = Lists::new();
@@ -384,4 +810,101 @@ mod tests {
             .remove_member(PersonId::new(1), DepartmentId::new(2))
             .is_err());
     }
+
+    #[test]
+    fn transfer_person_ok() {
+        let mut test_list = Lists::new();
+        test_list.create_person(2);
+        test_list.create_department(3);
+        test_list
+            .add_member(PersonId::new(1), DepartmentId::new(1))
+            .unwrap();
+        test_list
+            .add_member(PersonId::new(1), DepartmentId::new(2))
+            .unwrap();
+
+        assert!(test_list
+            .transfer_member(PersonId::new(1), DepartmentId::new(1), DepartmentId::new(3))
+            .is_ok());
+        let member_list = &test_list.member_list;
+        assert!(!member_list.is_member(PersonId::new(1), DepartmentId::new(1)));
+        assert!(member_list.is_member(PersonId::new(1), DepartmentId::new(3)));
+        assert_eq!(
+            member_list.department_ids(PersonId::new(1)),
+            vec![DepartmentId::new(2), DepartmentId::new(3)]
+        );
+    }
+
+    #[test]
+    fn transfer_person_err() {
+        let mut test_list = Lists::new();
+        test_list.create_person(2);
+        test_list.create_department(3);
+        test_list
+            .add_member(PersonId::new(1), DepartmentId::new(1))
+            .unwrap();
+        test_list
+            .add_member(PersonId::new(1), DepartmentId::new(2))
+            .unwrap();
+
+        // 移動先に既に所属している
+        assert!(test_list
+            .transfer_member(PersonId::new(1), DepartmentId::new(1), DepartmentId::new(2))
+            .is_err());
+        // 移動元に所属していない
+        assert!(test_list
+            .transfer_member(PersonId::new(2), DepartmentId::new(1), DepartmentId::new(3))
+            .is_err());
+        // 移動元と移動先が同じ
+        assert!(test_list
+            .transfer_member(PersonId::new(1), DepartmentId::new(1), DepartmentId::new(1))
+            .is_err());
+        // 移動先が存在しない
+        assert!(test_list
+            .transfer_member(PersonId::new(1), DepartmentId::new(1), DepartmentId::new(4))
+            .is_err());
+        // 失敗した場合は所属が変わらない
+        assert_eq!(
+            test_list.member_list.department_ids(PersonId::new(1)),
+            vec![DepartmentId::new(1), DepartmentId::new(2)]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_ok() {
+        let mut lists = Lists::new();
+        lists.create_person(2);
+        lists.create_department(2);
+        lists
+            .add_member(PersonId::new(2), DepartmentId::new(1))
+            .unwrap();
+        lists
+            .add_member(PersonId::new(1), DepartmentId::new(2))
+            .unwrap();
+        lists.member_list.suspend_person(PersonId::new(2));
+        let json = serde_json::to_string(&lists.member_list).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"department_id":1,"person_id":2,"status":"suspended"},{"department_id":2,"person_id":1,"status":"valid"}]"#
+        );
+        let restored: MemberList = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            restored.member_ids(DepartmentId::new(2)),
+            vec![PersonId::new(1)]
+        );
+        assert_eq!(
+            restored.suspended_members(),
+            vec![(DepartmentId::new(1), PersonId::new(2))]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_duplicate_member_err() {
+        let json = r#"[{"department_id":1,"person_id":1,"status":"valid"},{"department_id":1,"person_id":1,"status":"suspended"}]"#;
+        assert!(serde_json::from_str::<MemberList>(json).is_err());
+        let json = r#"[{"department_id":1,"person_id":1,"status":"unknown"}]"#;
+        assert!(serde_json::from_str::<MemberList>(json).is_err());
+    }
 }
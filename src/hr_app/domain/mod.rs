@@ -1,5 +1,10 @@
+pub mod audit;
 pub mod department;
+pub mod error;
 mod id_type;
+mod kana;
 pub mod member;
 mod name_type;
 pub mod person;
+pub mod search;
+pub mod timestamp;
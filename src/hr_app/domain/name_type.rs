@@ -1,4 +1,7 @@
+use super::error::DomainError;
+
 // 最長値と最短値を持つ文字列を格納する
+#[derive(Clone, Debug)]
 pub struct NameType {
     value: String,
     _min_length: u8,
@@ -9,25 +12,40 @@ impl NameType {
     // 最長値と最短値はともに正の値
     // 最長値は最短値よりも大きくなければならない
     // name引数の長さが最短値以上最長値以下に収まらなければエラーを返す
-    pub fn new(name: &str, min_length: u8, max_length: u8) -> Result<NameType, &'static str> {
+    // テキストファイルでは空白で項目を、= でキーと値を区切るので、空白と = を含む名前はエラーを返す
+    pub fn new(name: &str, min_length: u8, max_length: u8) -> Result<NameType, DomainError> {
         let min_length = if min_length > 0 {
             min_length
         } else {
-            return Err("NameType::new : min_length must be larger than 0.");
+            return Err(DomainError::invalid(
+                "NameType::new : min_length must be larger than 0.",
+            ));
         };
 
         let max_length = if max_length > min_length {
             max_length
         } else {
-            return Err("NameType::new : max_length must be larger than min_length.");
+            return Err(DomainError::invalid(
+                "NameType::new : max_length must be larger than min_length.",
+            ));
         };
 
         let name_length: usize = name.len();
         if name_length > max_length as usize {
-            return Err("Name::new : the size of value is larger than the max length.");
+            return Err(DomainError::invalid(
+                "Name::new : the size of value is larger than the max length.",
+            ));
         }
         if name_length < min_length as usize {
-            return Err("Name::new : the size of value is smaller than the min length");
+            return Err(DomainError::invalid(
+                "Name::new : the size of value is smaller than the min length",
+            ));
+        }
+
+        if name.contains(|c: char| c.is_whitespace() || c == '=') {
+            return Err(DomainError::invalid(
+                "Name::new : the value must not contain spaces or '='.",
+            ));
         }
 
         return Ok(NameType {
@@ -85,6 +103,13 @@ mod tests {
         assert!(NameType::new("abcdefghijk", 5, 10).is_err());
     }
 
+    #[test]
+    fn new_separator_err() {
+        for name in ["Van Damme", "Jean\tClaude", "A=B", "Bob\n"] {
+            assert!(NameType::new(name, 1, 20).is_err(), "{}", name);
+        }
+    }
+
     #[test]
     fn value_ok() {
         let name_type = NameType::new("Alice", 1, 20).unwrap();
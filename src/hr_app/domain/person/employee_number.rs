@@ -0,0 +1,54 @@
+use super::super::error::DomainError;
+
+// 社員番号を格納する
+// 一括取り込み(import_csv)で既存の人を特定するために使用する
+// 英数字、ハイフン、アンダースコアのみ使用できる
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EmployeeNumber {
+    value: String,
+}
+
+impl EmployeeNumber {
+    const MAX_LENGTH: usize = 32;
+
+    pub fn new(value: &str) -> Result<EmployeeNumber, DomainError> {
+        if value.is_empty() || value.len() > Self::MAX_LENGTH {
+            return Err(DomainError::invalid(
+                "EmployeeNumber::new : the length of employee number is invalid!",
+            ));
+        }
+        if !value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(DomainError::invalid(
+                "EmployeeNumber::new : employee number contains invalid characters!",
+            ));
+        }
+        return Ok(EmployeeNumber {
+            value: String::from(value),
+        });
+    }
+
+    pub fn value(&self) -> &str {
+        return &self.value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_ok() {
+        let number = EmployeeNumber::new("E-001").unwrap();
+        assert_eq!(number.value(), "E-001");
+    }
+
+    #[test]
+    fn new_invalid_err() {
+        assert!(EmployeeNumber::new("").is_err());
+        assert!(EmployeeNumber::new("E 001").is_err());
+        assert!(EmployeeNumber::new(&"1".repeat(33)).is_err());
+    }
+}
@@ -1,3 +1,6 @@
+use super::super::error::DomainError;
+use super::super::search::{self, MatchLevel};
+use super::super::timestamp::Timestamp;
 use super::id::PersonId;
 use super::*;
 use std::collections::HashMap;
@@ -8,10 +11,12 @@ use std::error::Error;
 // max_idはHashMap内にある最も値の大きいIDを保持する
 // max_idは新規にPersonを追加する際のIDの割り振りなどに使用する
 // iter_idはIterator実装のためのメンバー変数
+// 削除されたPersonは削除日時と共にdeleted_listに移し、復元や完全削除(purge)に備える
 pub struct PersonList {
     max_id: PersonId,
     iter_id: PersonId,
     person_list: HashMap<PersonId, Option<Person>>,
+    deleted_list: HashMap<PersonId, (Person, Timestamp)>,
 }
 
 impl PersonList {
@@ -25,11 +30,26 @@ impl PersonList {
             max_id: max_id,
             iter_id: iter_id,
             person_list: list,
+            deleted_list: HashMap::new(),
         };
     }
 
+    // 削除済でないPerson(exceptを除く)に同じ社員番号のPersonがいるかどうかを返す
+    // 社員番号の無いPersonの場合は常にfalseを返す
+    fn has_employee_number(&self, person: &Person, except: Option<PersonId>) -> bool {
+        let employee_number = match person.employee_number() {
+            Some(employee_number) => employee_number,
+            None => return false,
+        };
+        return self.person_list.iter().any(|(id, other)| match other {
+            Some(other) => Some(*id) != except && other.employee_number() == Some(employee_number),
+            None => false,
+        });
+    }
+
     // Personを渡してリストに追加する
     // 必要であればIDを指定することが出来る(プログラム起動時のファイル読み込みで使用)
+    // 同じ社員番号のPersonは複数存在できない
     // 既にリストに存在しているIDと同じIDを重複して指定することは出来ない
     // 追加されたPersonのIDに応じてmax_idを更新する
     pub fn add_person(
@@ -37,14 +57,19 @@ impl PersonList {
         person: Person,
         id: Option<PersonId>,
     ) -> Result<(), Box<dyn Error>> {
+        if self.has_employee_number(&person, None) {
+            let error = DomainError::conflict(
+                "PersonList::add_person : This employee number is already used!",
+            );
+            return Err(error.into());
+        }
         match id {
             Some(id) => {
-                match self.person_list.get(&id) {
-                    Some(_) => {
-                        let message = "PersonList::add_person : The same id has already existed";
-                        return Err(message.into());
-                    }
-                    None => (),
+                if self.person_list.contains_key(&id) {
+                    let error = DomainError::conflict(
+                        "PersonList::add_person : The same id has already existed",
+                    );
+                    return Err(error.into());
                 }
                 self.person_list.insert(id, Some(person));
                 if id.greater_than(self.max_id) {
@@ -60,45 +85,324 @@ impl PersonList {
         return Ok(());
     }
 
+    // 削除済のPersonを削除日時と共にリストに追加する(プログラム起動時のファイル読み込みで使用)
+    // 既にリストに存在しているIDと同じIDを重複して指定することは出来ない
+    pub fn add_deleted_person(
+        &mut self,
+        person: Person,
+        id: PersonId,
+        deleted_at: Timestamp,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.person_list.contains_key(&id) {
+            let error = DomainError::conflict(
+                "PersonList::add_deleted_person : The same id has already existed",
+            );
+            return Err(error.into());
+        }
+        self.person_list.insert(id, None);
+        self.deleted_list.insert(id, (person, deleted_at));
+        if id.greater_than(self.max_id) {
+            self.max_id = id;
+        }
+        return Ok(());
+    }
+
     // IDを指定してPersonを削除する
     // HashMapのキーとなるIDは残し、値であるOption<Person>をNoneに変更する
-    pub fn delete_person(&mut self, person_id: PersonId) -> Result<(), &'static str> {
+    // 削除したPersonは現在時刻と共にdeleted_listに移す
+    pub fn delete_person(&mut self, person_id: PersonId) -> Result<(), DomainError> {
         match self.person_list.get(&person_id) {
             Some(option) => match option {
                 Some(_) => {
-                    self.person_list.insert(person_id, None);
+                    if let Some(Some(person)) = self.person_list.insert(person_id, None) {
+                        self.deleted_list
+                            .insert(person_id, (person, Timestamp::now()));
+                    }
                     return Ok(());
                 }
                 None => {
-                    return Err(
+                    return Err(DomainError::not_found(
                         "PersonList::delete_person : This person has already been deleted!",
-                    );
+                    ));
                 }
             },
             None => {
-                return Err("PersonList::delete_person : Id is empty!");
+                return Err(DomainError::not_found(
+                    "PersonList::delete_person : Id is empty!",
+                ));
+            }
+        }
+    }
+
+    // 削除済のPersonを元のIDのまま復元する
+    // 削除されていないIDや完全削除済のIDを指定するとエラーを返す
+    // 削除中に同じ社員番号のPersonが作られていた場合は復元できない
+    pub fn restore_person(&mut self, person_id: PersonId) -> Result<(), DomainError> {
+        if let Some((person, _deleted_at)) = self.deleted_list.get(&person_id) {
+            if self.has_employee_number(person, None) {
+                return Err(DomainError::conflict(
+                    "PersonList::restore_person : This employee number is already used!",
+                ));
+            }
+        }
+        match self.deleted_list.remove(&person_id) {
+            Some((person, _deleted_at)) => {
+                self.person_list.insert(person_id, Some(person));
+                return Ok(());
+            }
+            None => {
+                return Err(DomainError::not_found(
+                    "PersonList::restore_person : This person is not in the deleted list!",
+                ));
             }
         }
     }
 
+    // 削除済のPersonのIDと参照、削除日時をIDの小さい順に返す
+    pub fn deleted_persons(&self) -> Vec<(PersonId, &Person, Timestamp)> {
+        let mut result: Vec<(PersonId, &Person, Timestamp)> = self
+            .deleted_list
+            .iter()
+            .map(|(id, (person, deleted_at))| (*id, person, *deleted_at))
+            .collect();
+        result.sort_by_key(|(id, _person, _deleted_at)| *id);
+        return result;
+    }
+
+    // 指定した日時以前に削除されたPersonを完全に削除し、そのIDを返す
+    // IDの再利用を防ぐためHashMapのキーは残す
+    // 再読み込み後も再利用しないよう、保存先はmax_idも保存する
+    pub fn purge_deleted_persons(&mut self, before: Timestamp) -> Vec<PersonId> {
+        let mut purged: Vec<PersonId> = self
+            .deleted_list
+            .iter()
+            .filter(|(_id, (_person, deleted_at))| *deleted_at <= before)
+            .map(|(id, _value)| *id)
+            .collect();
+        purged.sort();
+        for id in purged.iter() {
+            self.deleted_list.remove(id);
+        }
+        return purged;
+    }
+
+    // IDがリストに存在するかどうかを返す
+    // 削除済(完全削除されていないもの)のPersonも含む
+    pub fn contains(&self, person_id: PersonId) -> bool {
+        return self.person(person_id).is_ok() || self.deleted_list.contains_key(&person_id);
+    }
+
     // IDを指定してPersonの参照を取得する
     // 存在しないIDや削除済のIDを指定するとエラーを返す
-    pub fn person(&self, person_id: PersonId) -> Result<&Person, &'static str> {
+    pub fn person(&self, person_id: PersonId) -> Result<&Person, DomainError> {
         let person = match self.person_list.get(&person_id) {
             Some(option) => match option {
                 Some(person) => person,
                 None => {
-                    let message = "PersonList::person : This person has already been deleted!";
-                    return Err(message);
+                    let error = DomainError::not_found(
+                        "PersonList::person : This person has already been deleted!",
+                    );
+                    return Err(error);
                 }
             },
             None => {
-                let message = "PersonList::person : Id is empty!";
-                return Err(message);
+                let error = DomainError::not_found("PersonList::person : Id is empty!");
+                return Err(error);
             }
         };
         return Ok(person);
     }
+
+    // 次にadd_personでIDを指定せずに追加した時に割り振られるIDを返す
+    pub fn next_id(&self) -> PersonId {
+        return self.max_id.next_id();
+    }
+
+    // これまでに割り振った最も大きいIDを返す
+    // 完全削除されたIDも含むので、保存先はこの値を保存してIDの再利用を防ぐ
+    pub fn max_id(&self) -> PersonId {
+        return self.max_id;
+    }
+
+    // 保存先から読み込んだmax_idを反映する(プログラム起動時のファイル読み込みで使用)
+    // 現在のmax_idより小さい値は無視する
+    pub fn reserve_ids(&mut self, max_id: PersonId) {
+        if max_id.greater_than(self.max_id) {
+            self.max_id = max_id;
+        }
+    }
+
+    // IDを指定してPersonをリストから取り除き、そのPersonを返す
+    // delete_personと異なりIDのキーも取り除く(作成の取り消しに使用する)
+    // 削除済のIDを指定するとエラーを返す
+    pub fn take_person(&mut self, person_id: PersonId) -> Result<Person, DomainError> {
+        let _person = self.person(person_id)?;
+        match self.person_list.remove(&person_id) {
+            Some(Some(person)) => return Ok(person),
+            _ => {
+                return Err(DomainError::not_found(
+                    "PersonList::take_person : Id is empty!",
+                ))
+            }
+        }
+    }
+
+    // IDを指定してPersonを別のPersonで置き換える
+    // 名前の変更を取り消す時などに使用する
+    // 他のPersonと同じ社員番号にすることは出来ない
+    pub fn replace_person(
+        &mut self,
+        person_id: PersonId,
+        person: Person,
+    ) -> Result<(), DomainError> {
+        if self.has_employee_number(&person, Some(person_id)) {
+            return Err(DomainError::conflict(
+                "PersonList::replace_person : This employee number is already used!",
+            ));
+        }
+        let old_person = self.person_mut(person_id)?;
+        *old_person = person;
+        return Ok(());
+    }
+
+    // IDを指定してPersonの可変参照を取得する
+    fn person_mut(&mut self, person_id: PersonId) -> Result<&mut Person, DomainError> {
+        match self.person_list.get_mut(&person_id) {
+            Some(Some(person)) => return Ok(person),
+            Some(None) => {
+                return Err(DomainError::not_found(
+                    "PersonList::person_mut : This person has already been deleted!",
+                ));
+            }
+            None => {
+                return Err(DomainError::not_found(
+                    "PersonList::person_mut : Id is empty!",
+                ))
+            }
+        }
+    }
+
+    // IDを指定してPersonの名前を変更する
+    // IDは変わらないので部署への所属はそのまま維持される
+    // 存在しないIDや削除済のIDを指定するとエラーを返す
+    pub fn rename_person(
+        &mut self,
+        person_id: PersonId,
+        last_name: &str,
+        first_name: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let person = self.person_mut(person_id)?;
+        person.rename(last_name, first_name)?;
+        return Ok(());
+    }
+
+    // IDを指定してPersonの名前の任意項目(読み仮名など)を設定する
+    pub fn set_person_name_attribute(
+        &mut self,
+        person_id: PersonId,
+        key: &str,
+        value: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let person = self.person_mut(person_id)?;
+        person.set_name_attribute(key, value)?;
+        return Ok(());
+    }
+
+    // 削除されていないPersonのIDを読み仮名順に並べて返す
+    // 読み仮名が同じ場合はIDの小さい順とする
+    pub fn ids_sorted_by_reading(&self) -> Vec<PersonId> {
+        let mut persons: Vec<(String, PersonId)> = Vec::new();
+        for (id, option) in self.person_list.iter() {
+            if let Some(person) = option {
+                persons.push((person.person_name().sort_key(), *id));
+            }
+        }
+        persons.sort();
+        return persons.into_iter().map(|(_key, id)| id).collect();
+    }
+
+    // 名前(表記、読み仮名、呼び名)で検索して一致したPersonのIDと一致度を返す
+    // 大文字と小文字、かなとローマ字の違いは無視する
+    // 一致度の高い順、同じ場合はIDの小さい順に並べる
+    pub fn find_persons(&self, query: &str) -> Vec<(PersonId, MatchLevel)> {
+        let mut result: Vec<(PersonId, MatchLevel)> = Vec::new();
+        for (id, option) in self.person_list.iter() {
+            if let Some(person) = option {
+                let candidates = person.person_name().search_candidates();
+                if let Some(level) = search::best_match_level(query, &candidates) {
+                    result.push((*id, level));
+                }
+            }
+        }
+        result.sort_by_key(|(id, level)| (*level, *id));
+        return result;
+    }
+
+    // 社員番号が一致する(削除されていない)PersonのIDを返す
+    // 社員番号は削除されていないPersonの間で重複しないので、一致するのは1人までとなる
+    pub fn find_by_employee_number(&self, employee_number: &str) -> Option<PersonId> {
+        for (id, option) in self.person_list.iter() {
+            if let Some(person) = option {
+                if person.employee_number() == Some(employee_number) {
+                    return Some(*id);
+                }
+            }
+        }
+        return None;
+    }
+
+    // 姓と名が完全に一致する(削除されていない)PersonのIDをIDの小さい順に返す
+    pub fn find_by_full_name(&self, last_name: &str, first_name: Option<&str>) -> Vec<PersonId> {
+        let mut result: Vec<PersonId> = Vec::new();
+        for (id, option) in self.person_list.iter() {
+            if let Some(person) = option {
+                let person_name = person.person_name();
+                if person_name.last_name() == last_name && person_name.first_name() == first_name {
+                    result.push(*id);
+                }
+            }
+        }
+        result.sort();
+        return result;
+    }
+
+    // コマンドで指定されたIDまたは名前からPersonIdを特定する
+    // 数値の場合はIDとして扱う(存在の確認は行わない)
+    // 名前の場合は完全一致するPersonが１人だけの時にそのIDを返す
+    pub fn resolve_person(&self, key: &str) -> Result<PersonId, Box<dyn Error>> {
+        if let Ok(id) = key.parse::<u64>() {
+            return Ok(PersonId::new(id));
+        }
+        let ids: Vec<PersonId> = self
+            .find_persons(key)
+            .into_iter()
+            .filter(|(_id, level)| *level == MatchLevel::Exact)
+            .map(|(id, _level)| id)
+            .collect();
+        match ids.len() {
+            1 => return Ok(ids[0]),
+            0 => {
+                return Err(DomainError::not_found(
+                    "PersonList::resolve_person : No person has this name!",
+                )
+                .into())
+            }
+            _ => {
+                let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+                let message = format!(
+                    "PersonList::resolve_person : Several persons have this name! (ids: {})",
+                    ids.join(", ")
+                );
+                return Err(DomainError::invalid(&message).into());
+            }
+        }
+    }
+}
+
+impl Default for PersonList {
+    fn default() -> PersonList {
+        return PersonList::new();
+    }
 }
 
 // IDの小さい順にPersonIdと対応するPersonの名前を文字列として返す
@@ -112,11 +416,8 @@ impl Iterator for &mut PersonList {
                 self.iter_id = PersonId::new(0);
                 return None;
             }
-            match self.person(self.iter_id) {
-                Ok(person) => {
-                    return Some((self.iter_id, person.name()));
-                }
-                Err(_) => (),
+            if let Ok(person) = self.person(self.iter_id) {
+                return Some((self.iter_id, person.name()));
             }
         }
     }
@@ -224,6 +525,264 @@ mod tests {
         assert!(person_list.delete_person(id).is_err());
     }
 
+    #[test]
+    fn rename_person_ok() {
+        let mut person_list = PersonList::new();
+        let person = generate_a_person();
+        let id = PersonId::new(1);
+        person_list.add_person(person, Some(id)).unwrap();
+        assert!(person_list
+            .rename_person(id, "Potter", Some("Harry"))
+            .is_ok());
+        assert_eq!(
+            person_list.person(id).unwrap().name(),
+            String::from("Potter Harry")
+        );
+    }
+
+    #[test]
+    fn rename_person_drops_old_reading_ok() {
+        let mut person_list = PersonList::new();
+        let mut person = Person::new("Potter", Some("Harry")).unwrap();
+        person.set_attribute("reading_last", "ぽったー").unwrap();
+        let id = PersonId::new(1);
+        person_list.add_person(person, Some(id)).unwrap();
+        assert_eq!(person_list.find_persons("ぽったー").len(), 1);
+        person_list
+            .rename_person(id, "Weasley", Some("Harry"))
+            .unwrap();
+        assert!(person_list.find_persons("ぽったー").is_empty());
+        assert_eq!(person_list.find_persons("weasley").len(), 1);
+    }
+
+    #[test]
+    fn rename_person_deleted_person_err() {
+        let mut person_list = PersonList::new();
+        let person = generate_a_person();
+        let id = PersonId::new(1);
+        person_list.add_person(person, Some(id)).unwrap();
+        person_list.delete_person(id).unwrap();
+        assert!(person_list.rename_person(id, "Potter", None).is_err());
+    }
+
+    #[test]
+    fn ids_sorted_by_reading_ok() {
+        let mut person_list = PersonList::new();
+        let mut person1 = Person::new("渡辺", None).unwrap();
+        person1
+            .set_name_attribute("reading_last", "わたなべ")
+            .unwrap();
+        let mut person2 = Person::new("阿部", None).unwrap();
+        person2.set_name_attribute("reading_last", "アベ").unwrap();
+        let person3 = Person::new("Sample", None).unwrap();
+        person_list.add_person(person1, None).unwrap();
+        person_list.add_person(person2, None).unwrap();
+        person_list.add_person(person3, None).unwrap();
+
+        let ids = person_list.ids_sorted_by_reading();
+        assert_eq!(
+            ids,
+            vec![PersonId::new(3), PersonId::new(2), PersonId::new(1)]
+        );
+    }
+
+    #[test]
+    fn find_persons_ok() {
+        let mut person_list = PersonList::new();
+        let mut person1 = Person::new("下道", Some("雄太")).unwrap();
+        person1
+            .set_name_attribute("reading_last", "しもみち")
+            .unwrap();
+        let person2 = Person::new("Shimizu", None).unwrap();
+        let person3 = Person::new("Potter", None).unwrap();
+        person_list.add_person(person1, None).unwrap();
+        person_list.add_person(person2, None).unwrap();
+        person_list.add_person(person3, None).unwrap();
+
+        let ids: Vec<PersonId> = person_list
+            .find_persons("SHIMO")
+            .into_iter()
+            .map(|(id, _level)| id)
+            .collect();
+        assert_eq!(ids, vec![PersonId::new(1)]);
+        assert_eq!(person_list.find_persons("shi").len(), 2);
+        assert!(person_list.find_persons("weasley").is_empty());
+    }
+
+    #[test]
+    fn resolve_person_by_id_ok() {
+        let person_list = PersonList::new();
+        assert_eq!(person_list.resolve_person("3").unwrap(), PersonId::new(3));
+    }
+
+    #[test]
+    fn resolve_person_by_unique_name_ok() {
+        let mut person_list = PersonList::new();
+        person_list
+            .add_person(Person::new("Potter", None).unwrap(), None)
+            .unwrap();
+        person_list
+            .add_person(Person::new("Weasley", None).unwrap(), None)
+            .unwrap();
+        assert_eq!(
+            person_list.resolve_person("weasley").unwrap(),
+            PersonId::new(2)
+        );
+    }
+
+    #[test]
+    fn resolve_person_ambiguous_name_err() {
+        let mut person_list = PersonList::new();
+        person_list.add_person(generate_a_person(), None).unwrap();
+        person_list.add_person(generate_a_person(), None).unwrap();
+        assert!(person_list.resolve_person("Sample").is_err());
+        assert!(person_list.resolve_person("Nobody").is_err());
+    }
+
+    #[test]
+    fn find_by_employee_number_ok() {
+        let mut person_list = PersonList::new();
+        let mut person = generate_a_person();
+        person.set_attribute("employee_number", "E001").unwrap();
+        person_list.add_person(generate_a_person(), None).unwrap();
+        person_list.add_person(person, None).unwrap();
+        assert_eq!(
+            person_list.find_by_employee_number("E001"),
+            Some(PersonId::new(2))
+        );
+        assert_eq!(person_list.find_by_employee_number("E002"), None);
+    }
+
+    #[test]
+    fn duplicate_employee_number_err() {
+        let numbered = |employee_number: &str| {
+            let mut person = generate_a_person();
+            person
+                .set_attribute("employee_number", employee_number)
+                .unwrap();
+            return person;
+        };
+        let mut person_list = PersonList::new();
+        person_list.add_person(numbered("E001"), None).unwrap();
+        person_list.add_person(generate_a_person(), None).unwrap();
+        assert!(person_list.add_person(numbered("E001"), None).is_err());
+        assert!(person_list
+            .replace_person(PersonId::new(2), numbered("E001"))
+            .is_err());
+        // 自身と同じ社員番号への置き換えは出来る
+        assert!(person_list
+            .replace_person(PersonId::new(1), numbered("E001"))
+            .is_ok());
+
+        // 削除中に同じ社員番号のPersonが作られた場合は復元できない
+        person_list.delete_person(PersonId::new(1)).unwrap();
+        person_list.add_person(numbered("E001"), None).unwrap();
+        assert!(person_list.restore_person(PersonId::new(1)).is_err());
+        assert_eq!(
+            person_list.find_by_employee_number("E001"),
+            Some(PersonId::new(3))
+        );
+    }
+
+    #[test]
+    fn find_by_full_name_ok() {
+        let mut person_list = PersonList::new();
+        person_list
+            .add_person(Person::new("Potter", Some("Harry")).unwrap(), None)
+            .unwrap();
+        person_list
+            .add_person(Person::new("Potter", Some("James")).unwrap(), None)
+            .unwrap();
+        assert_eq!(
+            person_list.find_by_full_name("Potter", Some("James")),
+            vec![PersonId::new(2)]
+        );
+        assert!(person_list.find_by_full_name("Potter", None).is_empty());
+    }
+
+    #[test]
+    fn restore_person_ok() {
+        let mut person_list = PersonList::new();
+        let id = PersonId::new(1);
+        person_list
+            .add_person(generate_a_person(), Some(id))
+            .unwrap();
+        person_list.delete_person(id).unwrap();
+        assert_eq!(person_list.deleted_persons().len(), 1);
+        assert!(person_list.restore_person(id).is_ok());
+        assert!(person_list.person(id).is_ok());
+        assert!(person_list.deleted_persons().is_empty());
+    }
+
+    #[test]
+    fn restore_person_not_deleted_err() {
+        let mut person_list = PersonList::new();
+        let id = PersonId::new(1);
+        person_list
+            .add_person(generate_a_person(), Some(id))
+            .unwrap();
+        assert!(person_list.restore_person(id).is_err());
+        assert!(person_list.restore_person(PersonId::new(2)).is_err());
+    }
+
+    #[test]
+    fn purge_deleted_persons_ok() {
+        let mut person_list = PersonList::new();
+        person_list
+            .add_deleted_person(generate_a_person(), PersonId::new(1), Timestamp::new(100))
+            .unwrap();
+        person_list
+            .add_deleted_person(generate_a_person(), PersonId::new(2), Timestamp::new(300))
+            .unwrap();
+        person_list
+            .add_deleted_person(generate_a_person(), PersonId::new(3), Timestamp::new(200))
+            .unwrap();
+
+        // 指定した日時ちょうどに削除されたものも対象とする
+        let purged = person_list.purge_deleted_persons(Timestamp::new(200));
+        assert_eq!(purged, vec![PersonId::new(1), PersonId::new(3)]);
+        assert!(person_list.restore_person(PersonId::new(1)).is_err());
+        assert!(person_list.restore_person(PersonId::new(2)).is_ok());
+        assert!(person_list
+            .add_person(generate_a_person(), Some(PersonId::new(1)))
+            .is_err());
+    }
+
+    #[test]
+    fn contains_ok() {
+        let mut person_list = PersonList::new();
+        let id = PersonId::new(1);
+        person_list
+            .add_person(generate_a_person(), Some(id))
+            .unwrap();
+        person_list.delete_person(id).unwrap();
+        assert!(person_list.contains(id));
+        assert!(!person_list.contains(PersonId::new(2)));
+    }
+
+    #[test]
+    fn take_person_ok() {
+        let mut person_list = PersonList::new();
+        let id = person_list.next_id();
+        person_list.add_person(generate_a_person(), None).unwrap();
+        let person = person_list.take_person(id).unwrap();
+        assert!(person_list.person(id).is_err());
+        assert!(!person_list.contains(id));
+        assert!(person_list.add_person(person, Some(id)).is_ok());
+    }
+
+    #[test]
+    fn replace_person_ok() {
+        let mut person_list = PersonList::new();
+        let id = PersonId::new(1);
+        person_list
+            .add_person(generate_a_person(), Some(id))
+            .unwrap();
+        let person = Person::new("Potter", None).unwrap();
+        assert!(person_list.replace_person(id, person).is_ok());
+        assert_eq!(person_list.person(id).unwrap().name(), "Potter");
+    }
+
     #[test]
     fn iterator_ok_1() {
         let mut person_list = PersonList::new();
@@ -1,20 +1,44 @@
+pub mod employee_number;
 pub mod id;
 pub mod list;
 pub mod name;
 
+use employee_number::EmployeeNumber;
 use name::PersonName;
 use std::error::Error;
 
 // 個人に関する情報を格納する
-// 情報として持っているのは名前のみ
+// 情報として持っているのは名前と社員番号(任意)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "PersonRecord", into = "PersonRecord")
+)]
 pub struct Person {
     name: PersonName,
+    employee_number: Option<EmployeeNumber>,
 }
 
 impl Person {
+    const EMPLOYEE_NUMBER_KEY: &'static str = "employee_number";
+    // "キー=値" の形式で設定できる任意項目
+    // 社員番号以外は名前の任意項目(PersonName::ATTRIBUTE_KEYS)
+    pub const ATTRIBUTE_KEYS: [&'static str; 6] = [
+        Self::EMPLOYEE_NUMBER_KEY,
+        PersonName::ATTRIBUTE_KEYS[0],
+        PersonName::ATTRIBUTE_KEYS[1],
+        PersonName::ATTRIBUTE_KEYS[2],
+        PersonName::ATTRIBUTE_KEYS[3],
+        PersonName::ATTRIBUTE_KEYS[4],
+    ];
+
     pub fn new(last_name: &str, first_name: Option<&str>) -> Result<Person, Box<dyn Error>> {
         let name = PersonName::new(last_name, first_name)?;
-        return Ok(Person { name: name });
+        return Ok(Person {
+            name: name,
+            employee_number: None,
+        });
     }
 
     // 現在持っている情報が名前のみなので、情報の出力メソッド名もnameとした
@@ -23,6 +47,98 @@ impl Person {
         let name = self.name.value();
         return name;
     }
+
+    // 姓と名や読み仮名などの構造化された名前を返す
+    pub fn person_name(&self) -> &PersonName {
+        return &self.name;
+    }
+
+    // 姓と名を変更する
+    // 新しい名前が不正な場合は元の名前のままエラーを返す
+    // 表記が変わった姓、名の読み仮名は削除し、それ以外の任意項目は引き継ぐ
+    pub fn rename(
+        &mut self,
+        last_name: &str,
+        first_name: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.name.rename(last_name, first_name)?;
+        return Ok(());
+    }
+
+    // 名前の任意項目(読み仮名、ミドルネームなど)を設定する
+    pub fn set_name_attribute(&mut self, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+        self.name.set_attribute(key, value)?;
+        return Ok(());
+    }
+
+    pub fn employee_number(&self) -> Option<&str> {
+        match &self.employee_number {
+            Some(number) => return Some(number.value()),
+            None => return None,
+        }
+    }
+
+    // 社員番号または名前の任意項目を設定する
+    // 値が空文字列の場合はその項目を削除する
+    pub fn set_attribute(&mut self, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+        if key != Self::EMPLOYEE_NUMBER_KEY {
+            return self.set_name_attribute(key, value);
+        }
+        if value.is_empty() {
+            self.employee_number = None;
+        } else {
+            self.employee_number = Some(EmployeeNumber::new(value)?);
+        }
+        return Ok(());
+    }
+
+    // 設定されている任意項目を "キー=値" の組で返す
+    pub fn attributes(&self) -> Vec<(&str, String)> {
+        let mut attributes: Vec<(&str, String)> = Vec::new();
+        if let Some(number) = self.employee_number() {
+            attributes.push((Self::EMPLOYEE_NUMBER_KEY, String::from(number)));
+        }
+        attributes.extend(self.name.attributes());
+        return attributes;
+    }
+}
+
+// serde用の中間表現
+// 名前の項目と同じ階層に社員番号を持つ(エクスポートのJSON形式と同じ)
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PersonRecord {
+    #[serde(flatten)]
+    name: name::PersonNameRecord,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    employee_number: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Person> for PersonRecord {
+    fn from(person: Person) -> PersonRecord {
+        let employee_number = person.employee_number().map(String::from);
+        return PersonRecord {
+            name: person.name.into(),
+            employee_number: employee_number,
+        };
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PersonRecord> for Person {
+    type Error = Box<dyn Error>;
+
+    fn try_from(record: PersonRecord) -> Result<Person, Box<dyn Error>> {
+        let employee_number = match record.employee_number {
+            Some(number) => Some(EmployeeNumber::new(&number)?),
+            None => None,
+        };
+        return Ok(Person {
+            name: PersonName::try_from(record.name)?,
+            employee_number: employee_number,
+        });
+    }
 }
 
 #[cfg(test)]
@@ -34,4 +150,49 @@ mod tests {
         let person = Person::new("Shimomichi", Some("Yuta")).unwrap();
         assert_eq!(person.name(), String::from("Shimomichi Yuta"));
     }
+
+    #[test]
+    fn set_attribute_ok() {
+        let mut person = Person::new("Potter", Some("Harry")).unwrap();
+        person.set_attribute("employee_number", "E001").unwrap();
+        person.set_attribute("nickname", "Boy").unwrap();
+        assert_eq!(person.employee_number(), Some("E001"));
+        assert_eq!(person.attributes().len(), 2);
+        person.set_attribute("employee_number", "").unwrap();
+        assert_eq!(person.employee_number(), None);
+        assert!(person.set_attribute("employee_number", "E 1").is_err());
+    }
+
+    #[test]
+    fn rename_ok() {
+        let mut person = Person::new("Shimomichi", Some("Yuta")).unwrap();
+        assert!(person.rename("Potter", None).is_ok());
+        assert_eq!(person.name(), String::from("Potter"));
+    }
+
+    #[test]
+    fn rename_invalid_name_err() {
+        let mut person = Person::new("Shimomichi", Some("Yuta")).unwrap();
+        assert!(person.rename("", None).is_err());
+        assert_eq!(person.name(), String::from("Shimomichi Yuta"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_ok() {
+        let mut person = Person::new("Potter", Some("Harry")).unwrap();
+        person.set_attribute("employee_number", "E001").unwrap();
+        person.set_attribute("nickname", "Boy").unwrap();
+        let json = serde_json::to_string(&person).unwrap();
+        let restored: Person = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.name(), person.name());
+        assert_eq!(restored.attributes(), person.attributes());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_invalid_employee_number_err() {
+        let json = r#"{"last_name":"Potter","employee_number":"E 1"}"#;
+        assert!(serde_json::from_str::<Person>(json).is_err());
+    }
 }
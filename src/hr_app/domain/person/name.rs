@@ -1,43 +1,331 @@
+use super::super::error::DomainError;
+use super::super::kana;
 use super::super::name_type::NameType;
 use std::error::Error;
+use std::fmt;
+
+// 名前を表示する際の姓と名の順序
+// FamilyFirstは "姓 名 ミドルネーム"、GivenFirstは "名 ミドルネーム 姓" の順で表示する
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum NameOrder {
+    FamilyFirst,
+    GivenFirst,
+}
+
+impl NameOrder {
+    pub fn new(value: &str) -> Result<NameOrder, DomainError> {
+        match value {
+            "family_first" => return Ok(NameOrder::FamilyFirst),
+            "given_first" => return Ok(NameOrder::GivenFirst),
+            _ => {
+                return Err(DomainError::invalid(
+                    "NameOrder::new : order must be family_first or given_first",
+                ))
+            }
+        }
+    }
+}
+
+impl fmt::Display for NameOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NameOrder::FamilyFirst => return write!(f, "family_first"),
+            NameOrder::GivenFirst => return write!(f, "given_first"),
+        }
+    }
+}
 
 // Personの名前を格納する
 // 姓と名のフィールドを持つ
 // 名の入力は任意
 // 姓と名それぞれ最大長と最短超は固定値
+// 任意項目としてミドルネーム、呼び名、姓と名の読み仮名、表示順を持つ
+// 任意項目は "キー=値" の形式で設定できる(ATTRIBUTE_KEYSを参照)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "PersonNameRecord", into = "PersonNameRecord")
+)]
 pub struct PersonName {
     last_name: NameType,
     first_name: Option<NameType>,
+    middle_name: Option<NameType>,
+    nickname: Option<NameType>,
+    last_name_reading: Option<NameType>,
+    first_name_reading: Option<NameType>,
+    order: NameOrder,
 }
 
 impl PersonName {
     const MIN_LENGTH: u8 = 1;
     const MAX_LENGTH: u8 = 60;
+    const READING_MAX_LENGTH: u8 = 180;
+    pub const ATTRIBUTE_KEYS: [&'static str; 5] = [
+        "middle",
+        "nickname",
+        "reading_last",
+        "reading_first",
+        "order",
+    ];
 
     pub fn new(last_name: &str, first_name: Option<&str>) -> Result<PersonName, Box<dyn Error>> {
         let last_name = NameType::new(last_name, Self::MIN_LENGTH, Self::MAX_LENGTH)?;
-        let first_name = match first_name {
-            Some(first_name) => Some(NameType::new(
-                first_name,
-                Self::MIN_LENGTH,
-                Self::MAX_LENGTH,
-            )?),
-            None => None,
-        };
+        let first_name = Self::optional_name(first_name, Self::MAX_LENGTH)?;
 
         return Ok(PersonName {
             last_name: last_name,
             first_name: first_name,
+            middle_name: None,
+            nickname: None,
+            last_name_reading: None,
+            first_name_reading: None,
+            order: NameOrder::FamilyFirst,
         });
     }
 
-    // 画面表示用に姓と名を連結して文字列を返す
-    pub fn value(&self) -> String {
-        let last_name = self.last_name.value();
+    fn optional_name(
+        name: Option<&str>,
+        max_length: u8,
+    ) -> Result<Option<NameType>, Box<dyn Error>> {
+        match name {
+            Some(name) => return Ok(Some(NameType::new(name, Self::MIN_LENGTH, max_length)?)),
+            None => return Ok(None),
+        }
+    }
+
+    // 姓と名のみを変更する
+    // 表記が変わった姓、名の読み仮名は古い名前のものなので削除し、それ以外の任意項目は変更前の値を引き継ぐ
+    pub fn rename(
+        &mut self,
+        last_name: &str,
+        first_name: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let last_name = NameType::new(last_name, Self::MIN_LENGTH, Self::MAX_LENGTH)?;
+        let first_name = Self::optional_name(first_name, Self::MAX_LENGTH)?;
+        if last_name.value() != self.last_name.value() {
+            self.last_name_reading = None;
+        }
+        if first_name.as_ref().map(|name| name.value()) != self.first_name() {
+            self.first_name_reading = None;
+        }
+        self.last_name = last_name;
+        self.first_name = first_name;
+        return Ok(());
+    }
+
+    // キーを指定して任意項目を設定する
+    // 値に空文字列を指定すると項目を削除する(orderは既定値のfamily_firstに戻る)
+    pub fn set_attribute(&mut self, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+        let value = if value.is_empty() { None } else { Some(value) };
+        match key {
+            "middle" => self.middle_name = Self::optional_name(value, Self::MAX_LENGTH)?,
+            "nickname" => self.nickname = Self::optional_name(value, Self::MAX_LENGTH)?,
+            "reading_last" => {
+                self.last_name_reading = Self::optional_name(value, Self::READING_MAX_LENGTH)?
+            }
+            "reading_first" => {
+                self.first_name_reading = Self::optional_name(value, Self::READING_MAX_LENGTH)?
+            }
+            "order" => {
+                self.order = match value {
+                    Some(value) => NameOrder::new(value)?,
+                    None => NameOrder::FamilyFirst,
+                }
+            }
+            _ => {
+                return Err(DomainError::invalid(
+                    "PersonName::set_attribute : unknown attribute key",
+                )
+                .into())
+            }
+        }
+        return Ok(());
+    }
+
+    // 設定されている任意項目を (キー, 値) の一覧として返す
+    // 既定値のままの項目は含まない
+    pub fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes: Vec<(&'static str, String)> = Vec::new();
+        let optional_fields = [
+            ("middle", &self.middle_name),
+            ("nickname", &self.nickname),
+            ("reading_last", &self.last_name_reading),
+            ("reading_first", &self.first_name_reading),
+        ];
+        for (key, field) in optional_fields.iter() {
+            if let Some(value) = field {
+                attributes.push((*key, String::from(value.value())));
+            }
+        }
+        if self.order != NameOrder::FamilyFirst {
+            attributes.push(("order", self.order.to_string()));
+        }
+        return attributes;
+    }
+
+    pub fn last_name(&self) -> &str {
+        return self.last_name.value();
+    }
+
+    pub fn first_name(&self) -> Option<&str> {
         match &self.first_name {
-            Some(first_name) => return format!("{} {}", last_name, first_name.value()),
-            None => return format!("{}", last_name),
+            Some(first_name) => return Some(first_name.value()),
+            None => return None,
+        }
+    }
+
+    pub fn nickname(&self) -> Option<&str> {
+        match &self.nickname {
+            Some(nickname) => return Some(nickname.value()),
+            None => return None,
+        }
+    }
+
+    pub fn order(&self) -> NameOrder {
+        return self.order;
+    }
+
+    // 画面表示用に表示順に従って姓、名、ミドルネームを連結して文字列を返す
+    pub fn value(&self) -> String {
+        let mut parts: Vec<&str> = Vec::new();
+        let given_names: Vec<&str> = [&self.first_name, &self.middle_name]
+            .iter()
+            .filter_map(|name| name.as_ref().map(|name| name.value()))
+            .collect();
+        match self.order {
+            NameOrder::FamilyFirst => {
+                parts.push(self.last_name.value());
+                parts.extend(given_names);
+            }
+            NameOrder::GivenFirst => {
+                parts.extend(given_names);
+                parts.push(self.last_name.value());
+            }
+        }
+        return parts.join(" ");
+    }
+
+    // 読み仮名を表示順に従って連結して返す
+    // 読み仮名が設定されていない項目は表記をそのまま使用する
+    pub fn reading(&self) -> String {
+        let last_name = self.last_name_reading_or_name();
+        let first_name = self.first_name_reading_or_name();
+        let parts: Vec<&str> = match self.order {
+            NameOrder::FamilyFirst => vec![last_name, first_name],
+            NameOrder::GivenFirst => vec![first_name, last_name],
+        };
+        let parts: Vec<&str> = parts.into_iter().filter(|part| !part.is_empty()).collect();
+        return parts.join(" ");
+    }
+
+    // 名前検索の対象となる文字列の一覧を返す
+    // 表示名、読み仮名、姓、名、呼び名を対象とする
+    pub fn search_candidates(&self) -> Vec<String> {
+        let mut candidates = vec![self.value(), self.reading(), String::from(self.last_name())];
+        if let Some(first_name) = self.first_name() {
+            candidates.push(String::from(first_name));
+        }
+        if let Some(nickname) = self.nickname() {
+            candidates.push(String::from(nickname));
+        }
+        return candidates;
+    }
+
+    // 読み仮名順に並べ替えるためのキーを返す
+    // 表示順に関わらず姓、名の順に比較する
+    // カタカナはひらがなに、英字は小文字に揃える
+    pub fn sort_key(&self) -> String {
+        let key = format!(
+            "{} {}",
+            self.last_name_reading_or_name(),
+            self.first_name_reading_or_name()
+        );
+        return kana::to_hiragana(&key.to_lowercase());
+    }
+
+    fn last_name_reading_or_name(&self) -> &str {
+        match &self.last_name_reading {
+            Some(reading) => return reading.value(),
+            None => return self.last_name.value(),
+        }
+    }
+
+    fn first_name_reading_or_name(&self) -> &str {
+        match (&self.first_name_reading, &self.first_name) {
+            (Some(reading), _) => return reading.value(),
+            (None, Some(first_name)) => return first_name.value(),
+            (None, None) => return "",
+        }
+    }
+}
+
+// serde用の中間表現
+// キーはエクスポートのJSON形式と同じで、未設定の任意項目は出力しない
+// 読み込み時はPersonName::newとset_attributeを通すため、不正な値はエラーになる
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(super) struct PersonNameRecord {
+    last_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    first_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    middle: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nickname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reading_last: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reading_first: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    order: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl From<PersonName> for PersonNameRecord {
+    fn from(name: PersonName) -> PersonNameRecord {
+        let mut record = PersonNameRecord {
+            last_name: String::from(name.last_name()),
+            first_name: name.first_name().map(String::from),
+            middle: None,
+            nickname: None,
+            reading_last: None,
+            reading_first: None,
+            order: None,
         };
+        for (key, value) in name.attributes() {
+            match key {
+                "middle" => record.middle = Some(value),
+                "nickname" => record.nickname = Some(value),
+                "reading_last" => record.reading_last = Some(value),
+                "reading_first" => record.reading_first = Some(value),
+                "order" => record.order = Some(value),
+                _ => (),
+            }
+        }
+        return record;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PersonNameRecord> for PersonName {
+    type Error = Box<dyn Error>;
+
+    fn try_from(record: PersonNameRecord) -> Result<PersonName, Box<dyn Error>> {
+        let mut name = PersonName::new(&record.last_name, record.first_name.as_deref())?;
+        let attributes = [
+            ("middle", record.middle),
+            ("nickname", record.nickname),
+            ("reading_last", record.reading_last),
+            ("reading_first", record.reading_first),
+            ("order", record.order),
+        ];
+        for (key, value) in attributes.iter() {
+            if let Some(value) = value {
+                name.set_attribute(key, value)?;
+            }
+        }
+        return Ok(name);
     }
 }
 
@@ -78,6 +366,24 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn rename_clears_changed_reading_ok() {
+        let mut name = PersonName::new("Potter", Some("Harry")).unwrap();
+        name.set_attribute("reading_last", "ぽったー").unwrap();
+        name.set_attribute("reading_first", "はりー").unwrap();
+        name.set_attribute("nickname", "Boy").unwrap();
+        name.rename("Weasley", Some("Harry")).unwrap();
+        // 姓の読み仮名だけが削除され、名の読み仮名と呼び名は残る
+        assert_eq!(name.reading(), "Weasley はりー");
+        assert_eq!(name.sort_key(), "weasley はりー");
+        assert_eq!(name.nickname(), Some("Boy"));
+        name.rename("Weasley", Some("Ron")).unwrap();
+        assert_eq!(name.reading(), "Weasley Ron");
+        assert!(!name
+            .search_candidates()
+            .contains(&String::from("ぽったー はりー")));
+    }
+
     #[test]
     fn new_no_first_name_ok() {
         assert!(PersonName::new("Shimomichi", None).is_ok());
@@ -96,4 +402,112 @@ mod tests {
         let person_name = PersonName::new("Shimomichi", None).unwrap();
         assert_eq!(person_name.value(), ans);
     }
+
+    #[test]
+    fn set_attribute_ok() {
+        let mut person_name = PersonName::new("Potter", Some("Harry")).unwrap();
+        assert!(person_name.set_attribute("middle", "James").is_ok());
+        assert!(person_name.set_attribute("nickname", "Boy").is_ok());
+        assert!(person_name
+            .set_attribute("reading_last", "ポッター")
+            .is_ok());
+        assert!(person_name.set_attribute("order", "given_first").is_ok());
+        assert_eq!(person_name.attributes().len(), 4);
+    }
+
+    #[test]
+    fn set_attribute_unknown_key_err() {
+        let mut person_name = PersonName::new("Potter", Some("Harry")).unwrap();
+        assert!(person_name.set_attribute("age", "17").is_err());
+    }
+
+    #[test]
+    fn set_attribute_invalid_order_err() {
+        let mut person_name = PersonName::new("Potter", Some("Harry")).unwrap();
+        assert!(person_name.set_attribute("order", "random").is_err());
+    }
+
+    #[test]
+    fn set_attribute_empty_value_removes_ok() {
+        let mut person_name = PersonName::new("Potter", Some("Harry")).unwrap();
+        person_name.set_attribute("middle", "James").unwrap();
+        person_name.set_attribute("middle", "").unwrap();
+        assert!(person_name.attributes().is_empty());
+    }
+
+    #[test]
+    fn value_given_first_ok() {
+        let ans = String::from("Harry James Potter");
+        let mut person_name = PersonName::new("Potter", Some("Harry")).unwrap();
+        person_name.set_attribute("middle", "James").unwrap();
+        person_name.set_attribute("order", "given_first").unwrap();
+        assert_eq!(person_name.value(), ans);
+    }
+
+    #[test]
+    fn value_family_first_with_middle_name_ok() {
+        let ans = String::from("Potter Harry James");
+        let mut person_name = PersonName::new("Potter", Some("Harry")).unwrap();
+        person_name.set_attribute("middle", "James").unwrap();
+        assert_eq!(person_name.value(), ans);
+    }
+
+    #[test]
+    fn rename_keeps_attributes_ok() {
+        let mut person_name = PersonName::new("Potter", Some("Harry")).unwrap();
+        person_name.set_attribute("nickname", "Boy").unwrap();
+        person_name.rename("Potter", Some("James")).unwrap();
+        assert_eq!(person_name.nickname(), Some("Boy"));
+        assert_eq!(person_name.value(), String::from("Potter James"));
+    }
+
+    #[test]
+    fn reading_ok() {
+        let mut person_name = PersonName::new("下道", Some("雄太")).unwrap();
+        person_name
+            .set_attribute("reading_last", "しもみち")
+            .unwrap();
+        person_name
+            .set_attribute("reading_first", "ゆうた")
+            .unwrap();
+        assert_eq!(person_name.reading(), String::from("しもみち ゆうた"));
+    }
+
+    #[test]
+    fn sort_key_normalizes_katakana_ok() {
+        let mut person_name1 = PersonName::new("下道", None).unwrap();
+        person_name1
+            .set_attribute("reading_last", "シモミチ")
+            .unwrap();
+        let mut person_name2 = PersonName::new("霜道", None).unwrap();
+        person_name2
+            .set_attribute("reading_last", "しもみち")
+            .unwrap();
+        assert_eq!(person_name1.sort_key(), person_name2.sort_key());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_ok() {
+        let mut person_name = PersonName::new("Potter", Some("Harry")).unwrap();
+        person_name.set_attribute("middle", "James").unwrap();
+        person_name.set_attribute("order", "given_first").unwrap();
+        let json = serde_json::to_string(&person_name).unwrap();
+        assert_eq!(
+            json,
+            r#"{"last_name":"Potter","first_name":"Harry","middle":"James","order":"given_first"}"#
+        );
+        let restored: PersonName = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.value(), person_name.value());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_invalid_value_err() {
+        assert!(serde_json::from_str::<PersonName>(r#"{"last_name":""}"#).is_err());
+        let too_long = format!(r#"{{"last_name":"{}"}}"#, "a".repeat(61));
+        assert!(serde_json::from_str::<PersonName>(&too_long).is_err());
+        let order = r#"{"last_name":"Potter","order":"random"}"#;
+        assert!(serde_json::from_str::<PersonName>(order).is_err());
+    }
 }
@@ -0,0 +1,89 @@
+use super::kana;
+
+// 名前検索での一致の度合い
+// 値が小さいほどよく一致している(検索結果の並べ替えに使用する)
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+pub enum MatchLevel {
+    Exact,
+    Prefix,
+    Substring,
+}
+
+// 検索文字列と候補の文字列を比較して一致の度合いを返す
+// 大文字と小文字、ひらがなとカタカナ、かなとローマ字の違いは無視する
+// 前方一致は候補を空白で区切った各単語に対しても判定する
+// 一致しない場合はNoneを返す
+pub fn match_level(query: &str, candidate: &str) -> Option<MatchLevel> {
+    let query = kana::normalize(query.trim());
+    let candidate = kana::normalize(candidate);
+    if query.is_empty() {
+        return None;
+    }
+    if candidate == query {
+        return Some(MatchLevel::Exact);
+    }
+    if candidate.starts_with(&query)
+        || candidate
+            .split_whitespace()
+            .any(|word| word.starts_with(&query))
+    {
+        return Some(MatchLevel::Prefix);
+    }
+    if candidate.contains(&query) {
+        return Some(MatchLevel::Substring);
+    }
+    return None;
+}
+
+// 複数の候補のうち最もよく一致したものの度合いを返す
+pub fn best_match_level(query: &str, candidates: &[String]) -> Option<MatchLevel> {
+    return candidates
+        .iter()
+        .filter_map(|candidate| match_level(query, candidate))
+        .min();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_level_exact_ignore_case_ok() {
+        assert_eq!(match_level("potter", "Potter"), Some(MatchLevel::Exact));
+    }
+
+    #[test]
+    fn match_level_prefix_ok() {
+        assert_eq!(match_level("pot", "Potter"), Some(MatchLevel::Prefix));
+        assert_eq!(match_level("har", "Potter Harry"), Some(MatchLevel::Prefix));
+    }
+
+    #[test]
+    fn match_level_substring_ok() {
+        assert_eq!(match_level("tte", "Potter"), Some(MatchLevel::Substring));
+    }
+
+    #[test]
+    fn match_level_kana_romaji_ok() {
+        assert_eq!(match_level("shimo", "シモミチ"), Some(MatchLevel::Prefix));
+        assert_eq!(
+            match_level("しもみち", "Shimomichi"),
+            Some(MatchLevel::Exact)
+        );
+    }
+
+    #[test]
+    fn match_level_none() {
+        assert_eq!(match_level("weasley", "Potter"), None);
+        assert_eq!(match_level("", "Potter"), None);
+    }
+
+    #[test]
+    fn best_match_level_ok() {
+        let candidates = vec![String::from("Potter"), String::from("pot")];
+        assert_eq!(
+            best_match_level("pot", &candidates),
+            Some(MatchLevel::Exact)
+        );
+    }
+}
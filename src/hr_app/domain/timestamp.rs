@@ -0,0 +1,103 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 日時をUNIXエポックからの経過秒数(UTC)として格納する
+// 監査ログや削除日時の記録に使用する
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+pub struct Timestamp {
+    seconds: u64,
+}
+
+impl Timestamp {
+    pub fn new(seconds: u64) -> Timestamp {
+        return Timestamp { seconds: seconds };
+    }
+
+    // 現在時刻を返す
+    // システム時刻がUNIXエポックより前の場合は0とする
+    pub fn now() -> Timestamp {
+        let seconds = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs(),
+            Err(_) => 0,
+        };
+        return Timestamp { seconds: seconds };
+    }
+
+    pub fn seconds(&self) -> u64 {
+        return self.seconds;
+    }
+
+    // 指定した日数だけ前の日時を返す
+    // UNIXエポックより前にはならない
+    pub fn days_before(&self, days: u64) -> Timestamp {
+        let seconds = self.seconds.saturating_sub(days.saturating_mul(86400));
+        return Timestamp { seconds: seconds };
+    }
+
+    // ファイルへの出力用に "YYYY-MM-DDThh:mm:ssZ" 形式(ISO 8601, UTC)の文字列を返す
+    pub fn to_iso8601(&self) -> String {
+        return format!("{}Z", self.to_string().replace(' ', "T"));
+    }
+}
+
+// 画面表示用の "YYYY-MM-DD hh:mm:ss" 形式(UTC)の文字列
+// 日付の計算はグレゴリオ暦の400年周期を利用する
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let days = (self.seconds / 86400) as i64;
+        let rest = self.seconds % 86400;
+        let (hour, minute, second) = (rest / 3600, rest % 3600 / 60, rest % 60);
+
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let day_of_era = z.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        return write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            year, month, day, hour, minute, second
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_string_epoch_ok() {
+        let timestamp = Timestamp::new(0);
+        assert_eq!(timestamp.to_string(), String::from("1970-01-01 00:00:00"));
+    }
+
+    #[test]
+    fn to_string_leap_day_ok() {
+        let timestamp = Timestamp::new(951782400 + 3661);
+        assert_eq!(timestamp.to_string(), String::from("2000-02-29 01:01:01"));
+    }
+
+    #[test]
+    fn to_iso8601_ok() {
+        let timestamp = Timestamp::new(951782400 + 3661);
+        assert_eq!(timestamp.to_iso8601(), String::from("2000-02-29T01:01:01Z"));
+    }
+
+    #[test]
+    fn days_before_ok() {
+        let timestamp = Timestamp::new(86400 * 3 + 5);
+        assert_eq!(timestamp.days_before(2), Timestamp::new(86400 + 5));
+        assert_eq!(timestamp.days_before(10), Timestamp::new(0));
+    }
+
+    #[test]
+    fn compare_ok() {
+        assert!(Timestamp::new(10) > Timestamp::new(5));
+    }
+}
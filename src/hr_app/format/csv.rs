@@ -0,0 +1,134 @@
+use std::error::Error;
+
+// CSV(RFC 4180)の読み書きを行う
+// ダブルクォートで囲まれた値の中ではカンマ、改行、"" (ダブルクォート１文字)を使用できる
+// 先頭のBOMと、行末のCRLFにも対応する
+
+// 元のファイルでの行番号と、その行の値のリスト
+pub type Row = (usize, Vec<String>);
+
+// CSVの文字列を行ごとの値のリストに変換する
+// 各行には元のファイルでの行番号(1始まり)を付けて返す
+// 空行は無視する
+pub fn parse(text: &str) -> Result<Vec<Row>, Box<dyn Error>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut rows: Vec<Row> = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line_number = 1;
+    let mut row_line_number = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line_number += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '"' => {
+                let message = format!("csv::parse : Unexpected quote! (line {})", line_number);
+                return Err(message.into());
+            }
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                if !(row.len() == 1 && row[0].is_empty()) {
+                    rows.push((row_line_number, std::mem::take(&mut row)));
+                }
+                row.clear();
+                line_number += 1;
+                row_line_number = line_number;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        let message = format!(
+            "csv::parse : Quoted value is not closed! (line {})",
+            row_line_number
+        );
+        return Err(message.into());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push((row_line_number, row));
+    }
+    return Ok(rows);
+}
+
+// 値のリストをCSVの１行(改行を含む)に変換する
+// カンマ、ダブルクォート、改行を含む値はダブルクォートで囲む
+pub fn format_row(fields: &[String]) -> String {
+    let fields: Vec<String> = fields.iter().map(|field| escape(field)).collect();
+    let mut line = fields.join(",");
+    line.push_str("\r\n");
+    return line;
+}
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    return String::from(field);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(row: &[&str]) -> Vec<String> {
+        return row.iter().map(|value| String::from(*value)).collect();
+    }
+
+    #[test]
+    fn parse_simple_ok() {
+        let rows = parse("a,b,c\r\n1,2,3\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], (1, values(&["a", "b", "c"])));
+        assert_eq!(rows[1], (2, values(&["1", "2", "3"])));
+    }
+
+    #[test]
+    fn parse_quoted_ok() {
+        let rows = parse("\"a,b\",\"say \"\"hi\"\"\",\"x\ny\"\nnext,,").unwrap();
+        assert_eq!(rows[0], (1, values(&["a,b", "say \"hi\"", "x\ny"])));
+        assert_eq!(rows[1], (3, values(&["next", "", ""])));
+    }
+
+    #[test]
+    fn parse_bom_and_blank_lines_ok() {
+        let rows = parse("\u{feff}name\n\nDefence\n").unwrap();
+        assert_eq!(
+            rows,
+            vec![(1, values(&["name"])), (3, values(&["Defence"]))]
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_quote_err() {
+        assert!(parse("\"abc\n").is_err());
+        assert!(parse("ab\"c\n").is_err());
+    }
+
+    #[test]
+    fn format_row_ok() {
+        let row = format_row(&values(&["a", "b,c", "say \"hi\""]));
+        assert_eq!(row, String::from("a,\"b,c\",\"say \"\"hi\"\"\"\r\n"));
+        let rows = parse(&row).unwrap();
+        assert_eq!(rows[0].1, values(&["a", "b,c", "say \"hi\""]));
+    }
+}
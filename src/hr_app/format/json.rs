@@ -0,0 +1,446 @@
+use std::error::Error;
+
+// JSONの読み書きを行う
+// 人事データで使用する値に限定し、数値は0以上の整数のみを扱う
+
+// JSONの値
+// Objectはキーの順番を保つために(キー, 値)のリストとする
+#[derive(PartialEq, Clone, Debug)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    // 値がNoneの場合はNullとする
+    pub fn optional_string(value: Option<&str>) -> Value {
+        match value {
+            Some(value) => return Value::String(String::from(value)),
+            None => return Value::Null,
+        }
+    }
+
+    // Objectのキーに対応する値を返す
+    // Object以外の場合やキーが存在しない場合はNoneを返す
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => {
+                return members
+                    .iter()
+                    .find(|(member_key, _value)| member_key == key)
+                    .map(|(_key, value)| value);
+            }
+            _ => return None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => return Some(value),
+            _ => return None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(value) => return Some(*value),
+            _ => return None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(values) => return Some(values),
+            _ => return None,
+        }
+    }
+
+    // インデント(空白２文字)付きのJSON文字列に変換する
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write(&mut json, 0);
+        json.push('\n');
+        return json;
+    }
+
+    fn write(&self, json: &mut String, depth: usize) {
+        match self {
+            Value::Null => json.push_str("null"),
+            Value::Bool(value) => json.push_str(if *value { "true" } else { "false" }),
+            Value::Number(value) => json.push_str(&value.to_string()),
+            Value::String(value) => write_string(json, value),
+            Value::Array(values) if values.is_empty() => json.push_str("[]"),
+            Value::Array(values) => {
+                json.push('[');
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        json.push(',');
+                    }
+                    newline(json, depth + 1);
+                    value.write(json, depth + 1);
+                }
+                newline(json, depth);
+                json.push(']');
+            }
+            Value::Object(members) if members.is_empty() => json.push_str("{}"),
+            Value::Object(members) => {
+                json.push('{');
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        json.push(',');
+                    }
+                    newline(json, depth + 1);
+                    write_string(json, key);
+                    json.push_str(": ");
+                    value.write(json, depth + 1);
+                }
+                newline(json, depth);
+                json.push('}');
+            }
+        }
+    }
+}
+
+fn newline(json: &mut String, depth: usize) {
+    json.push('\n');
+    json.push_str(&"  ".repeat(depth));
+}
+
+// 文字列をダブルクォートで囲み、必要な文字をエスケープする
+fn write_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+// 配列とオブジェクトの入れ子の上限
+// 再帰下降で解析するので、深すぎる入れ子でスタックを使い果たさないようにする
+const MAX_NESTING: usize = 128;
+
+// JSON文字列を解析する
+// 前後の空白以外に余分な文字がある場合はエラーとする
+pub fn parse(text: &str) -> Result<Value, Box<dyn Error>> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        position: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.position < parser.chars.len() {
+        return Err(parser.error("Unexpected character"));
+    }
+    return Ok(value);
+}
+
+// depthは解析中の配列とオブジェクトの入れ子の深さ
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    // エラーの位置を行番号で示す
+    fn error(&self, message: &str) -> Box<dyn Error> {
+        let line = self.chars[..self.position.min(self.chars.len())]
+            .iter()
+            .filter(|c| **c == '\n')
+            .count()
+            + 1;
+        return format!("json::parse : {}! (line {})", message, line).into();
+    }
+
+    fn peek(&self) -> Option<char> {
+        return self.chars.get(self.position).copied();
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r' | '\u{feff}') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Box<dyn Error>> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("'{}' is expected", expected)));
+        }
+        self.position += 1;
+        return Ok(());
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, Box<dyn Error>> {
+        for expected in keyword.chars() {
+            if self.peek() != Some(expected) {
+                return Err(self.error("Unexpected character"));
+            }
+            self.position += 1;
+        }
+        return Ok(value);
+    }
+
+    fn value(&mut self) -> Result<Value, Box<dyn Error>> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') | Some('[') => return self.nested(),
+            Some('"') => return Ok(Value::String(self.string()?)),
+            Some('n') => return self.keyword("null", Value::Null),
+            Some('t') => return self.keyword("true", Value::Bool(true)),
+            Some('f') => return self.keyword("false", Value::Bool(false)),
+            Some('0'..='9') => return self.number(),
+            Some('-') => return Err(self.error("Negative number is not supported")),
+            Some(_) => return Err(self.error("Unexpected character")),
+            None => return Err(self.error("Unexpected end of text")),
+        }
+    }
+
+    // 配列かオブジェクトを入れ子の深さを数えながら解析する
+    fn nested(&mut self) -> Result<Value, Box<dyn Error>> {
+        if self.depth >= MAX_NESTING {
+            return Err(self.error("Nesting is too deep"));
+        }
+        self.depth += 1;
+        let value = match self.peek() {
+            Some('{') => self.object(),
+            _ => self.array(),
+        };
+        self.depth -= 1;
+        return value;
+    }
+
+    fn object(&mut self) -> Result<Value, Box<dyn Error>> {
+        self.expect('{')?;
+        let mut members: Vec<(String, Value)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("Object key is expected"));
+            }
+            let key = self.string()?;
+            self.expect(':')?;
+            let value = self.value()?;
+            members.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(members));
+                }
+                _ => return Err(self.error("',' or '}' is expected")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Box<dyn Error>> {
+        self.expect('[')?;
+        let mut values: Vec<Value> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Value::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Value::Array(values));
+                }
+                _ => return Err(self.error("',' or ']' is expected")),
+            }
+        }
+    }
+
+    // 小数と指数表記には対応しない
+    fn number(&mut self) -> Result<Value, Box<dyn Error>> {
+        let start = self.position;
+        while let Some('0'..='9') = self.peek() {
+            self.position += 1;
+        }
+        if let Some('.' | 'e' | 'E') = self.peek() {
+            return Err(self.error("Only integer number is supported"));
+        }
+        let digits: String = self.chars[start..self.position].iter().collect();
+        match digits.parse() {
+            Ok(number) => return Ok(Value::Number(number)),
+            Err(_) => return Err(self.error("Number is too large")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Box<dyn Error>> {
+        self.position += 1;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("String is not closed")),
+                Some('"') => {
+                    self.position += 1;
+                    return Ok(value);
+                }
+                Some('\\') => {
+                    self.position += 1;
+                    let escaped = match self.peek() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error("Invalid escape sequence")),
+                    };
+                    value.push(escaped);
+                    self.position += 1;
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("Control character in string"));
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    // "\uXXXX" を読み込み、サロゲートペアは１文字にまとめる
+    // 読み込み後の位置は最後の16進数字とする
+    fn unicode_escape(&mut self) -> Result<char, Box<dyn Error>> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("Invalid escape sequence"));
+        }
+        let next: String = self.chars.iter().skip(self.position + 1).take(2).collect();
+        if next != "\\u" {
+            return Err(self.error("Invalid escape sequence"));
+        }
+        self.position += 2;
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(self.error("Invalid escape sequence"));
+        }
+        let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+        return char::from_u32(code).ok_or_else(|| self.error("Invalid escape sequence"));
+    }
+
+    fn hex4(&mut self) -> Result<u32, Box<dyn Error>> {
+        let digits: String = self.chars.iter().skip(self.position + 1).take(4).collect();
+        if digits.len() != 4 {
+            return Err(self.error("Invalid escape sequence"));
+        }
+        self.position += 4;
+        match u32::from_str_radix(&digits, 16) {
+            Ok(code) => return Ok(code),
+            Err(_) => return Err(self.error("Invalid escape sequence")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_json_ok() {
+        let value = Value::Object(vec![
+            (String::from("id"), Value::Number(1)),
+            (
+                String::from("name"),
+                Value::String(String::from("Harry \"H\"")),
+            ),
+            (String::from("deleted_at"), Value::Null),
+            (String::from("tags"), Value::Array(vec![])),
+        ]);
+        let json = "{\n  \"id\": 1,\n  \"name\": \"Harry \\\"H\\\"\",\n  \"deleted_at\": null,\n  \"tags\": []\n}\n";
+        assert_eq!(value.to_json(), String::from(json));
+    }
+
+    #[test]
+    fn to_json_array_ok() {
+        let value = Value::Array(vec![
+            Value::Bool(true),
+            Value::String(String::from("ポッター")),
+        ]);
+        assert_eq!(
+            value.to_json(),
+            String::from("[\n  true,\n  \"ポッター\"\n]\n")
+        );
+    }
+
+    #[test]
+    fn parse_ok() {
+        let text = "{\"id\": 1, \"name\": \"Po\\\"tter\\u3042\\ud83d\\ude00\", \"tags\": [true, null], \"empty\": {}}";
+        let value = parse(text).unwrap();
+        assert_eq!(value.get("id").and_then(|v| v.as_u64()), Some(1));
+        assert_eq!(
+            value.get("name").and_then(|v| v.as_str()),
+            Some("Po\"tter\u{3042}\u{1f600}")
+        );
+        assert_eq!(
+            value.get("tags"),
+            Some(&Value::Array(vec![Value::Bool(true), Value::Null]))
+        );
+        assert_eq!(value.get("empty"), Some(&Value::Object(vec![])));
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn parse_round_trip_ok() {
+        let value = Value::Object(vec![
+            (
+                String::from("name"),
+                Value::String(String::from("a\"b\\c\n\u{1}")),
+            ),
+            (String::from("list"), Value::Array(vec![Value::Number(42)])),
+        ]);
+        assert_eq!(parse(&value.to_json()).unwrap(), value);
+    }
+
+    #[test]
+    fn parse_err() {
+        assert!(parse("{\"id\": 1").is_err());
+        assert!(parse("[1, 2,]").is_err());
+        assert!(parse("{\"id\": -1}").is_err());
+        assert!(parse("{\"id\": 1.5}").is_err());
+        assert!(parse("\"abc").is_err());
+        assert!(parse("[] []").is_err());
+        let message = parse("{\n\"id\": nul}").unwrap_err().to_string();
+        assert_eq!(message, "json::parse : Unexpected character! (line 2)");
+    }
+
+    #[test]
+    fn parse_nesting_err() {
+        let nested = format!("{}{}", "[".repeat(MAX_NESTING), "]".repeat(MAX_NESTING));
+        assert!(parse(&nested).is_ok());
+        let depth = MAX_NESTING + 1;
+        let nested = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let message = parse(&nested).unwrap_err().to_string();
+        assert_eq!(message, "json::parse : Nesting is too deep! (line 1)");
+        assert!(parse(&"[{\"a\":".repeat(200000)).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+// ライブラリとして利用する場合に公開するドメインの型
+// domainモジュール自体は非公開とし、外部のクレートがリストを操作したり、
+// 独自のリポジトリ(hr_app::repositoryのトレイト)を実装したりするのに必要な型だけをここから公開する
+pub use super::domain::audit::AuditEvent;
+pub use super::domain::department::id::DepartmentId;
+pub use super::domain::department::list::DepartmentList;
+pub use super::domain::department::Department;
+pub use super::domain::member::MemberList;
+pub use super::domain::person::id::PersonId;
+pub use super::domain::person::list::PersonList;
+pub use super::domain::person::name::{NameOrder, PersonName};
+pub use super::domain::person::Person;
+pub use super::domain::search::MatchLevel;
+pub use super::domain::timestamp::Timestamp;
@@ -0,0 +1,6 @@
+use super::super::domain::audit::AuditEvent;
+use std::error::Error;
+
+pub trait AuditRepository {
+    fn append_event(&self, event: &AuditEvent) -> Result<(), Box<dyn Error>>;
+}
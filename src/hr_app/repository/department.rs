@@ -7,4 +7,11 @@ pub trait DepartmentRepository {
         &self,
         department_list: &mut DepartmentList,
     ) -> Result<(), Box<dyn Error>>;
+
+    // 保存先の現在の内容を表す値
+    // 読み込んだ後に他のプログラムが保存先を変更したことの検出に使用する
+    // 検出に対応しない保存先はNoneを返す
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(None);
+    }
 }
@@ -11,4 +11,11 @@ pub trait MemberRepository {
         department_list: &DepartmentList,
     ) -> Result<(), Box<dyn Error>>;
     fn overwrite_all_data(&self, member_list: &mut MemberList) -> Result<(), Box<dyn Error>>;
+
+    // 保存先の現在の内容を表す値
+    // 読み込んだ後に他のプログラムが保存先を変更したことの検出に使用する
+    // 検出に対応しない保存先はNoneを返す
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(None);
+    }
 }
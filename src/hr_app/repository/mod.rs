@@ -1,3 +1,16 @@
+pub mod audit;
 pub mod department;
 pub mod member;
 pub mod person;
+
+use department::DepartmentRepository;
+use member::MemberRepository;
+use person::PersonRepository;
+
+// 保存形式ごとの個人、部署、所属のリポジトリの組
+// 保存形式を実行時に選ぶ場合や、保存形式の間でデータを移す場合に使用する
+pub struct Repositories {
+    pub person: Box<dyn PersonRepository>,
+    pub department: Box<dyn DepartmentRepository>,
+    pub member: Box<dyn MemberRepository>,
+}
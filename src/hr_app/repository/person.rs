@@ -4,4 +4,11 @@ use std::error::Error;
 pub trait PersonRepository {
     fn read_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>>;
     fn overwrite_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>>;
+
+    // 保存先の現在の内容を表す値
+    // 読み込んだ後に他のプログラムが保存先を変更したことの検出に使用する
+    // 検出に対応しない保存先はNoneを返す
+    fn fingerprint(&self) -> Result<Option<u64>, Box<dyn Error>> {
+        return Ok(None);
+    }
 }
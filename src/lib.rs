@@ -2,5 +2,7 @@ pub mod hr_app {
     pub mod application;
     pub mod datasource;
     mod domain;
-    mod repository;
+    mod format;
+    pub mod model;
+    pub mod repository;
 }
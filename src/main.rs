@@ -1,26 +1,339 @@
 extern crate human_management;
 
+use human_management::hr_app::application::check::{self, DataFile, Files};
+use human_management::hr_app::application::message::{Language, Message};
+use human_management::hr_app::application::migrate;
+use human_management::hr_app::application::repl::Repl;
+use human_management::hr_app::application::server;
 use human_management::hr_app::application::service;
+use human_management::hr_app::application::HrService;
+use human_management::hr_app::datasource::audit::TextFileAudit;
 use human_management::hr_app::datasource::department::TextFileDepartment;
 use human_management::hr_app::datasource::member::TextFileMember;
 use human_management::hr_app::datasource::person::TextFilePerson;
+use human_management::hr_app::datasource::{self, DirectoryLock, Storage};
+use std::env;
+use std::error::Error;
+use std::fs;
+
+// serveでポート番号を省略した時に待ち受けるポート
+const DEFAULT_PORT: u16 = 8080;
+
+// 値を取るオプション(値は次の引数)
+const OPTIONS_WITH_VALUE: [&str; 5] = ["--lang", "--storage", "--port", "--to", "--from"];
+
+// オプションとその値を除いた最初の引数をサブコマンドとして返す
+// "--storage json check" のようにオプションを先に書いた場合もcheckとなる
+fn subcommand(args: &[String]) -> Option<&str> {
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        if OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+            rest.next();
+            continue;
+        }
+        if arg.starts_with("--") {
+            continue;
+        }
+        return Some(arg.as_str());
+    }
+    return None;
+}
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    let language = match Language::detect(&args) {
+        Ok(language) => language,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
     let dir_path = "./src/hr_app/datasource/text/";
-    let person_datasouace = TextFilePerson::new(dir_path);
-    let department_datasource = TextFileDepartment::new(dir_path);
-    let member_datasource = TextFileMember::new(dir_path);
+    // データを変更するセッションは他のセッションと同時に実行しない
+    // 読み取り専用のセッションと修復しないcheckは共有ロックを取得し、互いには同時に実行できる
+    let read_only = args.iter().any(|arg| arg == "--read-only");
+    let subcommand = subcommand(&args);
+    let check_only = subcommand == Some("check") && !args.iter().any(|arg| arg == "--repair");
+    let _lock = if read_only || check_only {
+        match DirectoryLock::acquire_shared(dir_path) {
+            Ok(lock) => lock,
+            Err(_) => {
+                eprintln!("{}", language.text(Message::DataLockedForReading));
+                return;
+            }
+        }
+    } else {
+        match DirectoryLock::acquire(dir_path) {
+            Ok(lock) => lock,
+            Err(_) => {
+                eprintln!("{}", language.text(Message::DataLocked));
+                return;
+            }
+        }
+    };
+    if subcommand == Some("migrate") {
+        if read_only {
+            eprintln!("{}", language.text(Message::ReadOnlySession));
+            return;
+        }
+        if let Err(e) = run_migration(&args, dir_path, language) {
+            eprintln!(
+                "{}: {}",
+                language.text(Message::ApplicationError),
+                language.translate_error(&e.to_string())
+            );
+        }
+        return;
+    }
+    let storage = match Storage::detect(&args) {
+        Ok(storage) => storage,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    if subcommand == Some("check") {
+        if storage != Storage::Text {
+            println!("{}", language.text(Message::CheckTextOnly));
+            return;
+        }
+        let repair = args.iter().any(|arg| arg == "--repair");
+        if repair && read_only {
+            eprintln!("{}", language.text(Message::ReadOnlySession));
+            return;
+        }
+        if let Err(e) = run_check(repair, dir_path, language) {
+            eprintln!(
+                "{}: {}",
+                language.text(Message::ApplicationError),
+                language.translate_error(&e.to_string())
+            );
+        }
+        return;
+    }
+    let audit_datasource = Box::new(TextFileAudit::new(dir_path));
+    let mut service =
+        match HrService::new(storage.repositories(dir_path), audit_datasource, read_only) {
+            Ok(service) => service,
+            Err(e) => {
+                eprintln!("{}: {}", language.text(Message::ApplicationError), e);
+                return;
+            }
+        };
+    if subcommand == Some("serve") {
+        if let Err(e) = run_server(&args, &mut service, language) {
+            eprintln!(
+                "{}: {}",
+                language.text(Message::ApplicationError),
+                language.translate_error(&e.to_string())
+            );
+        }
+        return;
+    }
+    start(&mut service, dir_path, language);
+}
+
+// 選択した保存形式のデータソースでアプリケーションを実行する
+fn start(service: &mut HrService, dir_path: &str, language: Language) {
+    let mut repl = match Repl::new(dir_path) {
+        Ok(repl) => repl,
+        Err(e) => {
+            eprintln!("{}: {}", language.text(Message::ApplicationError), e);
+            return;
+        }
+    };
     loop {
-        if let Err(e) = service::run(
-            &person_datasouace,
-            &department_datasource,
-            &member_datasource,
-        ) {
-            eprintln!("Application Error: {}", e);
-            continue;
-        } else {
-            break;
+        match service::run(service, &mut repl, language) {
+            Ok(_) => break,
+            Err(e) => {
+                eprintln!(
+                    "{}: {}",
+                    language.text(Message::ApplicationError),
+                    language.translate_error(&e.to_string())
+                );
+                // 途中で失敗したコマンドの影響を残さないように、保存先から読み込み直して続ける
+                if let Err(e) = service.reload() {
+                    eprintln!("{}: {}", language.text(Message::ApplicationError), e);
+                    return;
+                }
+            }
+        }
+    }
+    println!("{}", language.text(Message::ApplicationFinished));
+}
+
+// "serve [--port <番号>]"
+// 他のマシンから接続できないように、ローカルホストでのみ待ち受ける
+fn run_server(
+    args: &[String],
+    service: &mut HrService,
+    language: Language,
+) -> Result<(), Box<dyn Error>> {
+    let port: u16 = match args.iter().position(|arg| arg == "--port") {
+        Some(index) => match args.get(index + 1).map(|port| port.parse()) {
+            Some(Ok(port)) => port,
+            _ => return Err("port must be a number".into()),
+        },
+        None => DEFAULT_PORT,
+    };
+    return server::serve(&format!("127.0.0.1:{}", port), service, language);
+}
+
+// "migrate --to <storage> [--from <storage>]"
+// 移行元と移行先のデータファイルをバックアップしてから移行する
+fn run_migration(
+    args: &[String],
+    dir_path: &str,
+    language: Language,
+) -> Result<(), Box<dyn Error>> {
+    let source = Storage::from_option(args, "--from")?.unwrap_or(Storage::Text);
+    let target = match Storage::from_option(args, "--to")? {
+        Some(target) => target,
+        None => {
+            println!("{}", language.text(Message::MigrationUsage));
+            return Ok(());
+        }
+    };
+    if !source.exists(dir_path) {
+        println!("{}", language.text(Message::MigrationNoData));
+        return Ok(());
+    }
+    let version_text = |version: Option<u64>| match version {
+        Some(version) => version.to_string(),
+        None => String::from("-"),
+    };
+    let source_version = version_text(source.schema_version(dir_path)?);
+    println!(
+        "{}",
+        language.format(
+            Message::MigrationSource,
+            &[&source.to_string(), &source_version]
+        )
+    );
+    let target_version = version_text(target.current_schema_version());
+    println!(
+        "{}",
+        language.format(
+            Message::MigrationTarget,
+            &[&target.to_string(), &target_version]
+        )
+    );
+    let storages = if source == target {
+        vec![source]
+    } else {
+        vec![source, target]
+    };
+    for copied in datasource::backup(dir_path, &storages)? {
+        println!("{}", language.format(Message::BackedUp, &[&copied]));
+    }
+    let summary = migrate::migrate(
+        &source.repositories(dir_path),
+        &target.repositories(dir_path),
+    )?;
+    println!(
+        "{}",
+        language.format(Message::MigrationVerified, &[&summary.to_string()])
+    );
+    return Ok(());
+}
+
+// "check [--repair]"
+// テキストファイルを検査し、--repairの場合はバックアップしてから修復可能な問題を修復する
+fn run_check(repair: bool, dir_path: &str, language: Language) -> Result<(), Box<dyn Error>> {
+    // 部署のファイルは以前の名前のファイルしか無い場合はそちらを検査する
+    let department_file_path = TextFileDepartment::new(dir_path).file_path()?;
+    let file_path = |data_file: DataFile| match data_file {
+        DataFile::Person => format!("{}{}", dir_path, TextFilePerson::FILE_NAME),
+        DataFile::Department => department_file_path.clone(),
+        DataFile::Member => format!("{}{}", dir_path, TextFileMember::FILE_NAME),
+    };
+    // 存在しないファイルは空とみなす
+    let read = |data_file: DataFile| fs::read_to_string(file_path(data_file)).unwrap_or_default();
+    let files = Files {
+        person: read(DataFile::Person),
+        department: read(DataFile::Department),
+        member: read(DataFile::Member),
+    };
+    let result = check::check(&files);
+    for issue in result.issues.iter() {
+        let mut location = file_path(issue.file);
+        if issue.line > 0 {
+            location.push_str(&format!(":{}", issue.line));
         }
+        let description = issue.describe(language);
+        println!(
+            "{}",
+            language.format(Message::CheckIssue, &[&location, &description])
+        );
+    }
+    if result.issues.is_empty() {
+        println!("{}", language.text(Message::CheckNoIssue));
+        return Ok(());
+    }
+    let repairable = result
+        .issues
+        .iter()
+        .filter(|issue| issue.repairable)
+        .count();
+    println!(
+        "{}",
+        language.format(
+            Message::CheckSummary,
+            &[&result.issues.len().to_string(), &repairable.to_string()]
+        )
+    );
+    if repairable == 0 {
+        return Ok(());
+    }
+    if !repair {
+        println!("{}", language.text(Message::CheckRepairHint));
+        return Ok(());
+    }
+    for copied in datasource::backup(dir_path, &[Storage::Text])? {
+        println!("{}", language.format(Message::BackedUp, &[&copied]));
+    }
+    let repaired = [
+        (DataFile::Person, &result.repaired.person),
+        (DataFile::Department, &result.repaired.department),
+        (DataFile::Member, &result.repaired.member),
+    ];
+    for (data_file, contents) in repaired {
+        let changed = result
+            .issues
+            .iter()
+            .any(|issue| issue.file == data_file && issue.repairable);
+        if changed {
+            fs::write(file_path(data_file), contents)?;
+            let path = file_path(data_file);
+            println!("{}", language.format(Message::CheckRepaired, &[&path]));
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        return args.iter().map(|a| a.to_string()).collect();
+    }
+
+    #[test]
+    fn subcommand_ok() {
+        assert_eq!(
+            subcommand(&args(&["hr", "check", "--repair"])),
+            Some("check")
+        );
+        assert_eq!(
+            subcommand(&args(&["hr", "--storage", "json", "serve", "--port", "80"])),
+            Some("serve")
+        );
+        assert_eq!(
+            subcommand(&args(&["hr", "--read-only", "--lang", "ja", "check"])),
+            Some("check")
+        );
+        assert_eq!(subcommand(&args(&["hr", "--storage", "json"])), None);
+        assert_eq!(subcommand(&args(&["hr"])), None);
     }
-    println!("Application finished successfully!");
 }
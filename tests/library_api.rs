@@ -0,0 +1,143 @@
+extern crate human_management;
+
+use human_management::hr_app::application::{HrService, PersonOrder};
+use human_management::hr_app::model::{
+    AuditEvent, Department, DepartmentId, DepartmentList, MemberList, Person, PersonId, PersonList,
+};
+use human_management::hr_app::repository::audit::AuditRepository;
+use human_management::hr_app::repository::department::DepartmentRepository;
+use human_management::hr_app::repository::member::MemberRepository;
+use human_management::hr_app::repository::person::PersonRepository;
+use human_management::hr_app::repository::Repositories;
+use std::cell::RefCell;
+use std::error::Error;
+use std::rc::Rc;
+
+// 公開APIだけを使って、メモリ上に保存するリポジトリを実装する
+#[derive(Default)]
+struct Storage {
+    persons: Vec<(PersonId, Person)>,
+    departments: Vec<(DepartmentId, Department)>,
+    members: Vec<(DepartmentId, Vec<PersonId>)>,
+    events: Vec<String>,
+}
+
+struct MemoryRepository {
+    storage: Rc<RefCell<Storage>>,
+}
+
+impl PersonRepository for MemoryRepository {
+    fn read_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>> {
+        for (id, person) in self.storage.borrow().persons.iter() {
+            person_list.add_person(person.clone(), Some(*id))?;
+        }
+        return Ok(());
+    }
+
+    fn overwrite_all_data(&self, person_list: &mut PersonList) -> Result<(), Box<dyn Error>> {
+        let ids: Vec<PersonId> = person_list.into_iter().map(|(id, _name)| id).collect();
+        let mut persons = Vec::new();
+        for id in ids {
+            persons.push((id, person_list.person(id)?.clone()));
+        }
+        self.storage.borrow_mut().persons = persons;
+        return Ok(());
+    }
+}
+
+impl DepartmentRepository for MemoryRepository {
+    fn read_all_data(&self, department_list: &mut DepartmentList) -> Result<(), Box<dyn Error>> {
+        for (id, department) in self.storage.borrow().departments.iter() {
+            department_list.add_department(department.clone(), Some(*id))?;
+        }
+        return Ok(());
+    }
+
+    fn overwrite_all_data(
+        &self,
+        department_list: &mut DepartmentList,
+    ) -> Result<(), Box<dyn Error>> {
+        let ids: Vec<DepartmentId> = department_list.into_iter().map(|(id, _)| id).collect();
+        let mut departments = Vec::new();
+        for id in ids {
+            departments.push((id, department_list.department(id)?.clone()));
+        }
+        self.storage.borrow_mut().departments = departments;
+        return Ok(());
+    }
+}
+
+impl MemberRepository for MemoryRepository {
+    fn read_all_data(
+        &self,
+        member_list: &mut MemberList,
+        person_list: &PersonList,
+        department_list: &DepartmentList,
+    ) -> Result<(), Box<dyn Error>> {
+        for (department_id, person_ids) in self.storage.borrow().members.iter() {
+            for person_id in person_ids.iter() {
+                member_list.add_person_to_department(
+                    *person_id,
+                    *department_id,
+                    person_list,
+                    department_list,
+                )?;
+            }
+        }
+        return Ok(());
+    }
+
+    fn overwrite_all_data(&self, member_list: &mut MemberList) -> Result<(), Box<dyn Error>> {
+        self.storage.borrow_mut().members = member_list.into_iter().collect();
+        return Ok(());
+    }
+}
+
+impl AuditRepository for MemoryRepository {
+    fn append_event(&self, event: &AuditEvent) -> Result<(), Box<dyn Error>> {
+        let line = format!("{} {}", event.action(), event.detail());
+        self.storage.borrow_mut().events.push(line);
+        return Ok(());
+    }
+}
+
+#[test]
+fn custom_repository_with_hr_service_ok() {
+    let storage = Rc::new(RefCell::new(Storage::default()));
+    let repository = || {
+        Box::new(MemoryRepository {
+            storage: Rc::clone(&storage),
+        })
+    };
+    let repositories = Repositories {
+        person: repository(),
+        department: repository(),
+        member: repository(),
+    };
+    let mut service = HrService::new(repositories, repository(), false).unwrap();
+
+    let harry = service.create_person("Potter", Some("Harry"), &[]).unwrap();
+    let gryffindor = service.create_department("Gryffindor").unwrap();
+    service.add_member(harry.id, gryffindor.id).unwrap();
+    assert_eq!(storage.borrow().persons.len(), 1);
+    assert_eq!(storage.borrow().events.len(), 3);
+
+    // 保存した内容から読み込み直しても同じ結果になる
+    service.reload().unwrap();
+    let members = service.department_members(gryffindor.id).unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].person.person_name().last_name(), "Potter");
+    assert_eq!(service.persons(PersonOrder::Id).unwrap()[0].id, harry.id);
+
+    // リストと所属の照会はHrServiceを使わずに直接行うことも出来る
+    let mut person_list = PersonList::new();
+    let mut department_list = DepartmentList::new();
+    let mut member_list = MemberList::new();
+    let memory = repository();
+    PersonRepository::read_all_data(&*memory, &mut person_list).unwrap();
+    DepartmentRepository::read_all_data(&*memory, &mut department_list).unwrap();
+    MemberRepository::read_all_data(&*memory, &mut member_list, &person_list, &department_list)
+        .unwrap();
+    assert_eq!(member_list.member_ids(gryffindor.id), vec![harry.id]);
+    assert_eq!(member_list.department_ids(harry.id), vec![gryffindor.id]);
+}